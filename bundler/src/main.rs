@@ -31,18 +31,23 @@ fn main() -> Result<()> {
         watcher.watch(Path::new(f), RecursiveMode::NonRecursive)?;
     }
 
+    // Nested modules are named by their path relative to the shallowest file.
+    let root = args
+        .files
+        .iter()
+        .map(|f| Path::new(f).parent().unwrap())
+        .min_by_key(|p| p.components().count())
+        .unwrap_or(Path::new(""));
+
     loop {
         let files = args
             .files
             .iter()
             .map(|f| {
+                let path = Path::new(f);
+                let name = path.strip_prefix(root).unwrap_or(path);
                 (
-                    std::path::Path::new(f)
-                        .file_name()
-                        .unwrap()
-                        .to_str()
-                        .unwrap()
-                        .to_owned(),
+                    name.to_str().unwrap().replace('\\', "/"),
                     std::fs::read_to_string(f).unwrap(),
                 )
             })
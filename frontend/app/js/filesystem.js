@@ -34,3 +34,29 @@ export async function open() {
   }
   return new FileHandle(handle);
 }
+
+const wasmPickerOpts = {
+  types: [
+    {
+      description: "WebAssembly",
+      accept: {
+        "application/wasm": [".wasm"],
+      },
+    },
+  ],
+  excludeAcceptAllOption: true,
+  multiple: false,
+};
+
+// Returns the module base64-encoded with a "WASM:" prefix, which the compiler
+// service passes through after validating it.
+export async function openWasm() {
+  let [handle] = await window.showOpenFilePicker(wasmPickerOpts);
+  let file = await handle.getFile();
+  let bytes = new Uint8Array(await file.arrayBuffer());
+  let binary = "";
+  for (let i = 0; i < bytes.length; i++) {
+    binary += String.fromCharCode(bytes[i]);
+  }
+  return "WASM:" + btoa(binary);
+}
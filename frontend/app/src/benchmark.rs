@@ -71,7 +71,7 @@ impl Component for Benchmark {
             }) => {
                 if snapshot.status == oort_simulator::scenario::Status::Running {
                     self.time = snapshot.time;
-                    if snapshot.timing.total() > oort_simulator::simulation::PHYSICS_TICK_LENGTH {
+                    if snapshot.timing.total() > snapshot.tick_length {
                         self.num_slow_ticks += BATCH_SIZE;
                     }
                     if self.slowest_snapshot.is_none()
@@ -77,6 +77,7 @@ pub fn documentation(props: &DocumentationProps) -> Html {
               <li><code>{ "angular_velocity() → f64" }</code>{ ": Get the current angular velocity in radians/s." }</li>
               <li><code>{ "health() → f64" }</code>{ ": Current health." }</li>
               <li><code>{ "fuel() → f64" }</code>{ ": Current fuel (delta-v)." }</li>
+              <li><code>{ "energy() → f64" }</code>{ ": Current energy, which guns, radar and abilities may draw from in some scenarios. Infinite if the ship doesn't use energy." }</li>
               <li><code>{ "accelerate(acceleration: Vec2)" }</code>{ ": Accelerate the ship. Units are m/s²." }</li>
               <li><code>{ "turn(speed: f64)" }</code>{ ": Rotate the ship. Unit is radians/s." }</li>
               <li><code>{ "torque(acceleration: f64)" }</code>{ ": Angular acceleration. Unit is radians/s²." }</li>
@@ -112,6 +113,8 @@ pub fn documentation(props: &DocumentationProps) -> Html {
               <li><code>{ "set_radar_ecm_mode(mode: EcmMode)" }</code>{ ": Set the Electronic Counter Measures (ECM) mode." }</li>
               <li><code>{ "EcmMode::None" }</code>{ ": No ECM, radar will operate normally." }</li>
               <li><code>{ "EcmMode::Noise" }</code>{ ": Decrease the enemy radar's signal to noise ratio, making it more difficult to detect targets and reducing accuracy of returned contacts." }</li>
+              <li><code>{ "radar_warnings() → Vec<RadarWarning>" }</code>{ ": Get the enemy radars pointed at this ship, strongest first. Enemy radars can be detected from much further away than they can detect you." }</li>
+              <li><code>{ "struct RadarWarning { bearing: f64, rssi: f64 }" }</code></li>
             </ul>
 
             <h2>{ "Radio" }</h2>
@@ -135,6 +138,9 @@ pub fn documentation(props: &DocumentationProps) -> Html {
                   <li><code>{ "Ability::Boost" }</code>{ ": Fighter and missile only. Applies a 100 m/s² forward acceleration for 2s. Reloads in 10s." }</li>
                   <li><code>{ "Ability::Decoy" }</code>{ ": Torpedo only. Mimics the radar signature of a Cruiser for 0.5s. Reloads in 10s." }</li>
                   <li><code>{ "Ability::Shield" }</code>{ ": Cruiser only. Deflects damage for 1s. Reloads in 5s." }</li>
+                  <li><code>{ "Ability::Cloak" }</code>{ ": Only in some scenarios. Reduces the radar cross section by 95% for 5s. Reloads in 20s." }</li>
+                  <li><code>{ "Ability::PointDefense" }</code>{ ": Only in some scenarios. Destroys the closest enemy missile or torpedo within 1 km every tick for 1s. Reloads in 4s." }</li>
+                  <li><code>{ "Ability::TractorBeam" }</code>{ ": Only in some scenarios. Pulls the closest ship within 2 km inside the radar beam towards this ship for 5s. Reloads in 10s." }</li>
                 </ul>
               </li>
             </ul>
@@ -130,6 +130,19 @@ impl Component for EditorWindow {
                 self.set_read_only(false);
                 false
             }
+            Msg::EditorAction(ref action) if action == "oort-upload-wasm" => {
+                let text_cb = context.link().callback(Msg::LoadedCodeFromDisk);
+                wasm_bindgen_futures::spawn_local(async move {
+                    match js::filesystem::open_wasm().await {
+                        Ok(text) => text_cb.emit(text.as_string().unwrap()),
+                        Err(e) => log::error!("upload failed: {:?}", e),
+                    }
+                });
+                self.file_handle = None;
+                self.linked = false;
+                self.set_read_only(false);
+                false
+            }
             Msg::EditorAction(ref action) if action == "oort-reload-file" => {
                 if let Some(file_handle) = self.file_handle.clone() {
                     let cb = context.link().callback(Msg::LoadedCodeFromDisk);
@@ -440,10 +453,30 @@ impl Component for EditorWindow {
 
                 add_action("oort-restore-initial-code", "Restore initial code", None);
 
+                add_action(
+                    "oort-restore-initial-code-c",
+                    "Restore initial code (C)",
+                    None,
+                );
+
+                add_action(
+                    "oort-restore-initial-code-cpp",
+                    "Restore initial code (C++)",
+                    None,
+                );
+
+                add_action(
+                    "oort-restore-initial-code-zig",
+                    "Restore initial code (Zig)",
+                    None,
+                );
+
                 add_action("oort-load-solution", "Load solution", None);
 
                 add_action("oort-load-file", "Load from a file", None);
 
+                add_action("oort-upload-wasm", "Upload a precompiled WASM module", None);
+
                 add_action(
                     "oort-reload-file",
                     "Reload from file",
@@ -15,16 +15,14 @@ use crate::userid;
 use crate::versions_window::VersionsWindow;
 use crate::welcome::Welcome;
 use monaco::yew::CodeEditorLink;
-use oort_proto::{LeaderboardSubmission, Telemetry};
+use oort_proto::{CompilerDiagnostics, LeaderboardSubmission, Severity, Telemetry};
 use oort_simulation_worker::SimAgent;
 use oort_simulator::scenario::{self, Status, MAX_TICKS};
-use oort_simulator::simulation;
 use oort_simulator::simulation::Code;
 use oort_simulator::snapshot::Snapshot;
 use rand::Rng;
 use regex::Regex;
 use reqwasm::http::Request;
-use simulation::PHYSICS_TICK_LENGTH;
 use std::collections::HashMap;
 use std::rc::Rc;
 use wasm_bindgen::prelude::Closure;
@@ -52,7 +50,7 @@ pub enum Msg {
     EditorAction { team: usize, action: String },
     ShowFeedback,
     DismissOverlay,
-    CompileFinished(Vec<Result<Code, String>>, ExecutionMode),
+    CompileFinished(Vec<Result<Code, CompilerDiagnostics>>, ExecutionMode),
     SubmitToTournament,
     UploadShortcode,
     FormattedCode { team: usize, text: String },
@@ -239,6 +237,28 @@ impl Component for Game {
                 self.team(team).set_editor_text(&code_to_string(&code));
                 false
             }
+            Msg::EditorAction { team, ref action }
+                if action.starts_with("oort-restore-initial-code-") =>
+            {
+                let extension = action.trim_start_matches("oort-restore-initial-code-");
+                let name = match scenario::load(&context.props().scenario)
+                    .initial_code()
+                    .get(team)
+                {
+                    Some(Code::Builtin(name)) => name.clone(),
+                    _ => "empty".to_string(),
+                };
+                let code =
+                    oort_simulator::vm::builtin::load_source_with_extension(&name, extension)
+                        .or_else(|_| {
+                            oort_simulator::vm::builtin::load_source_with_extension(
+                                "empty", extension,
+                            )
+                        })
+                        .unwrap();
+                self.team(team).set_editor_text(&code_to_string(&code));
+                false
+            }
             Msg::EditorAction { team, ref action } if action == "oort-load-solution" => {
                 let mut code = scenario::load(&context.props().scenario).solution();
                 if let Code::Builtin(name) = code {
@@ -284,7 +304,7 @@ impl Component for Game {
             ) => {
                 if snapshot.nonce == self.background_nonce {
                     if snapshot.status == Status::Running
-                        && snapshot.time < (MAX_TICKS as f64 * PHYSICS_TICK_LENGTH)
+                        && snapshot.time < (MAX_TICKS as f64 * snapshot.tick_length)
                     {
                         if !self.background_agents.is_empty() {
                             self.background_agents[seed as usize].send(
@@ -296,6 +316,7 @@ impl Component for Game {
                         }
                         false
                     } else {
+                        let tick_length = snapshot.tick_length;
                         self.background_snapshots.push((seed, snapshot));
                         if let Some(summary) =
                             self.summarize_background_simulations(&context.props().scenario)
@@ -304,10 +325,8 @@ impl Component for Game {
                             services::send_telemetry(Telemetry::FinishScenario {
                                 scenario_name: context.props().scenario.clone(),
                                 code: code_to_string(&code),
-                                ticks: (summary.average_time.unwrap_or(0.0)
-                                    / simulation::PHYSICS_TICK_LENGTH)
-                                    as u32,
-                                code_size: crate::code_size::calculate(&code_to_string(&code)),
+                                ticks: (summary.average_time.unwrap_or(0.0) / tick_length) as u32,
+                                code_size: oort_code_size::calculate(&code_to_string(&code)),
                                 success: summary.failed_seeds.is_empty(),
                                 time: summary.average_time,
                             });
@@ -360,8 +379,9 @@ impl Component for Game {
                                 .insert(self.team(team).running_source_code.clone(), code.clone());
                         }
                         Err(error) => {
+                            let source = code_to_string(&self.team(team).running_source_code);
                             self.team_mut(team)
-                                .display_compiler_errors(&make_editor_errors(error));
+                                .display_compiler_errors(&make_editor_errors(&source, error));
                             self.team_mut(team).running_compiled_code = Code::None;
                             teams_with_errors.push(team);
                         }
@@ -370,7 +390,7 @@ impl Component for Game {
                 let errors: Vec<_> = results
                     .iter()
                     .filter_map(|x| x.as_ref().err())
-                    .cloned()
+                    .map(|x| x.rendered.clone())
                     .collect();
                 if errors.is_empty() {
                     services::send_telemetry(Telemetry::StartScenario {
@@ -657,6 +677,7 @@ struct BackgroundSimSummary {
     victory_count: usize,
     failed_seeds: Vec<u32>,
     average_time: Option<f64>,
+    average_cpu: Option<f64>,
     best_seed: Option<u32>,
     worst_seed: Option<u32>,
     scenario_name: String,
@@ -830,13 +851,28 @@ impl Game {
             None
         };
 
+        let victory_cpu: Vec<f64> = self
+            .background_snapshots
+            .iter()
+            .filter(|(_, snapshot)| is_victory(&snapshot.status))
+            .filter_map(|(_, snapshot)| snapshot.cpu_usage.get(&0).copied())
+            .collect();
+        let average_cpu: Option<f64> = if !victory_cpu.is_empty() {
+            Some(victory_cpu.iter().sum::<f64>() / victory_cpu.len() as f64)
+        } else {
+            None
+        };
+
         let mut victory_seeds_by_time: Vec<_> = self
             .background_snapshots
             .iter()
             .filter(|(_, snapshot)| is_victory(&snapshot.status))
-            .map(|(seed, snapshot)| (*seed, snapshot.score_time))
+            .map(|(seed, snapshot)| {
+                let ticks = (snapshot.score_time / snapshot.tick_length) as i64;
+                (*seed, ticks)
+            })
             .collect();
-        victory_seeds_by_time.sort_by_key(|(_, time)| (time / PHYSICS_TICK_LENGTH) as i64);
+        victory_seeds_by_time.sort_by_key(|(_, ticks)| *ticks);
         let best_seed = victory_seeds_by_time.first().map(|(seed, _)| *seed);
         let mut worst_seed = victory_seeds_by_time.last().map(|(seed, _)| *seed);
         if worst_seed == best_seed {
@@ -848,6 +884,7 @@ impl Game {
             victory_count,
             failed_seeds,
             average_time,
+            average_cpu,
             best_seed,
             worst_seed,
             scenario_name: scenario_name.to_owned(),
@@ -861,7 +898,7 @@ impl Game {
             0.0
         };
         let source_code = code_to_string(&self.player_team().running_source_code);
-        let code_size = crate::code_size::calculate(&source_code);
+        let code_size = oort_code_size::calculate(&source_code);
         let leaderboard_eligible = self.leaderboard_eligible();
 
         let next_scenario = scenario::load(&context.props().scenario).next_scenario();
@@ -996,6 +1033,8 @@ impl Game {
                     code: source_code.clone(),
                     code_size,
                     time: summary.average_time.unwrap(),
+                    cpu_gas: summary.average_cpu,
+                    verification: None,
                 });
             html! {
                 <>
@@ -1047,31 +1086,34 @@ impl Game {
             .link()
             .callback(move |results| Msg::CompileFinished(results, execution_mode));
 
-        async fn compile(text: String) -> Result<Code, String> {
+        async fn compile(text: String) -> Result<Code, CompilerDiagnostics> {
             if text.trim().is_empty() {
                 return Ok(Code::None);
             }
 
             let start_time = instant::Instant::now();
 
-            let url = format!("{}/compile", services::compiler_url());
+            let url = format!("{}/compile?format=json", services::compiler_url());
             let result = Request::post(&url).body(text).send().await;
             if let Err(e) = result {
                 log::error!("Compile error: {}", e);
-                return Err(e.to_string());
+                return Err(plain_diagnostics(e.to_string()));
             }
 
             let response = result.unwrap();
             if !response.ok() {
                 let error = response.text().await.unwrap();
                 log::error!("Compile error: {}", error);
-                return Err(error);
+                // Errors other than rustc's (e.g. the sanitizer) are plain text.
+                return Err(
+                    serde_json::from_str(&error).unwrap_or_else(|_| plain_diagnostics(error))
+                );
             }
 
             let wasm = response.binary().await;
             if let Err(e) = wasm {
                 log::error!("Compile error: {}", e);
-                return Err(e.to_string());
+                return Err(plain_diagnostics(e.to_string()));
             }
 
             let elapsed = instant::Instant::now() - start_time;
@@ -1332,7 +1374,13 @@ impl Team {
             .map(|error| {
                 let decoration: IModelDeltaDecoration = empty().into();
                 decoration.set_range(
-                    &Range::new(error.line as f64, 1.0, error.line as f64, 1.0).unchecked_into(),
+                    &Range::new(
+                        error.line as f64,
+                        error.column as f64,
+                        error.end_line as f64,
+                        error.end_column as f64,
+                    )
+                    .unchecked_into(),
                 );
                 let options: IModelDecorationOptions = empty().into();
                 options.set_is_whole_line(Some(true));
@@ -1387,19 +1435,41 @@ pub fn str_to_code(s: &str) -> Code {
 #[derive(Debug, Clone)]
 pub struct CompilerError {
     pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
     pub msg: String,
 }
 
-fn make_editor_errors(error: &str) -> Vec<CompilerError> {
-    let re = Regex::new(r"(?m)error.*?: (.*?)$\n.*?ai/src/user.rs:(\d+):").unwrap();
-    re.captures_iter(error)
-        .map(|m| CompilerError {
-            line: m[2].parse().unwrap(),
-            msg: m[1].to_string(),
+/// Converts diagnostics, whose lines are relative to each module's file, to
+/// errors on the joined source shown in the editor.
+fn make_editor_errors(source: &str, diagnostics: &CompilerDiagnostics) -> Vec<CompilerError> {
+    diagnostics
+        .diagnostics
+        .iter()
+        .filter(|x| x.severity == Severity::Error)
+        .filter_map(|x| {
+            let file = (x.file != "user.rs").then_some(x.file.as_str());
+            let line = oort_multifile::joined_line(source, file, x.line)?;
+            let end_line = oort_multifile::joined_line(source, file, x.end_line).unwrap_or(line);
+            Some(CompilerError {
+                line,
+                column: x.column,
+                end_line,
+                end_column: x.end_column,
+                msg: x.message.clone(),
+            })
         })
         .collect()
 }
 
+fn plain_diagnostics(rendered: String) -> CompilerDiagnostics {
+    CompilerDiagnostics {
+        rendered,
+        diagnostics: vec![],
+    }
+}
+
 pub(crate) fn is_encrypted(code: &Code) -> bool {
     match code {
         Code::Rust(src) => src.starts_with("ENCRYPTED:"),
@@ -16,6 +16,9 @@ pub mod filesystem {
 
         #[wasm_bindgen(catch)]
         pub async fn open() -> Result<JsValue, JsValue>;
+
+        #[wasm_bindgen(catch, js_name = openWasm)]
+        pub async fn open_wasm() -> Result<JsValue, JsValue>;
     }
 }
 
@@ -1,7 +1,7 @@
 use crate::services;
 use crate::userid;
+use oort_proto::LeaderboardData;
 use oort_proto::LeaderboardSubmission;
-use oort_proto::{LeaderboardData, TimeLeaderboardRow};
 use oort_simulator::scenario;
 use yew::prelude::*;
 
@@ -9,6 +9,68 @@ use yew::prelude::*;
 pub enum Msg {
     SendRequest,
     ReceiveResponse(Result<LeaderboardData, anyhow::Error>),
+    SelectCategory(Category),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Time,
+    CodeSize,
+    Cpu,
+}
+
+impl Category {
+    const ALL: [Category; 3] = [Category::Time, Category::CodeSize, Category::Cpu];
+
+    fn title(self) -> &'static str {
+        match self {
+            Category::Time => "Time",
+            Category::CodeSize => "Code Size",
+            Category::Cpu => "CPU",
+        }
+    }
+}
+
+struct Row {
+    userid: String,
+    username: Option<String>,
+    value: String,
+    shortcode: Option<String>,
+}
+
+fn rows(data: &LeaderboardData, category: Category) -> Vec<Row> {
+    match category {
+        Category::Time => data
+            .lowest_time
+            .iter()
+            .map(|row| Row {
+                userid: row.userid.clone(),
+                username: row.username.clone(),
+                value: row.time.clone(),
+                shortcode: row.shortcode.clone(),
+            })
+            .collect(),
+        Category::CodeSize => data
+            .smallest_code
+            .iter()
+            .map(|row| Row {
+                userid: row.userid.clone(),
+                username: row.username.clone(),
+                value: format!("{} bytes", row.code_size),
+                shortcode: row.shortcode.clone(),
+            })
+            .collect(),
+        Category::Cpu => data
+            .lowest_cpu
+            .iter()
+            .map(|row| Row {
+                userid: row.userid.clone(),
+                username: row.username.clone(),
+                value: format!("{:.0} gas", row.cpu_gas),
+                shortcode: row.shortcode.clone(),
+            })
+            .collect(),
+    }
 }
 
 #[derive(Properties, Clone, PartialEq)]
@@ -22,6 +84,7 @@ pub struct Leaderboard {
     data: Option<LeaderboardData>,
     error: Option<String>,
     fetching: bool,
+    category: Category,
 }
 
 impl Component for Leaderboard {
@@ -34,6 +97,7 @@ impl Component for Leaderboard {
             data: None,
             error: None,
             fetching: false,
+            category: Category::Time,
         }
     }
 
@@ -66,6 +130,10 @@ impl Component for Leaderboard {
                 self.fetching = false;
                 true
             }
+            SelectCategory(category) => {
+                self.category = category;
+                true
+            }
         }
     }
 
@@ -79,7 +147,7 @@ impl Component for Leaderboard {
             let is_tournament = scenario::load_safe(&context.props().scenario_name)
                 .map(|scenario| scenario.is_tournament())
                 .unwrap_or(false);
-            let render_time_row = |rank: usize, row: &TimeLeaderboardRow| -> Html {
+            let render_row = |rank: usize, row: &Row| -> Html {
                 let class = (row.userid == userid).then_some("own-leaderboard-entry");
                 let shortcode = row
                     .shortcode
@@ -96,7 +164,7 @@ impl Component for Leaderboard {
                     <tr class={classes!(class)}>
                         <td class="centered"><b>{ rank }</b></td>
                         <td>{ row.username.clone().unwrap_or_else(|| userid::generate_username(&row.userid)) }</td>
-                        <td>{ &row.time }</td>
+                        <td>{ &row.value }</td>
                         <td>
                             <a title="Play As" class="material-symbols-outlined" onclick={make_play_cb(0)}>{ "play_arrow" }</a>
                             { if is_tournament { html! { <>
@@ -110,15 +178,15 @@ impl Component for Leaderboard {
                 }
             };
 
-            let own_row_index = data
-                .lowest_time
+            let category_rows = rows(data, self.category);
+            let own_row_index = category_rows
                 .iter()
                 .position(|row| row.userid == userid)
                 .unwrap_or(std::usize::MAX - 1);
 
             let mut table_rows = vec![];
             let mut last_index = None;
-            for (i, row) in data.lowest_time.iter().enumerate() {
+            for (i, row) in category_rows.iter().enumerate() {
                 let rank = i + 1;
                 let add_entry = i < 10
                     || i + 1 == own_row_index
@@ -131,22 +199,44 @@ impl Component for Leaderboard {
                             table_rows.push(html! { <tr><td colspan=4 class="skip">{ "skipped " }{ skipped }{ " rows" }</td></tr> });
                         }
                     }
-                    table_rows.push(render_time_row(rank, row));
+                    table_rows.push(render_row(rank, row));
                     last_index = Some(i);
                 }
             }
             if let Some(last_index) = last_index {
-                if last_index + 1 != data.lowest_time.len() {
-                    let skipped = data.lowest_time.len() - (last_index + 1);
+                if last_index + 1 != category_rows.len() {
+                    let skipped = category_rows.len() - (last_index + 1);
                     table_rows.push(html! { <tr><td colspan=4 class="skip">{ "skipped " }{ skipped }{ " rows" }</td></tr> });
                 }
             }
 
+            let category_links = Category::ALL
+                .iter()
+                .map(|&category| {
+                    let onclick = context
+                        .link()
+                        .callback(move |_| Msg::SelectCategory(category));
+                    if category == self.category {
+                        html! { <>{ " " }<b>{ category.title() }</b></> }
+                    } else {
+                        html! { <>{ " " }<a href="#" {onclick}>{ category.title() }</a></> }
+                    }
+                })
+                .collect::<Html>();
+
+            let verification_note = if context.props().submission.is_some() {
+                html! { <p>{ "Your submission will appear once the server has verified it." }</p> }
+            } else {
+                html! {}
+            };
+
             html! {
                 <div class="leaderboard">
+                    { verification_note }
                     <table>
                         <tr><th colspan=4>{ "Leaderboard" }</th></tr>
-                        <tr><th>{ "Rank" }</th><th>{ "User" }</th><th>{ "Time" }</th><th>{ "Play" }</th></tr>
+                        <tr><td colspan=4 class="centered">{ category_links }</td></tr>
+                        <tr><th>{ "Rank" }</th><th>{ "User" }</th><th>{ self.category.title() }</th><th>{ "Play" }</th></tr>
                         <tbody>{ for table_rows }</tbody>
                     </table>
                 </div>
@@ -1,6 +1,5 @@
 mod analyzer_stub;
 pub mod benchmark;
-pub mod code_size;
 pub mod codestorage;
 pub mod compiler_output_window;
 pub mod documentation;
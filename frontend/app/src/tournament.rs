@@ -1,5 +1,5 @@
 use crate::services;
-use oort_proto::{TournamentCompetitor, TournamentResults};
+use oort_proto::{TournamentCompetitor, TournamentFormat, TournamentResults};
 use yew::prelude::*;
 
 #[derive(Debug)]
@@ -74,6 +74,7 @@ impl Component for Tournament {
                 <div id="tournament_results">
                     <h1>{ "Tournament Results" }</h1>
                     <p>{ "Scenario: " }{ data.scenario_name.clone() }</p>
+                    <p>{ "Format: " }{ format_name(data.format) }</p>
                     <p>
                         { "Ratings are calculated with " }
                         <a href="https://en.wikipedia.org/wiki/Glicko_rating_system">{ "Glicko-2" }</a>
@@ -81,11 +82,15 @@ impl Component for Tournament {
                     </p>
                     { make_ratings_table(data) }
                     <br />
-                    <p>
-                        { "This table shows the win percentage of the user in the row vs the user in the column. " }
-                        { "Click a cell to run those AIs against each other." }
-                    </p>
-                    { make_win_matrix_table(data) }
+                    if data.format == TournamentFormat::RoundRobin {
+                        <p>
+                            { "This table shows the win percentage of the user in the row vs the user in the column. " }
+                            { "Click a cell to run those AIs against each other." }
+                        </p>
+                        { make_win_matrix_table(data) }
+                    } else {
+                        { make_rounds(data) }
+                    }
                 </div>
             }
         } else {
@@ -97,22 +102,98 @@ impl Component for Tournament {
 fn make_ratings_table(data: &TournamentResults) -> Html {
     let make_link =
         |shortcode: &str| format!("/scenario/{}?player1={}", data.scenario_name, shortcode);
+    let has_points = data.competitors.iter().any(|x| x.points.is_some());
     html! {
         <table>
             <tr>
                 <th>{ "Username" }</th>
                 <th>{ "Rating" }</th>
+                if has_points {
+                    <th>{ "Points" }</th>
+                }
             </tr>
             { data.competitors.iter().map(|x| html! {
                 <tr>
                     <td><a href={make_link(&x.shortcode)}>{ x.username.clone() }</a></td>
                     <td>{ x.rating.round() }</td>
+                    if has_points {
+                        <td>{ x.points.unwrap_or_default() }</td>
+                    }
                 </tr>
             }).collect::<Html>() }
         </table>
     }
 }
 
+fn format_name(format: TournamentFormat) -> &'static str {
+    match format {
+        TournamentFormat::RoundRobin => "Round robin",
+        TournamentFormat::Swiss => "Swiss",
+        TournamentFormat::SingleElimination => "Single elimination",
+        TournamentFormat::DoubleElimination => "Double elimination",
+    }
+}
+
+fn make_rounds(data: &TournamentResults) -> Html {
+    let shortcode = |username: &str| {
+        data.competitors
+            .iter()
+            .find(|x| x.username == username)
+            .map(|x| x.shortcode.clone())
+            .unwrap_or_default()
+    };
+    let make_link = |username0: &str, username1: &str| {
+        format!(
+            "/scenario/{}?player0={}&player1={}",
+            data.scenario_name,
+            shortcode(username0),
+            shortcode(username1)
+        )
+    };
+    let bold = |username: &str, winner: &Option<String>| {
+        if winner.as_deref() == Some(username) {
+            html! { <b>{ username.to_string() }</b> }
+        } else {
+            html! { <>{ username.to_string() }</> }
+        }
+    };
+    html! {
+        <>
+            <p>{ "Click a result to run those AIs against each other." }</p>
+            { data.rounds.iter().map(|round| html! {
+                <>
+                    <h2>{ round.name.clone() }</h2>
+                    <table>
+                        <tr>
+                            <th>{ "Player 0" }</th>
+                            <th>{ "Result" }</th>
+                            <th>{ "Player 1" }</th>
+                        </tr>
+                        { round.matches.iter().map(|m| match &m.username1 {
+                            Some(username1) => html! {
+                                <tr>
+                                    <td>{ bold(&m.username0, &m.winner) }</td>
+                                    <td><a href={make_link(&m.username0, username1)}>
+                                        { format!("{}-{}-{}", m.wins0, m.draws, m.wins1) }
+                                    </a></td>
+                                    <td>{ bold(username1, &m.winner) }</td>
+                                </tr>
+                            },
+                            None => html! {
+                                <tr>
+                                    <td>{ m.username0.clone() }</td>
+                                    <td>{ "bye" }</td>
+                                    <td></td>
+                                </tr>
+                            },
+                        }).collect::<Html>() }
+                    </table>
+                </>
+            }).collect::<Html>() }
+        </>
+    }
+}
+
 fn make_win_matrix_table(data: &TournamentResults) -> Html {
     let make_link = |c0: &TournamentCompetitor, c1: &TournamentCompetitor| {
         format!(
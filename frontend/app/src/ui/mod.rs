@@ -7,7 +7,7 @@ use nalgebra::{point, vector, Point2, Vector2};
 use oort_renderer::Renderer;
 use oort_simulator::model;
 use oort_simulator::scenario::Status;
-use oort_simulator::simulation::{self, PHYSICS_TICK_LENGTH};
+use oort_simulator::simulation::PHYSICS_TICK_LENGTH;
 use oort_simulator::snapshot::{self, ShipSnapshot, Snapshot};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::time::Duration;
@@ -254,7 +254,12 @@ impl UI {
                 || self.slowmo
                 || self.snapshot.is_none())
         {
-            let dt = std::time::Duration::from_secs_f64(simulation::PHYSICS_TICK_LENGTH);
+            let tick_length = self
+                .snapshot
+                .as_ref()
+                .or(self.pending_snapshots.front())
+                .map_or(PHYSICS_TICK_LENGTH, |snapshot| snapshot.tick_length);
+            let dt = std::time::Duration::from_secs_f64(tick_length);
             if fast_forward {
                 for _ in 0..10 {
                     self.physics_time += dt;
@@ -320,6 +325,17 @@ impl UI {
             _ => {}
         }
 
+        if let Some(snapshot) = self.snapshot.as_ref() {
+            if !snapshot.scores.is_empty() {
+                let scores: Vec<String> = snapshot
+                    .scores
+                    .values()
+                    .map(|score| format!("{:.0}", score.floor()))
+                    .collect();
+                status_msgs.push(format!("SCORE {}", scores.join("-")));
+            }
+        }
+
         if self.pending_snapshots.len() <= 1 && !fast_forward {
             status_msgs.push("SLOW SIM".to_owned());
         }
@@ -329,7 +345,7 @@ impl UI {
             if let Some(snapshot) = self.snapshot.as_ref() {
                 status_msgs.push(format!(
                     "TICK {}",
-                    (snapshot.time / PHYSICS_TICK_LENGTH).round() as i64
+                    (snapshot.time / snapshot.tick_length).round() as i64
                 ));
             }
         }
@@ -2,7 +2,6 @@ use super::{buffer_arena, geometry, glutil};
 use glutil::VertexAttribBuilder;
 use nalgebra::{Matrix4, Point2, Vector2, Vector4};
 use oort_simulator::color;
-use oort_simulator::simulation::PHYSICS_TICK_LENGTH;
 use oort_simulator::snapshot::Snapshot;
 use wasm_bindgen::prelude::*;
 use web_sys::{WebGl2RenderingContext, WebGlProgram, WebGlUniformLocation, WebGlVertexArrayObject};
@@ -104,7 +103,7 @@ void main() {
             for bullet in bullets.iter() {
                 let p: Point2<f32> = bullet.position.cast();
                 let v: Vector2<f32> = bullet.velocity.cast();
-                let dt = PHYSICS_TICK_LENGTH as f32;
+                let dt = snapshot.tick_length as f32;
                 let mut color = color::from_u32(bullet.color);
                 if bullet.ttl < 0.3 {
                     color.w *= bullet.ttl + 0.3;
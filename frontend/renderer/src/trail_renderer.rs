@@ -3,7 +3,6 @@ use log::warn;
 use nalgebra::{vector, Matrix4, Point2, UnitComplex, Vector2};
 use oort_api::Ability;
 use oort_simulator::ship::ShipClass;
-use oort_simulator::simulation::PHYSICS_TICK_LENGTH;
 use oort_simulator::snapshot::Snapshot;
 use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
@@ -130,7 +129,7 @@ void main() {
                         data.push(color.y);
                         data.push(color.z);
                         data.push(color.w);
-                        data.push(creation_time - PHYSICS_TICK_LENGTH as f32);
+                        data.push(creation_time - snapshot.tick_length as f32);
                         data.push(0.0);
 
                         data.push(current_position.x);
@@ -1,3 +1,4 @@
+use crate::verify::{self, QueueSender};
 use crate::{discord, error, project_id, Error};
 use axum::debug_handler;
 use axum::extract::{Path, State};
@@ -6,44 +7,140 @@ use bytes::Bytes;
 use chrono::Utc;
 use firestore::*;
 use gcloud_sdk::google::firestore::v1::Document;
-use oort_proto::{LeaderboardData, LeaderboardSubmission, TimeLeaderboardRow};
+use oort_proto::{
+    CodeSizeLeaderboardRow, CpuLeaderboardRow, LeaderboardData, LeaderboardSubmission,
+    TimeLeaderboardRow,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    Time,
+    CodeSize,
+    Cpu,
+}
+
+impl Category {
+    pub const ALL: [Category; 3] = [Category::Time, Category::CodeSize, Category::Cpu];
+
+    /// Firestore collection holding each user's best submission in this category.
+    pub fn collection(self) -> &'static str {
+        match self {
+            Category::Time => "leaderboard",
+            Category::CodeSize => "leaderboard_code_size",
+            Category::Cpu => "leaderboard_cpu",
+        }
+    }
+
+    pub fn from_collection(collection: &str) -> Option<Category> {
+        Category::ALL
+            .iter()
+            .copied()
+            .find(|category| category.collection() == collection)
+    }
+
+    pub fn field(self) -> &'static str {
+        match self {
+            Category::Time => "time",
+            Category::CodeSize => "code_size",
+            Category::Cpu => "cpu_gas",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::Time => "time",
+            Category::CodeSize => "code size",
+            Category::Cpu => "CPU",
+        }
+    }
+
+    /// Returns the value ranked by this category (lower is better), if the
+    /// submission can be ranked in it.
+    pub fn score(self, submission: &LeaderboardSubmission) -> Option<f64> {
+        match self {
+            Category::Time => Some(submission.time),
+            Category::CodeSize => Some(submission.code_size as f64),
+            Category::Cpu => submission.cpu_gas,
+        }
+    }
+
+    pub fn format_score(self, submission: &LeaderboardSubmission) -> String {
+        match self {
+            Category::Time => format!("{:.3}s", submission.time),
+            Category::CodeSize => format!("{} bytes", submission.code_size),
+            Category::Cpu => format!("{:.0} gas/tick", submission.cpu_gas.unwrap_or_default()),
+        }
+    }
+
+    fn shortcode(self, submission: &LeaderboardSubmission) -> String {
+        format!(
+            "{}:{}:{}",
+            self.collection(),
+            submission.username,
+            submission.scenario_name
+        )
+    }
+
+    fn rank(self, leaderboard: &LeaderboardData, userid: &str) -> Option<usize> {
+        let position = match self {
+            Category::Time => leaderboard
+                .lowest_time
+                .iter()
+                .position(|x| x.userid == userid),
+            Category::CodeSize => leaderboard
+                .smallest_code
+                .iter()
+                .position(|x| x.userid == userid),
+            Category::Cpu => leaderboard
+                .lowest_cpu
+                .iter()
+                .position(|x| x.userid == userid),
+        };
+        position.map(|i| i + 1)
+    }
+}
 
 async fn fetch_leaderboard(
     db: &FirestoreDb,
     scenario_name: &str,
 ) -> anyhow::Result<LeaderboardData> {
-    let docs: Vec<Document> = db
-        .query_doc(
-            FirestoreQueryParams::new("leaderboard".into())
-                .with_filter(FirestoreQueryFilter::Composite(
-                    FirestoreQueryFilterComposite::new(
-                        vec![FirestoreQueryFilter::Compare(Some(
-                            FirestoreQueryFilterCompare::Equal(
-                                "scenario_name".into(),
-                                scenario_name.into(),
-                            ),
-                        ))],
-                        FirestoreQueryFilterCompositeOperator::And,
-                    ),
-                ))
-                .with_order_by(vec![
-                    FirestoreQueryOrder::new("time".to_owned(), FirestoreQueryDirection::Ascending),
-                    FirestoreQueryOrder::new(
-                        "timestamp".to_owned(),
-                        FirestoreQueryDirection::Ascending,
-                    ),
-                ])
-                .with_limit(1000),
-        )
-        .await?;
-
     let mut leaderboard = LeaderboardData::default();
 
-    for doc in &docs {
-        if let Ok(msg) = FirestoreDb::deserialize_doc_to::<LeaderboardSubmission>(doc) {
-            leaderboard.lowest_time.push(make_row(&msg));
-        } else {
-            log::error!("Failed to deserialize doc {}", doc.name);
+    for category in Category::ALL {
+        let docs: Vec<Document> = db
+            .query_doc(
+                FirestoreQueryParams::new(category.collection().into())
+                    .with_filter(FirestoreQueryFilter::Composite(
+                        FirestoreQueryFilterComposite::new(
+                            vec![FirestoreQueryFilter::Compare(Some(
+                                FirestoreQueryFilterCompare::Equal(
+                                    "scenario_name".into(),
+                                    scenario_name.into(),
+                                ),
+                            ))],
+                            FirestoreQueryFilterCompositeOperator::And,
+                        ),
+                    ))
+                    .with_order_by(vec![
+                        FirestoreQueryOrder::new(
+                            category.field().to_owned(),
+                            FirestoreQueryDirection::Ascending,
+                        ),
+                        FirestoreQueryOrder::new(
+                            "timestamp".to_owned(),
+                            FirestoreQueryDirection::Ascending,
+                        ),
+                    ])
+                    .with_limit(1000),
+            )
+            .await?;
+
+        for doc in &docs {
+            if let Ok(msg) = FirestoreDb::deserialize_doc_to::<LeaderboardSubmission>(doc) {
+                add_row(&mut leaderboard, category, &msg);
+            } else {
+                log::error!("Failed to deserialize doc {}", doc.name);
+            }
         }
     }
 
@@ -54,29 +151,95 @@ pub fn make_row(submission: &LeaderboardSubmission) -> TimeLeaderboardRow {
     TimeLeaderboardRow {
         userid: submission.userid.clone(),
         username: Some(submission.username.clone()),
-        time: format!("{:.3}s", submission.time),
+        time: Category::Time.format_score(submission),
         encrypted_code: "".into(),
         timestamp: Some(submission.timestamp),
         time_float: Some(submission.time),
-        shortcode: Some(format!(
-            "leaderboard:{}:{}",
-            submission.username, submission.scenario_name
-        )),
+        shortcode: Some(Category::Time.shortcode(submission)),
+    }
+}
+
+pub fn make_code_size_row(submission: &LeaderboardSubmission) -> CodeSizeLeaderboardRow {
+    CodeSizeLeaderboardRow {
+        userid: submission.userid.clone(),
+        username: Some(submission.username.clone()),
+        code_size: submission.code_size,
+        timestamp: Some(submission.timestamp),
+        shortcode: Some(Category::CodeSize.shortcode(submission)),
+    }
+}
+
+pub fn make_cpu_row(submission: &LeaderboardSubmission) -> CpuLeaderboardRow {
+    CpuLeaderboardRow {
+        userid: submission.userid.clone(),
+        username: Some(submission.username.clone()),
+        cpu_gas: submission.cpu_gas.unwrap_or_default(),
+        timestamp: Some(submission.timestamp),
+        shortcode: Some(Category::Cpu.shortcode(submission)),
+    }
+}
+
+/// Appends the submission to the category's rows without re-sorting.
+fn add_row(leaderboard: &mut LeaderboardData, category: Category, msg: &LeaderboardSubmission) {
+    match category {
+        Category::Time => leaderboard.lowest_time.push(make_row(msg)),
+        Category::CodeSize => leaderboard.smallest_code.push(make_code_size_row(msg)),
+        Category::Cpu => {
+            if msg.cpu_gas.is_some() {
+                leaderboard.lowest_cpu.push(make_cpu_row(msg))
+            }
+        }
     }
 }
 
+/// Replaces the user's row in the category and restores the ranking order.
+fn replace_row(leaderboard: &mut LeaderboardData, category: Category, msg: &LeaderboardSubmission) {
+    match category {
+        Category::Time => {
+            leaderboard.lowest_time.retain(|x| x.userid != msg.userid);
+            add_row(leaderboard, category, msg);
+            leaderboard
+                .lowest_time
+                .sort_by_key(|x| ((x.time_float.unwrap_or(1e6) * 1e6) as u64, x.timestamp));
+        }
+        Category::CodeSize => {
+            leaderboard.smallest_code.retain(|x| x.userid != msg.userid);
+            add_row(leaderboard, category, msg);
+            leaderboard
+                .smallest_code
+                .sort_by_key(|x| (x.code_size, x.timestamp));
+        }
+        Category::Cpu => {
+            leaderboard.lowest_cpu.retain(|x| x.userid != msg.userid);
+            add_row(leaderboard, category, msg);
+            leaderboard
+                .lowest_cpu
+                .sort_by_key(|x| ((x.cpu_gas * 1e3) as u64, x.timestamp));
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LeaderboardState {
+    pub cache: SharedLeaderboardCache,
+    pub queue: QueueSender,
+}
+
 pub async fn get(
     Path(scenario_name): Path<String>,
-    cache: State<SharedLeaderboardCache>,
+    State(state): State<LeaderboardState>,
 ) -> Result<Json<LeaderboardData>, Error> {
     let db = FirestoreDb::new(&project_id()).await?;
-    let data: LeaderboardData = cache.get(&db, &scenario_name).await?;
+    let data: LeaderboardData = state.cache.get(&db, &scenario_name).await?;
     Ok(Json(data))
 }
 
+/// Queues a submission for verification. It only appears on the leaderboard
+/// once the verifier has re-simulated it, so the returned leaderboard does not
+/// include it yet.
 #[debug_handler]
 pub async fn post(
-    cache: State<SharedLeaderboardCache>,
+    State(state): State<LeaderboardState>,
     payload: Bytes,
 ) -> Result<Json<LeaderboardData>, Error> {
     let db = FirestoreDb::new(&project_id()).await?;
@@ -100,62 +263,83 @@ pub async fn post(
     }
 
     obj.timestamp = Utc::now();
+    // Never trust the client-reported code size.
+    obj.code_size = oort_code_size::calculate(&obj.code);
+    obj.verification = None;
+    let path = verify::queue_docid(&obj);
+
+    db.update_obj(verify::QUEUE_COLLECTION, &path, &obj, None, None, None)
+        .await?;
+    state.queue.send(path)?;
+
+    let leaderboard = state.cache.get(&db, &obj.scenario_name).await?;
+    Ok(Json(leaderboard))
+}
+
+/// Adds a verified submission to each leaderboard category it improves on.
+pub async fn insert(
+    db: &FirestoreDb,
+    cache: &SharedLeaderboardCache,
+    obj: &LeaderboardSubmission,
+) -> Result<(), Error> {
     let path = format!("{}.{}", obj.scenario_name, obj.userid);
 
-    let old_leaderboard = cache.get(&db, &obj.scenario_name).await?;
+    let old_leaderboard = cache.get(db, &obj.scenario_name).await?;
 
-    if let Ok(existing_obj) = db
-        .get_obj::<LeaderboardSubmission, _>("leaderboard", &path)
-        .await
-    {
-        log::debug!("Got existing obj {:?}", existing_obj);
-        if existing_obj.time <= obj.time {
-            log::debug!("Ignoring slower time");
-            return Ok(Json(old_leaderboard));
-        }
-    }
+    for category in Category::ALL {
+        let Some(score) = category.score(obj) else {
+            continue;
+        };
 
-    db.update_obj("leaderboard", &path, &obj, None, None, None)
-        .await?;
+        if let Ok(existing_obj) = db
+            .get_obj::<LeaderboardSubmission, _>(category.collection(), &path)
+            .await
+        {
+            log::debug!("Got existing obj {:?}", existing_obj);
+            if category
+                .score(&existing_obj)
+                .map(|existing_score| existing_score <= score)
+                .unwrap_or(false)
+            {
+                log::debug!("Ignoring worse {}", category.name());
+                continue;
+            }
+        }
 
-    cache
-        .update(&db, &obj.scenario_name, make_row(&obj))
-        .await?;
+        db.update_obj(category.collection(), &path, obj, None, None, None)
+            .await?;
 
-    let new_leaderboard = cache.get(&db, &obj.scenario_name).await?;
+        cache.update(db, &obj.scenario_name, category, obj).await?;
+    }
 
-    let get_rank = |leaderboard: &LeaderboardData, userid: &str| -> Option<usize> {
-        leaderboard
-            .lowest_time
-            .iter()
-            .enumerate()
-            .find(|(_, entry)| entry.userid == userid)
-            .map(|(i, _)| i + 1)
-    };
+    let new_leaderboard = cache.get(db, &obj.scenario_name).await?;
 
-    let old_rank = get_rank(&old_leaderboard, &obj.userid);
-    let new_rank = get_rank(&new_leaderboard, &obj.userid);
+    for category in Category::ALL {
+        let old_rank = category.rank(&old_leaderboard, &obj.userid);
+        let new_rank = category.rank(&new_leaderboard, &obj.userid);
 
-    let rank_improved = match (old_rank, new_rank) {
-        (Some(old_rank), Some(new_rank)) if old_rank > new_rank => true,
-        (None, Some(_)) => true,
-        _ => false,
-    };
+        let rank_improved = match (old_rank, new_rank) {
+            (Some(old_rank), Some(new_rank)) if old_rank > new_rank => true,
+            (None, Some(_)) => true,
+            _ => false,
+        };
 
-    if rank_improved && new_rank.map(|x| x <= 10).unwrap_or(false) {
-        discord::send_message(
-            discord::Channel::Leaderboard,
-            format!(
-                "{} achieved leaderboard rank {} on scenario {} with time {:.3}s",
-                obj.username,
-                new_rank.unwrap(),
-                obj.scenario_name,
-                obj.time
-            ),
-        );
+        if rank_improved && new_rank.map(|x| x <= 10).unwrap_or(false) {
+            discord::send_message(
+                discord::Channel::Leaderboard,
+                format!(
+                    "{} achieved {} leaderboard rank {} on scenario {} with {}",
+                    obj.username,
+                    category.name(),
+                    new_rank.unwrap(),
+                    obj.scenario_name,
+                    category.format_score(obj),
+                ),
+            );
+        }
     }
 
-    Ok(Json(new_leaderboard))
+    Ok(())
 }
 
 pub type SharedLeaderboardCache = std::sync::Arc<LeaderboardCache>;
@@ -203,9 +387,14 @@ impl LeaderboardCache {
         &self,
         db: &FirestoreDb,
         scenario_name: &str,
-        row: TimeLeaderboardRow,
+        category: Category,
+        submission: &LeaderboardSubmission,
     ) -> Result<(), Error> {
-        log::info!("Leaderboard cache update for {}", scenario_name);
+        log::info!(
+            "Leaderboard cache update for {} ({})",
+            scenario_name,
+            category.name()
+        );
         let has_cache_entry = {
             let scenarios = self.scenarios.lock().await;
             scenarios.contains_key(scenario_name)
@@ -225,15 +414,7 @@ impl LeaderboardCache {
 
         let mut scenarios = self.scenarios.lock().await;
         let cached = scenarios.get_mut(scenario_name).unwrap();
-        cached
-            .leaderboard
-            .lowest_time
-            .retain(|x| x.userid != row.userid);
-        cached.leaderboard.lowest_time.push(row);
-        cached
-            .leaderboard
-            .lowest_time
-            .sort_by_key(|x| ((x.time_float.unwrap_or(1e6) * 1e6) as u64, x.timestamp));
+        replace_row(&mut cached.leaderboard, category, submission);
         Ok(())
     }
 }
@@ -4,6 +4,7 @@ pub mod rescore;
 pub mod shortcode;
 pub mod telemetry;
 pub mod tournament;
+pub mod verify;
 
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
@@ -1,7 +1,9 @@
 use axum::Router;
 use clap::{Parser, Subcommand};
 use http::Method;
-use oort_backend_service::{leaderboard, project_id, rescore, shortcode, telemetry, tournament};
+use oort_backend_service::{
+    leaderboard, project_id, rescore, shortcode, telemetry, tournament, verify,
+};
 use tower_http::cors::{Any, CorsLayer};
 
 #[derive(Parser, Debug)]
@@ -53,6 +55,10 @@ async fn serve() -> anyhow::Result<()> {
 
     let leaderboard_cache: leaderboard::SharedLeaderboardCache =
         std::sync::Arc::new(leaderboard::LeaderboardCache::new());
+    let leaderboard_state = leaderboard::LeaderboardState {
+        queue: verify::start_worker(leaderboard_cache.clone()),
+        cache: leaderboard_cache,
+    };
 
     let cors = CorsLayer::new()
         .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
@@ -69,7 +75,7 @@ async fn serve() -> anyhow::Result<()> {
             .route("/tournament/results/:id", get(tournament::get_results))
             .route("/leaderboard/:scenario_name", get(leaderboard::get))
             .route("/leaderboard", post(leaderboard::post))
-            .with_state(leaderboard_cache)
+            .with_state(leaderboard_state)
             .layer(cors)
             .layer(tower_http::trace::TraceLayer::new_for_http())
     };
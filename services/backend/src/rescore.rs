@@ -1,31 +1,42 @@
+use crate::leaderboard::Category;
+use crate::verify::{compile, extract_docid, run_simulations};
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::Table;
 use firestore::*;
 use gcloud_sdk::google::firestore::v1::Document;
 use oort_proto::LeaderboardSubmission;
-use oort_simulator::simulation::Code;
-use oort_simulator::{scenario, simulation};
-use rayon::prelude::*;
+use oort_simulator::scenario;
 
 const TOP_N: u32 = 10;
 
 pub async fn rescore(dry_run: bool) -> anyhow::Result<()> {
     let db = FirestoreDb::new(&crate::project_id()).await?;
     let http = reqwest::Client::new();
-    let mut updates: Vec<(String, LeaderboardSubmission, Option<LeaderboardSubmission>)> =
-        Vec::new();
+    let mut updates: Vec<(
+        Category,
+        String,
+        LeaderboardSubmission,
+        Option<LeaderboardSubmission>,
+    )> = Vec::new();
 
     let scenario_names: Vec<String> = scenario::list()
         .iter()
         .flat_map(|(_, v)| v.clone())
         .collect();
 
-    for scenario_name in &scenario_names {
-        log::info!("Processing scenario {}", scenario_name);
+    for (scenario_name, category) in scenario_names
+        .iter()
+        .flat_map(|x| Category::ALL.iter().map(move |c| (x, *c)))
+    {
+        log::info!(
+            "Processing scenario {} ({} leaderboard)",
+            scenario_name,
+            category.name()
+        );
 
         let docs: Vec<Document> = db
             .query_doc(
-                FirestoreQueryParams::new("leaderboard".into())
+                FirestoreQueryParams::new(category.collection().into())
                     .with_filter(FirestoreQueryFilter::Composite(
                         FirestoreQueryFilterComposite::new(
                             vec![FirestoreQueryFilter::Compare(Some(
@@ -39,7 +50,7 @@ pub async fn rescore(dry_run: bool) -> anyhow::Result<()> {
                     ))
                     .with_order_by(vec![
                         FirestoreQueryOrder::new(
-                            "time".to_owned(),
+                            category.field().to_owned(),
                             FirestoreQueryDirection::Ascending,
                         ),
                         FirestoreQueryOrder::new(
@@ -52,7 +63,13 @@ pub async fn rescore(dry_run: bool) -> anyhow::Result<()> {
             .await?;
 
         for doc in docs {
-            let docid = extract_docid(&doc.name);
+            let docid = match extract_docid(&doc.name) {
+                Ok(docid) => docid,
+                Err(e) => {
+                    log::error!("Skipping document: {}", e);
+                    continue;
+                }
+            };
             if let Ok(msg) = FirestoreDb::deserialize_doc_to::<LeaderboardSubmission>(&doc) {
                 log::info!(
                     "Running simulations for username={} scenario={} old_time={} docid={}",
@@ -81,12 +98,30 @@ pub async fn rescore(dry_run: bool) -> anyhow::Result<()> {
                 log::info!("Successfully compiled to WASM");
                 let status = run_simulations(&msg.scenario_name, &wasm);
                 match status {
-                    Some(new_time) => {
-                        if (msg.time - new_time).abs() >= 0.001 {
-                            log::info!("Updating time from {} to {}", msg.time, new_time);
+                    Some((new_time, new_cpu_gas)) => {
+                        let new_code_size = oort_code_size::calculate(&msg.code);
+                        let cpu_changed = match (msg.cpu_gas, new_cpu_gas) {
+                            (Some(old), Some(new)) => (old - new).abs() >= 1.0,
+                            (old, new) => old.is_some() != new.is_some(),
+                        };
+                        if (msg.time - new_time).abs() >= 0.001
+                            || msg.code_size != new_code_size
+                            || cpu_changed
+                        {
+                            log::info!(
+                                "Updating time from {} to {}, code size from {} to {}, cpu from {:?} to {:?}",
+                                msg.time,
+                                new_time,
+                                msg.code_size,
+                                new_code_size,
+                                msg.cpu_gas,
+                                new_cpu_gas
+                            );
                             let mut new_msg = msg.clone();
                             new_msg.time = new_time;
-                            updates.push((doc.name.to_string(), msg.clone(), Some(new_msg)));
+                            new_msg.code_size = new_code_size;
+                            new_msg.cpu_gas = new_cpu_gas;
+                            updates.push((category, docid.clone(), msg.clone(), Some(new_msg)));
                         } else {
                             log::info!("Time unchanged, {}", new_time);
                         }
@@ -98,7 +133,7 @@ pub async fn rescore(dry_run: bool) -> anyhow::Result<()> {
                             msg.scenario_name,
                             docid,
                         );
-                        updates.push((doc.name.to_string(), msg.clone(), None));
+                        updates.push((category, docid.clone(), msg.clone(), None));
                     }
                 }
             }
@@ -108,14 +143,29 @@ pub async fn rescore(dry_run: bool) -> anyhow::Result<()> {
     log::info!("Applying {} updates:", updates.len());
     let mut table = Table::new();
     table.load_preset(UTF8_FULL);
-    table.set_header(vec!["Scenario", "User", "Old Time", "New Time", "Docid"]);
-    for (docname, old_msg, new_msg) in &updates {
-        let docid = extract_docid(docname);
+    table.set_header(vec![
+        "Leaderboard",
+        "Scenario",
+        "User",
+        "Old Time",
+        "New Time",
+        "Old Size",
+        "New Size",
+        "Old CPU",
+        "New CPU",
+        "Docid",
+    ]);
+    for (category, docid, old_msg, new_msg) in &updates {
         table.add_row(vec![
+            category.name().to_string(),
             old_msg.scenario_name.clone(),
             old_msg.username.clone(),
             format!("{:.3}", old_msg.time),
             format!("{:.3?}", new_msg.as_ref().map(|x| x.time)),
+            format!("{}", old_msg.code_size),
+            format!("{:?}", new_msg.as_ref().map(|x| x.code_size)),
+            format!("{:.0?}", old_msg.cpu_gas),
+            format!("{:.0?}", new_msg.as_ref().and_then(|x| x.cpu_gas)),
             docid.clone(),
         ]);
     }
@@ -126,65 +176,14 @@ pub async fn rescore(dry_run: bool) -> anyhow::Result<()> {
         return Ok(());
     }
 
-    for (docname, _old_msg, new_msg) in &updates {
-        let docid = extract_docid(docname);
+    for (category, docid, _old_msg, new_msg) in &updates {
         if let Some(new_msg) = new_msg {
-            db.update_obj("leaderboard", &docid, new_msg, None, None, None)
+            db.update_obj(category.collection(), docid, new_msg, None, None, None)
                 .await?;
         } else {
-            db.delete_by_id("leaderboard", &docid, None).await?;
+            db.delete_by_id(category.collection(), docid, None).await?;
         }
     }
 
     Ok(())
 }
-
-async fn compile(http: &reqwest::Client, name: &str, source_code: &str) -> anyhow::Result<Code> {
-    let compiler_url =
-        std::env::var("COMPILER_URL").unwrap_or_else(|_| "https://compiler.oort.rs".to_string());
-    log::info!("Using compiler at {}", compiler_url);
-
-    let response = http
-        .post(&format!("{compiler_url}/compile"))
-        .body(source_code.to_string())
-        .send()
-        .await?;
-
-    if !response.status().is_success() {
-        anyhow::bail!("Failed to compile {:?}: {:?}", name, response.text().await?);
-    }
-
-    let compiled_code = response.bytes().await?.to_vec();
-    Ok(oort_simulator::vm::precompile(&compiled_code).unwrap())
-}
-
-fn run_simulations(scenario_name: &str, code: &Code) -> Option<f64> {
-    let results: Vec<Option<f64>> = (0..10u32)
-        .into_par_iter()
-        .map(|seed| run_simulation(scenario_name, seed, code.clone()))
-        .collect();
-    log::info!("Results: {:?}", results);
-    if results.iter().any(|x| x.is_none()) {
-        return None;
-    }
-    Some(results.iter().map(|x| x.unwrap()).sum::<f64>() / results.len() as f64)
-}
-
-fn run_simulation(scenario_name: &str, seed: u32, code: Code) -> Option<f64> {
-    let scenario = scenario::load(scenario_name);
-    let mut codes = scenario.initial_code();
-    codes[0] = code;
-    let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
-    while sim.status() == scenario::Status::Running && sim.tick() < scenario::MAX_TICKS {
-        sim.step();
-    }
-    match sim.status() {
-        scenario::Status::Victory { team: 0 } => Some(sim.score_time()),
-        _ => None,
-    }
-}
-
-fn extract_docid(docname: &str) -> String {
-    let (_, docid) = docname.rsplit_once('/').unwrap();
-    docid.to_string()
-}
@@ -0,0 +1,308 @@
+use crate::leaderboard::{self, SharedLeaderboardCache};
+use crate::{discord, project_id};
+use firestore::*;
+use gcloud_sdk::google::firestore::v1::Document;
+use oort_proto::{LeaderboardSubmission, LeaderboardVerification};
+use oort_simulator::simulation::Code;
+use oort_simulator::{scenario, simulation};
+use rayon::prelude::*;
+use tokio::sync::mpsc;
+
+/// Submissions waiting to be compiled and re-simulated, keyed by [`queue_docid`].
+pub const QUEUE_COLLECTION: &str = "leaderboard_queue";
+
+/// Seeds every leaderboard submission is re-simulated on.
+pub const SEEDS: std::ops::Range<u32> = 0..10;
+
+/// Maximum difference between claimed and verified time before a submission is flagged.
+const TIME_TOLERANCE: f64 = 0.001;
+
+pub type QueueSender = mpsc::UnboundedSender<String>;
+
+pub fn start_worker(cache: SharedLeaderboardCache) -> QueueSender {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let requeue_sender = sender.clone();
+    tokio::spawn(async move {
+        // Pick up anything left over from a previous instance.
+        match pending().await {
+            Ok(docids) => {
+                for docid in docids {
+                    let _ = requeue_sender.send(docid);
+                }
+            }
+            Err(e) => log::error!("Failed to list leaderboard queue: {}", e),
+        }
+    });
+    tokio::spawn(run_worker(cache, receiver));
+    sender
+}
+
+async fn pending() -> anyhow::Result<Vec<String>> {
+    let db = FirestoreDb::new(&project_id()).await?;
+    let docs: Vec<Document> = db
+        .query_doc(FirestoreQueryParams::new(QUEUE_COLLECTION.into()))
+        .await?;
+    Ok(docs
+        .iter()
+        .filter_map(|doc| match extract_docid(&doc.name) {
+            Ok(docid) => Some(docid),
+            Err(e) => {
+                log::error!("Skipping queued submission: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+async fn run_worker(cache: SharedLeaderboardCache, mut receiver: mpsc::UnboundedReceiver<String>) {
+    let http = reqwest::Client::new();
+    while let Some(docid) = receiver.recv().await {
+        if let Err(e) = process(&http, &cache, &docid).await {
+            log::error!("Failed to verify leaderboard submission {}: {:?}", docid, e);
+        }
+    }
+}
+
+async fn process(
+    http: &reqwest::Client,
+    cache: &SharedLeaderboardCache,
+    docid: &str,
+) -> anyhow::Result<()> {
+    let db = FirestoreDb::new(&project_id()).await?;
+    let Ok(mut obj) = db
+        .get_obj::<LeaderboardSubmission, _>(QUEUE_COLLECTION, docid)
+        .await
+    else {
+        log::info!("Leaderboard submission {} already processed", docid);
+        return Ok(());
+    };
+
+    log::info!(
+        "Verifying leaderboard submission username={} scenario={} claimed_time={}",
+        obj.username,
+        obj.scenario_name,
+        obj.time
+    );
+
+    match verify(http, &obj).await {
+        Ok((time, cpu_gas)) => {
+            let claimed_time = obj.time;
+            if apply_verification(&mut obj, time, cpu_gas) {
+                log::warn!(
+                    "Leaderboard time mismatch for username={} scenario={}: claimed {} verified {}",
+                    obj.username,
+                    obj.scenario_name,
+                    claimed_time,
+                    time
+                );
+                discord::send_message(
+                    discord::Channel::Leaderboard,
+                    format!(
+                        "Flagged {} on scenario {}: claimed {:.3}s, verified {:.3}s",
+                        obj.username, obj.scenario_name, claimed_time, time
+                    ),
+                );
+            }
+            leaderboard::insert(&db, cache, &obj).await?;
+        }
+        Err(e) => {
+            log::warn!(
+                "Rejected leaderboard submission username={} scenario={}: {}",
+                obj.username,
+                obj.scenario_name,
+                e
+            );
+        }
+    }
+
+    db.delete_by_id(QUEUE_COLLECTION, docid, None).await?;
+    Ok(())
+}
+
+/// Document ID for a queued submission. Each submission gets its own document,
+/// so a resubmission isn't deleted along with an earlier entry that was being
+/// verified at the time.
+pub fn queue_docid(obj: &LeaderboardSubmission) -> String {
+    format!(
+        "{}.{}.{}",
+        obj.scenario_name,
+        obj.userid,
+        obj.timestamp.timestamp_nanos_opt().unwrap_or_default()
+    )
+}
+
+/// Replaces the claimed results with the verified ones, keeping the claimed
+/// values in `verification`. Returns true if the claimed time was wrong.
+fn apply_verification(obj: &mut LeaderboardSubmission, time: f64, cpu_gas: Option<f64>) -> bool {
+    let mismatch = (obj.time - time).abs() >= TIME_TOLERANCE;
+    obj.verification = Some(LeaderboardVerification {
+        claimed_time: obj.time,
+        claimed_cpu_gas: obj.cpu_gas,
+        mismatch,
+    });
+    obj.time = time;
+    obj.cpu_gas = cpu_gas;
+    mismatch
+}
+
+/// Compiles the submission and returns its verified time and CPU usage.
+async fn verify(
+    http: &reqwest::Client,
+    obj: &LeaderboardSubmission,
+) -> anyhow::Result<(f64, Option<f64>)> {
+    if scenario::load_safe(&obj.scenario_name).is_none() {
+        anyhow::bail!("unknown scenario {:?}", obj.scenario_name);
+    }
+    let code = compile(http, &obj.username, &obj.code).await?;
+    let scenario_name = obj.scenario_name.clone();
+    tokio::task::spawn_blocking(move || run_simulations(&scenario_name, &code))
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("simulation failed"))
+}
+
+pub async fn compile(
+    http: &reqwest::Client,
+    name: &str,
+    source_code: &str,
+) -> anyhow::Result<Code> {
+    let compiler_url =
+        std::env::var("COMPILER_URL").unwrap_or_else(|_| "https://compiler.oort.rs".to_string());
+    log::info!("Using compiler at {}", compiler_url);
+
+    let response = http
+        .post(&format!("{compiler_url}/compile"))
+        .body(source_code.to_string())
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Failed to compile {:?}: {:?}", name, response.text().await?);
+    }
+
+    let compiled_code = response.bytes().await?.to_vec();
+    oort_simulator::vm::precompile(&compiled_code).map_err(|e| anyhow::anyhow!("{}", e.msg))
+}
+
+/// Returns the average score time and average CPU gas per tick across seeds.
+pub fn run_simulations(scenario_name: &str, code: &Code) -> Option<(f64, Option<f64>)> {
+    let results: Vec<Option<(f64, Option<f64>)>> = SEEDS
+        .into_par_iter()
+        .map(|seed| run_simulation(scenario_name, seed, code.clone()))
+        .collect();
+    log::info!("Results: {:?}", results);
+    if results.iter().any(|x| x.is_none()) {
+        return None;
+    }
+    let results: Vec<(f64, Option<f64>)> = results.into_iter().flatten().collect();
+    let time = results.iter().map(|(time, _)| time).sum::<f64>() / results.len() as f64;
+    let cpu_gas = results
+        .iter()
+        .map(|(_, cpu_gas)| *cpu_gas)
+        .sum::<Option<f64>>()
+        .map(|x| x / results.len() as f64);
+    Some((time, cpu_gas))
+}
+
+fn run_simulation(scenario_name: &str, seed: u32, code: Code) -> Option<(f64, Option<f64>)> {
+    let scenario = scenario::load(scenario_name);
+    let mut codes = scenario.initial_code();
+    codes[0] = code;
+    let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
+    while sim.status() == scenario::Status::Running && sim.tick() < scenario::MAX_TICKS {
+        sim.step();
+    }
+    match sim.status() {
+        scenario::Status::Victory { team: 0 } => Some((sim.score_time(), sim.cpu_usage(0))),
+        _ => None,
+    }
+}
+
+pub fn extract_docid(docname: &str) -> anyhow::Result<String> {
+    match docname.rsplit_once('/') {
+        Some((_, docid)) if !docid.is_empty() => Ok(docid.to_string()),
+        _ => anyhow::bail!("malformed document name {:?}", docname),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::Utc;
+
+    fn submission(time: f64, cpu_gas: Option<f64>) -> LeaderboardSubmission {
+        LeaderboardSubmission {
+            scenario_name: "tutorial_guns".into(),
+            userid: "user".into(),
+            username: "User".into(),
+            timestamp: Utc::now(),
+            time,
+            code_size: 0,
+            code: String::new(),
+            cpu_gas,
+            verification: None,
+        }
+    }
+
+    #[test]
+    fn test_queue_docid() {
+        let a = submission(10.0, None);
+        let mut b = a.clone();
+        b.timestamp = a.timestamp + chrono::Duration::milliseconds(1);
+        assert!(queue_docid(&a).starts_with("tutorial_guns.user."));
+        assert_ne!(queue_docid(&a), queue_docid(&b));
+    }
+
+    #[test]
+    fn test_extract_docid() {
+        assert_eq!(
+            extract_docid("projects/oort/databases/(default)/documents/leaderboard_queue/a.b")
+                .unwrap(),
+            "a.b"
+        );
+        assert!(extract_docid("no-slash").is_err());
+        assert!(extract_docid("trailing/").is_err());
+    }
+
+    #[test]
+    fn test_verification_within_tolerance() {
+        let mut obj = submission(10.0, Some(500.0));
+        assert!(!apply_verification(
+            &mut obj,
+            10.0 + TIME_TOLERANCE / 2.0,
+            Some(400.0)
+        ));
+        assert_eq!(obj.time, 10.0 + TIME_TOLERANCE / 2.0);
+        assert_eq!(obj.cpu_gas, Some(400.0));
+        assert_eq!(
+            obj.verification,
+            Some(LeaderboardVerification {
+                claimed_time: 10.0,
+                claimed_cpu_gas: Some(500.0),
+                mismatch: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_verification_mismatch() {
+        let mut obj = submission(5.0, None);
+        assert!(apply_verification(&mut obj, 12.5, Some(300.0)));
+        assert_eq!(obj.time, 12.5);
+        assert_eq!(obj.cpu_gas, Some(300.0));
+        assert_eq!(
+            obj.verification,
+            Some(LeaderboardVerification {
+                claimed_time: 5.0,
+                claimed_cpu_gas: None,
+                mismatch: true,
+            })
+        );
+
+        let mut obj = submission(10.0, None);
+        assert!(apply_verification(
+            &mut obj,
+            10.0 - TIME_TOLERANCE * 2.0,
+            None
+        ));
+    }
+}
@@ -1,11 +1,13 @@
-use axum::extract::State;
-use axum::Router;
+use axum::extract::{Query, State};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
 use bytes::Bytes;
 use clap::Parser as _;
 use http::{Method, StatusCode};
 use once_cell::sync::Lazy;
-use oort_compiler::Compiler;
+use oort_compiler::{check_network_isolation, Cache, Compiler, Language, Limits};
 use oort_compiler_service::{error, Error};
+use std::collections::HashMap;
 use std::io::Write;
 use std::sync::{Arc, Mutex};
 use tempfile::NamedTempFile;
@@ -17,10 +19,39 @@ static FORMAT_LOCK: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mut
 static SEMAPHORE: Lazy<tokio::sync::Semaphore> =
     Lazy::new(|| tokio::sync::Semaphore::new(MAX_CONCURRENCY));
 
+#[derive(Clone)]
+struct AppState {
+    compiler: Arc<Mutex<Compiler>>,
+    cache: Arc<Cache>,
+    /// Toolchain versions for the cache key, looked up once at startup so the
+    /// cache can be checked without waiting for the compiler.
+    versions: Arc<HashMap<Language, String>>,
+}
+
 async fn post_compile(
-    State(compiler): State<Arc<Mutex<Compiler>>>,
-    mut code: String,
-) -> Result<Bytes, Error> {
+    State(AppState {
+        compiler,
+        cache,
+        versions,
+    }): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+    body: Bytes,
+) -> Result<Response, Error> {
+    // Precompiled modules can be uploaded directly or base64-encoded as source.
+    if body.starts_with(b"\0asm") {
+        return check_upload(&body).map(|()| body.into_response());
+    }
+    let mut code = String::from_utf8(body.to_vec())
+        .map_err(|_| error(StatusCode::BAD_REQUEST, "Code is not UTF-8".to_string()))?;
+
+    let language = match params.get("language") {
+        Some(name) => Some(
+            name.parse::<Language>()
+                .map_err(|e| error(StatusCode::BAD_REQUEST, e.to_string()))?,
+        ),
+        None => None,
+    };
+
     let permit = SEMAPHORE.try_acquire();
     if permit.is_err() {
         return Err(error(
@@ -34,24 +65,75 @@ async fn post_compile(
         code = oort_code_encryption::decrypt(&code)?;
     }
     log::debug!("Code: {}", code);
+    if let Some(wasm) = oort_compiler::decode_wasm(&code) {
+        let wasm = wasm.map_err(|e| error(StatusCode::BAD_REQUEST, e.to_string()))?;
+        check_upload(&wasm)?;
+        return Ok(Bytes::from(wasm).into_response());
+    }
+    let json = params.get("format").map(|x| x.as_str()) == Some("json");
+    let language = match language {
+        Some(language) => language,
+        None => oort_compiler::detect_language(&code)
+            .map_err(|e| error(StatusCode::BAD_REQUEST, e.to_string()))?,
+    };
+    let key = versions
+        .get(&language)
+        .map(|version| Cache::key(language, version, &code));
+    if let Some(wasm) = key.as_ref().and_then(|key| cache.get(key)) {
+        log::info!("Compile cache hit");
+        return Ok(Bytes::from(wasm).into_response());
+    }
     let start_time = std::time::Instant::now();
     let result = tokio::runtime::Handle::current()
-        .spawn_blocking(move || compiler.lock().unwrap().compile(&code))
+        .spawn_blocking(move || {
+            let mut compiler = compiler.lock().unwrap();
+            compiler.compile_language(&code, language)
+        })
         .await?;
     let elapsed = std::time::Instant::now() - start_time;
     match result {
         Ok(wasm) => {
             log::info!("Compile succeeded in {:?}", elapsed);
-            Ok(Bytes::copy_from_slice(&wasm))
+            if let Some(key) = &key {
+                if let Err(e) = cache.put(key, &wasm) {
+                    log::warn!("Failed to write to compiler cache: {:?}", e);
+                }
+            }
+            Ok(Bytes::copy_from_slice(&wasm).into_response())
         }
         Err(e) => {
             log::info!("Compile failed in {:?}", elapsed);
             log::debug!("Compile failed: {}", e);
-            Err(error(StatusCode::BAD_REQUEST, e.to_string()))
+            if let Some(limit) = e.downcast_ref::<oort_compiler::LimitExceeded>() {
+                log::warn!("Compile hit a resource limit: {}", limit);
+            }
+            match e.downcast_ref::<oort_compiler::CompileError>() {
+                Some(compile_error) if json => Ok((
+                    StatusCode::BAD_REQUEST,
+                    Json(compile_error.diagnostics.clone()),
+                )
+                    .into_response()),
+                _ => Err(error(StatusCode::BAD_REQUEST, e.to_string())),
+            }
         }
     }
 }
 
+fn check_upload(wasm: &[u8]) -> Result<(), Error> {
+    oort_simulator::vm::abi::validate(wasm).map_err(|e| {
+        log::info!("Rejected uploaded WASM: {}", e.msg);
+        error(StatusCode::BAD_REQUEST, e.msg)
+    })
+}
+
+async fn get_cache(State(AppState { cache, .. }): State<AppState>) -> String {
+    let stats = cache.stats();
+    format!(
+        "entries={} bytes={} max_bytes={} hits={} misses={}\n",
+        stats.entries, stats.bytes, stats.max_bytes, stats.hits, stats.misses
+    )
+}
+
 async fn post_format(code: String) -> Result<String, Error> {
     let _guard = FORMAT_LOCK.lock().await;
     let mut tmpfile = NamedTempFile::new()?;
@@ -82,6 +164,35 @@ async fn main() {
     struct Arguments {
         #[clap(short, long)]
         prepare: bool,
+
+        #[clap(long, default_value = "/tmp/oort-compiler-cache")]
+        cache_dir: String,
+
+        /// Size limit of the compiled code cache in megabytes.
+        #[clap(long, default_value_t = 256)]
+        cache_max_mb: u64,
+
+        /// CPU time limit for each compiler process in seconds.
+        #[clap(long, default_value_t = 30)]
+        cpu_limit_secs: u64,
+
+        /// Wall-clock time limit for each compiler process in seconds.
+        #[clap(long, default_value_t = 60)]
+        wall_limit_secs: u64,
+
+        /// Memory limit for each compiler process in megabytes.
+        #[clap(long, default_value_t = 4096)]
+        memory_limit_mb: u64,
+
+        /// Limit on the size of files written by each compiler process in megabytes.
+        #[clap(long, default_value_t = 64)]
+        output_limit_mb: u64,
+
+        /// Skips creating a network namespace for compiler processes. Without
+        /// it the service won't start on hosts that don't support user
+        /// namespaces.
+        #[clap(long)]
+        allow_network: bool,
     }
     let args = Arguments::parse();
 
@@ -98,11 +209,34 @@ async fn main() {
         Err(_e) => {}
     };
 
+    if !args.allow_network && !args.prepare {
+        if let Err(e) = check_network_isolation() {
+            log::error!("{}; pass --allow-network to run without it", e);
+            std::process::exit(1);
+        }
+    }
+
     let dir = "/tmp/oort-ai";
     std::fs::create_dir_all(dir).unwrap();
     let mut compiler = Compiler::new_with_dir(std::path::Path::new(dir));
+    let limits = Limits {
+        cpu_time: std::time::Duration::from_secs(args.cpu_limit_secs),
+        wall_time: std::time::Duration::from_secs(args.wall_limit_secs),
+        memory_bytes: args.memory_limit_mb << 20,
+        output_bytes: args.output_limit_mb << 20,
+        network: args.allow_network,
+    };
+    compiler.set_limits(limits.clone());
 
     if args.prepare {
+        // Building the AI crate's dependencies from scratch takes a while, and
+        // downloading them needs the network.
+        compiler.set_limits(Limits {
+            cpu_time: std::time::Duration::from_secs(600),
+            wall_time: std::time::Duration::from_secs(1800),
+            network: true,
+            ..limits
+        });
         compiler.enable_online();
         compiler
             .compile(include_str!("../../../shared/builtin_ai/src/empty.rs"))
@@ -110,6 +244,18 @@ async fn main() {
         return;
     }
 
+    let cache = Arc::new(
+        Cache::new(
+            std::path::Path::new(&args.cache_dir),
+            args.cache_max_mb << 20,
+        )
+        .unwrap(),
+    );
+    let versions: HashMap<Language, String> = Language::ALL
+        .iter()
+        .filter_map(|&language| Some((language, compiler.version(language)?)))
+        .collect();
+
     log::info!("Starting oort_compiler_service v1");
 
     let cors = CorsLayer::new()
@@ -118,13 +264,18 @@ async fn main() {
         .allow_headers(Any);
 
     let router = {
-        use axum::routing::post;
+        use axum::routing::{get, post};
         Router::new()
             .route("/compile", post(post_compile))
             .route("/format", post(post_format))
+            .route("/cache", get(get_cache))
             .layer(cors)
             .layer(tower_http::trace::TraceLayer::new_for_http())
-            .with_state(Arc::new(Mutex::new(compiler)))
+            .with_state(AppState {
+                compiler: Arc::new(Mutex::new(compiler)),
+                cache,
+                versions: Arc::new(versions),
+            })
     };
 
     let listener = tokio::net::TcpListener::bind(&format!("0.0.0.0:{port}"))
@@ -147,6 +147,18 @@ pub enum SystemState {
 
     Id,
 
+    RadarWarningCount,
+    RadarWarningBearing0,
+    RadarWarningBearing1,
+    RadarWarningBearing2,
+    RadarWarningBearing3,
+    RadarWarningRssi0,
+    RadarWarningRssi1,
+    RadarWarningRssi2,
+    RadarWarningRssi3,
+
+    Energy,
+
     Size,
     MaxSize = 128,
 }
@@ -319,10 +331,26 @@ pub enum Ability {
     Decoy,
     /// Cruiser only. Deflects projectiles for 1s. Reloads in 5s.
     Shield,
+    /// Only in some scenarios. Reduces the radar cross section by 95% for 5s.
+    /// Reloads in 20s.
+    Cloak,
+    /// Only in some scenarios. Destroys the closest enemy missile or torpedo
+    /// within 1 km every tick for 1s. Reloads in 4s.
+    PointDefense,
+    /// Only in some scenarios. Pulls the closest ship within 2 km inside the
+    /// radar beam towards this ship for 5s. Reloads in 10s.
+    TractorBeam,
 }
 
 /// Array of all ability types.
-pub const ABILITIES: &[Ability] = &[Ability::Boost, Ability::Decoy, Ability::Shield];
+pub const ABILITIES: &[Ability] = &[
+    Ability::Boost,
+    Ability::Decoy,
+    Ability::Shield,
+    Ability::Cloak,
+    Ability::PointDefense,
+    Ability::TractorBeam,
+];
 
 /// Electronic Counter Measures (ECM) modes.
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -493,6 +521,9 @@ mod api {
     /// The time between each simulation tick.
     pub const TICK_LENGTH: f64 = 1.0 / 60.0;
 
+    /// The maximum number of radars reported by [`radar_warnings`].
+    pub const MAX_RADAR_WARNINGS: usize = 4;
+
     /// Returns a per-ship ID that is unique within a team.
     pub fn id() -> u32 {
         read_system_state(SystemState::Id) as u32
@@ -521,6 +552,15 @@ mod api {
             .unwrap_or(0.0)
     }
 
+    /// Returns the time between each simulation tick in this scenario.
+    ///
+    /// This is usually [`TICK_LENGTH`], but scenarios can change it.
+    pub fn tick_length() -> f64 {
+        super::sys::getenv("TICK_LENGTH")
+            .and_then(|x| x.parse().ok())
+            .unwrap_or(TICK_LENGTH)
+    }
+
     /// Returns the current position (in meters).
     pub fn position() -> Vec2 {
         vec2(
@@ -638,6 +678,14 @@ mod api {
         read_system_state(SystemState::Fuel)
     }
 
+    /// Returns the current energy.
+    ///
+    /// Guns, missile launchers, radar and abilities may draw from it, and it
+    /// regenerates over time. Returns infinity if the ship doesn't use energy.
+    pub fn energy() -> f64 {
+        read_system_state(SystemState::Energy)
+    }
+
     /// Returns the heading the radar is pointed at.
     pub fn radar_heading() -> f64 {
         read_system_state(SystemState::RadarHeading)
@@ -734,6 +782,62 @@ mod api {
         })
     }
 
+    /// An enemy radar illuminating this ship.
+    #[derive(Clone, Debug)]
+    pub struct RadarWarning {
+        /// The direction to the enemy radar (in radians).
+        pub bearing: f64,
+        /// The received signal strength measured in dBm.
+        pub rssi: f64,
+    }
+
+    /// Returns the enemy radars whose beams are pointed at this ship, strongest
+    /// first.
+    ///
+    /// This is a passive sensor and can pick up a radar from much further away
+    /// than that radar can detect this ship. At most [`MAX_RADAR_WARNINGS`]
+    /// radars are reported.
+    pub fn radar_warnings() -> Vec<RadarWarning> {
+        let count =
+            (read_system_state(SystemState::RadarWarningCount) as usize).min(MAX_RADAR_WARNINGS);
+        (0..count)
+            .map(|i| {
+                let (bearing, rssi) = radar_internal::warning_indices(i);
+                RadarWarning {
+                    bearing: read_system_state(bearing),
+                    rssi: read_system_state(rssi),
+                }
+            })
+            .collect()
+    }
+
+    #[doc(hidden)]
+    pub mod radar_internal {
+        use super::SystemState;
+
+        /// Returns the bearing and RSSI slots for a radar warning.
+        pub fn warning_indices(index: usize) -> (SystemState, SystemState) {
+            match index {
+                0 => (
+                    SystemState::RadarWarningBearing0,
+                    SystemState::RadarWarningRssi0,
+                ),
+                1 => (
+                    SystemState::RadarWarningBearing1,
+                    SystemState::RadarWarningRssi1,
+                ),
+                2 => (
+                    SystemState::RadarWarningBearing2,
+                    SystemState::RadarWarningRssi2,
+                ),
+                _ => (
+                    SystemState::RadarWarningBearing3,
+                    SystemState::RadarWarningRssi3,
+                ),
+            }
+        }
+    }
+
     #[doc(hidden)]
     pub mod radio_internal {
         use super::SystemState;
@@ -898,7 +1002,7 @@ mod api {
 
     /// Returns the number of seconds elapsed since the simulation began.
     pub fn current_time() -> f64 {
-        read_system_state(SystemState::CurrentTick) * TICK_LENGTH
+        read_system_state(SystemState::CurrentTick) * tick_length()
     }
 
     /// Activates a special ability.
@@ -1163,6 +1267,18 @@ pub mod dbg {
         }
     }
 
+    /// Takes the contents of the debug buffers, for simulating ships natively.
+    #[doc(hidden)]
+    pub fn take() -> (String, Vec<Line>, Vec<Text>) {
+        unsafe {
+            (
+                std::mem::take(&mut *ptr::addr_of_mut!(TEXT_BUFFER)),
+                std::mem::take(&mut *ptr::addr_of_mut!(LINE_BUFFER)),
+                std::mem::take(&mut *ptr::addr_of_mut!(DRAWN_TEXT_BUFFER)),
+            )
+        }
+    }
+
     #[doc(hidden)]
     pub fn reset() {
         unsafe {
@@ -0,0 +1,4 @@
+// language: c
+#include "oort.h"
+
+void tick(void) {}
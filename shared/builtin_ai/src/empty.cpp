@@ -0,0 +1,6 @@
+// language: cpp
+#include "oort.h"
+
+using namespace oort;
+
+void tick() {}
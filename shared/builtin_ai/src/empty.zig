@@ -0,0 +1,4 @@
+// language: zig
+const oort = @import("oort.zig");
+
+pub fn tick() void {}
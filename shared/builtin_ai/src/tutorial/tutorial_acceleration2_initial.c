@@ -0,0 +1,13 @@
+// language: c
+// Tutorial: Acceleration 2
+// Fly through the target circle. The target is in a random
+// location given by the "oort_target" function.
+//
+// You can add vectors together: vec2_add(vec2(a, b), vec2(c, d)) == vec2(a + c, b + d)
+// And subtract them: vec2_sub(vec2(a, b), vec2(c, d)) == vec2(a - c, b - d)
+#include "oort.h"
+
+void tick(void) {
+    // Hint: "vec2_sub(oort_target(), oort_position())" returns a vector pointing towards the target.
+    oort_accelerate(vec2(100.0, 0.0));
+}
@@ -0,0 +1,15 @@
+// language: cpp
+// Tutorial: Acceleration 2
+// Fly through the target circle. The target is in a random
+// location given by the "target" function.
+//
+// You can add vectors together: vec2(a, b) + vec2(c, d) == vec2(a + c, b + d)
+// And subtract them: vec2(a, b) - vec2(c, d) == vec2(a - c, b - d)
+#include "oort.h"
+
+using namespace oort;
+
+void tick() {
+    // Hint: "target() - position()" returns a vector pointing towards the target.
+    accelerate(vec2(100.0, 0.0));
+}
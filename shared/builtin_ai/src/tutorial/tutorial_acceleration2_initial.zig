@@ -0,0 +1,13 @@
+// language: zig
+// Tutorial: Acceleration 2
+// Fly through the target circle. The target is in a random
+// location given by the "oort.target" function.
+//
+// You can add vectors together: vec2(a, b).add(vec2(c, d)) == vec2(a + c, b + d)
+// And subtract them: vec2(a, b).sub(vec2(c, d)) == vec2(a - c, b - d)
+const oort = @import("oort.zig");
+
+pub fn tick() void {
+    // Hint: "oort.target().sub(oort.position())" returns a vector pointing towards the target.
+    oort.accelerate(oort.vec2(100.0, 0.0));
+}
@@ -0,0 +1,9 @@
+// language: c
+// Tutorial: Acceleration
+// Fly through the target circle.
+#include "oort.h"
+
+void tick(void) {
+    // Hint: uncomment me
+    // oort_accelerate(vec2(100.0, 0.0));
+}
@@ -0,0 +1,11 @@
+// language: cpp
+// Tutorial: Acceleration
+// Fly through the target circle.
+#include "oort.h"
+
+using namespace oort;
+
+void tick() {
+    // Hint: uncomment me
+    // accelerate(vec2(100.0, 0.0));
+}
@@ -0,0 +1,9 @@
+// language: zig
+// Tutorial: Acceleration
+// Fly through the target circle.
+const oort = @import("oort.zig");
+
+pub fn tick() void {
+    // Hint: uncomment me
+    // oort.accelerate(oort.vec2(100.0, 0.0));
+}
@@ -0,0 +1,33 @@
+// language: c
+// Tutorial: Cruiser
+// Destroy the enemy ships with your Cruiser.
+#include "oort.h"
+
+static void turn_to(double target_heading) {
+    double heading_error = oort_angle_diff(oort_heading(), target_heading);
+    oort_turn(10.0 * heading_error);
+}
+
+void tick(void) {
+    if (oort_class() == ShipClass_Missile) {
+        ScanResult contact;
+        if (oort_scan(&contact)) {
+            Vec2 dp = vec2_sub(contact.position, oort_position());
+            Vec2 dv = vec2_sub(contact.velocity, oort_velocity());
+            turn_to(vec2_angle(dp));
+            oort_accelerate(vec2_add(dp, dv));
+            if (vec2_length(dp) < 20.0) {
+                oort_explode();
+            }
+        }
+    } else {
+        // Main gun
+        oort_aim(0, 0.0);
+        oort_fire(0);
+        // Missile launcher
+        oort_fire(1);
+        oort_fire(2);
+        // Torpedo launcher
+        oort_fire(3);
+    }
+}
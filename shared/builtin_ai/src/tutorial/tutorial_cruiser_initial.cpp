@@ -0,0 +1,34 @@
+// language: cpp
+// Tutorial: Cruiser
+// Destroy the enemy ships with your Cruiser.
+#include "oort.h"
+
+using namespace oort;
+
+void turn_to(double target_heading) {
+    double heading_error = angle_diff(heading(), target_heading);
+    turn(10.0 * heading_error);
+}
+
+void tick() {
+    if (ship_class() == ShipClass::Missile) {
+        if (auto contact = scan()) {
+            Vec2 dp = contact->position - position();
+            Vec2 dv = contact->velocity - velocity();
+            turn_to(dp.angle());
+            accelerate(dp + dv);
+            if (dp.length() < 20.0) {
+                explode();
+            }
+        }
+    } else {
+        // Main gun
+        aim(0, 0.0);
+        fire(0);
+        // Missile launcher
+        fire(1);
+        fire(2);
+        // Torpedo launcher
+        fire(3);
+    }
+}
@@ -0,0 +1,32 @@
+// language: zig
+// Tutorial: Cruiser
+// Destroy the enemy ships with your Cruiser.
+const oort = @import("oort.zig");
+
+fn turnTo(target_heading: f64) void {
+    const heading_error = oort.angleDiff(oort.heading(), target_heading);
+    oort.turn(10.0 * heading_error);
+}
+
+pub fn tick() void {
+    if (oort.class() == .Missile) {
+        if (oort.scan()) |contact| {
+            const dp = contact.position.sub(oort.position());
+            const dv = contact.velocity.sub(oort.velocity());
+            turnTo(dp.angle());
+            oort.accelerate(dp.add(dv));
+            if (dp.length() < 20.0) {
+                oort.explode();
+            }
+        }
+    } else {
+        // Main gun
+        oort.aim(0, 0.0);
+        oort.fire(0);
+        // Missile launcher
+        oort.fire(1);
+        oort.fire(2);
+        // Torpedo launcher
+        oort.fire(3);
+    }
+}
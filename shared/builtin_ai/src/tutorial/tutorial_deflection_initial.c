@@ -0,0 +1,16 @@
+// language: c
+// Tutorial: Deflection
+// Destroy the enemy ship. Its position is given by the "oort_target" function and velocity by the
+// "oort_target_velocity" function.
+//
+// Hint: p = p₀ + v₀t + ½at² (the third equation of kinematics)
+//
+// p.s. You can change your username by clicking on it at the top of the page.
+#include "oort.h"
+
+const double BULLET_SPEED = 1000.0; // m/s
+
+void tick(void) {
+    oort_turn(1.0);
+    oort_fire(0);
+}
@@ -0,0 +1,18 @@
+// language: cpp
+// Tutorial: Deflection
+// Destroy the enemy ship. Its position is given by the "target" function and velocity by the
+// "target_velocity" function.
+//
+// Hint: p = p₀ + v₀t + ½at² (the third equation of kinematics)
+//
+// p.s. You can change your username by clicking on it at the top of the page.
+#include "oort.h"
+
+using namespace oort;
+
+const double BULLET_SPEED = 1000.0; // m/s
+
+void tick() {
+    turn(1.0);
+    fire(0);
+}
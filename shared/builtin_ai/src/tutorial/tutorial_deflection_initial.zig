@@ -0,0 +1,16 @@
+// language: zig
+// Tutorial: Deflection
+// Destroy the enemy ship. Its position is given by the "oort.target" function and velocity by the
+// "oort.targetVelocity" function.
+//
+// Hint: p = p₀ + v₀t + ½at² (the third equation of kinematics)
+//
+// p.s. You can change your username by clicking on it at the top of the page.
+const oort = @import("oort.zig");
+
+pub const BULLET_SPEED: f64 = 1000.0; // m/s
+
+pub fn tick() void {
+    oort.turn(1.0);
+    oort.fire(0);
+}
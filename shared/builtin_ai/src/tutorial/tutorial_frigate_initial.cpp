@@ -0,0 +1,35 @@
+// language: cpp
+// Tutorial: Frigate
+// Destroy the enemy ships with your Frigate.
+#include "oort.h"
+
+using namespace oort;
+
+void turn_to(double target_heading) {
+    double heading_error = angle_diff(heading(), target_heading);
+    turn(10.0 * heading_error);
+}
+
+void tick() {
+    if (ship_class() == ShipClass::Missile) {
+        if (auto contact = scan()) {
+            Vec2 dp = contact->position - position();
+            Vec2 dv = contact->velocity - velocity();
+            turn_to(dp.angle());
+            accelerate(dp + dv);
+            if (dp.length() < 20.0) {
+                explode();
+            }
+        }
+    } else {
+        // Main gun
+        fire(0);
+        // Turreted guns
+        aim(1, heading() + TAU / 4.0);
+        fire(1);
+        aim(2, heading() - TAU / 4.0);
+        fire(2);
+        // Missile launcher
+        fire(3);
+    }
+}
@@ -0,0 +1,33 @@
+// language: zig
+// Tutorial: Frigate
+// Destroy the enemy ships with your Frigate.
+const oort = @import("oort.zig");
+
+fn turnTo(target_heading: f64) void {
+    const heading_error = oort.angleDiff(oort.heading(), target_heading);
+    oort.turn(10.0 * heading_error);
+}
+
+pub fn tick() void {
+    if (oort.class() == .Missile) {
+        if (oort.scan()) |contact| {
+            const dp = contact.position.sub(oort.position());
+            const dv = contact.velocity.sub(oort.velocity());
+            turnTo(dp.angle());
+            oort.accelerate(dp.add(dv));
+            if (dp.length() < 20.0) {
+                oort.explode();
+            }
+        }
+    } else {
+        // Main gun
+        oort.fire(0);
+        // Turreted guns
+        oort.aim(1, oort.heading() + oort.TAU / 4.0);
+        oort.fire(1);
+        oort.aim(2, oort.heading() - oort.TAU / 4.0);
+        oort.fire(2);
+        // Missile launcher
+        oort.fire(3);
+    }
+}
@@ -0,0 +1,10 @@
+// language: c
+// Tutorial: Guns
+// Destroy the asteroid.
+//
+// Uncomment me, then press Ctrl-Enter (Cmd-Enter on Mac) to upload the code.
+#include "oort.h"
+
+void tick(void) {
+    // oort_fire(0);
+}
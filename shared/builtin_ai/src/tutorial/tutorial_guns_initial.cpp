@@ -0,0 +1,12 @@
+// language: cpp
+// Tutorial: Guns
+// Destroy the asteroid.
+//
+// Uncomment me, then press Ctrl-Enter (Cmd-Enter on Mac) to upload the code.
+#include "oort.h"
+
+using namespace oort;
+
+void tick() {
+    // fire(0);
+}
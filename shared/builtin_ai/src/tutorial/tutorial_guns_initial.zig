@@ -0,0 +1,10 @@
+// language: zig
+// Tutorial: Guns
+// Destroy the asteroid.
+//
+// Uncomment me, then press Ctrl-Enter (Cmd-Enter on Mac) to upload the code.
+const oort = @import("oort.zig");
+
+pub fn tick() void {
+    // oort.fire(0);
+}
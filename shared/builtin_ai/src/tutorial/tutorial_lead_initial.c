@@ -0,0 +1,25 @@
+// language: c
+// Tutorial: Lead
+// Destroy the enemy ship. Its position is given by the "oort_target" function and velocity by the
+// "oort_target_velocity" function. Your ship is not able to accelerate in this scenario.
+//
+// This is where the game becomes challenging! You'll need to lead the target
+// by firing towards where the target will be by the time the bullet gets there.
+//
+// Hint: vec2_add(oort_target(), vec2_scale(oort_target_velocity(), t)) gives the position of the target after t seconds.
+//
+// You can scale a vector by a number: vec2_scale(vec2(a, b), c) == vec2(a * c, b * c)
+//
+// p.s. You can change your username by clicking on it at the top of the page.
+#include "oort.h"
+
+const double BULLET_SPEED = 1000.0; // m/s
+
+void tick(void) {
+    oort_draw_line(oort_position(), oort_target(), 0x00ff00);
+    Vec2 dp = vec2_sub(oort_target(), oort_position());
+    oort_debug("distance to target: %f", vec2_length(dp));
+    oort_debug("time to target: %f", vec2_length(dp) / BULLET_SPEED);
+    oort_turn(1.0);
+    oort_fire(0);
+}
@@ -0,0 +1,27 @@
+// language: cpp
+// Tutorial: Lead
+// Destroy the enemy ship. Its position is given by the "target" function and velocity by the
+// "target_velocity" function. Your ship is not able to accelerate in this scenario.
+//
+// This is where the game becomes challenging! You'll need to lead the target
+// by firing towards where the target will be by the time the bullet gets there.
+//
+// Hint: target() + target_velocity() * t gives the position of the target after t seconds.
+//
+// You can scale a vector by a number: vec2(a, b) * c == vec2(a * c, b * c)
+//
+// p.s. You can change your username by clicking on it at the top of the page.
+#include "oort.h"
+
+using namespace oort;
+
+const double BULLET_SPEED = 1000.0; // m/s
+
+void tick() {
+    draw_line(position(), target(), 0x00ff00);
+    Vec2 dp = target() - position();
+    debug("distance to target: %f", dp.length());
+    debug("time to target: %f", dp.length() / BULLET_SPEED);
+    turn(1.0);
+    fire(0);
+}
@@ -0,0 +1,25 @@
+// language: zig
+// Tutorial: Lead
+// Destroy the enemy ship. Its position is given by the "oort.target" function and velocity by the
+// "oort.targetVelocity" function. Your ship is not able to accelerate in this scenario.
+//
+// This is where the game becomes challenging! You'll need to lead the target
+// by firing towards where the target will be by the time the bullet gets there.
+//
+// Hint: oort.target().add(oort.targetVelocity().scale(t)) gives the position of the target after t seconds.
+//
+// You can scale a vector by a number: oort.vec2(a, b).scale(c) == oort.vec2(a * c, b * c)
+//
+// p.s. You can change your username by clicking on it at the top of the page.
+const oort = @import("oort.zig");
+
+const BULLET_SPEED: f64 = 1000.0; // m/s
+
+pub fn tick() void {
+    oort.drawLine(oort.position(), oort.target(), 0x00ff00);
+    const dp = oort.target().sub(oort.position());
+    oort.debug("distance to target: {d}", .{dp.length()});
+    oort.debug("time to target: {d}", .{dp.length() / BULLET_SPEED});
+    oort.turn(1.0);
+    oort.fire(0);
+}
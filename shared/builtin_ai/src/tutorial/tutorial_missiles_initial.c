@@ -0,0 +1,27 @@
+// language: c
+// Tutorial: Missiles
+// Destroy the enemy ship with your missiles.
+// Hint: https://en.wikipedia.org/wiki/Proportional_navigation
+#include "oort.h"
+
+static void turn_to(double target_heading) {
+    double heading_error = oort_angle_diff(oort_heading(), target_heading);
+    oort_turn(10.0 * heading_error);
+}
+
+void tick(void) {
+    if (oort_class() == ShipClass_Missile) {
+        ScanResult contact;
+        if (oort_scan(&contact)) {
+            Vec2 dp = vec2_sub(contact.position, oort_position());
+            Vec2 dv = vec2_sub(contact.velocity, oort_velocity());
+            turn_to(vec2_angle(dp));
+            oort_accelerate(vec2_add(dp, dv));
+            if (vec2_length(dp) < 20.0) {
+                oort_explode();
+            }
+        }
+    } else {
+        oort_fire(1);
+    }
+}
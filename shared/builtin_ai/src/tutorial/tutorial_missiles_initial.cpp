@@ -0,0 +1,28 @@
+// language: cpp
+// Tutorial: Missiles
+// Destroy the enemy ship with your missiles.
+// Hint: https://en.wikipedia.org/wiki/Proportional_navigation
+#include "oort.h"
+
+using namespace oort;
+
+void turn_to(double target_heading) {
+    double heading_error = angle_diff(heading(), target_heading);
+    turn(10.0 * heading_error);
+}
+
+void tick() {
+    if (ship_class() == ShipClass::Missile) {
+        if (auto contact = scan()) {
+            Vec2 dp = contact->position - position();
+            Vec2 dv = contact->velocity - velocity();
+            turn_to(dp.angle());
+            accelerate(dp + dv);
+            if (dp.length() < 20.0) {
+                explode();
+            }
+        }
+    } else {
+        fire(1);
+    }
+}
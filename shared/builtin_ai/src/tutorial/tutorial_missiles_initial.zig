@@ -0,0 +1,26 @@
+// language: zig
+// Tutorial: Missiles
+// Destroy the enemy ship with your missiles.
+// Hint: https://en.wikipedia.org/wiki/Proportional_navigation
+const oort = @import("oort.zig");
+
+fn turnTo(target_heading: f64) void {
+    const heading_error = oort.angleDiff(oort.heading(), target_heading);
+    oort.turn(10.0 * heading_error);
+}
+
+pub fn tick() void {
+    if (oort.class() == .Missile) {
+        if (oort.scan()) |contact| {
+            const dp = contact.position.sub(oort.position());
+            const dv = contact.velocity.sub(oort.velocity());
+            turnTo(dp.angle());
+            oort.accelerate(dp.add(dv));
+            if (dp.length() < 20.0) {
+                oort.explode();
+            }
+        }
+    } else {
+        oort.fire(1);
+    }
+}
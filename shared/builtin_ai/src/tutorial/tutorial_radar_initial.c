@@ -0,0 +1,22 @@
+// language: c
+// Tutorial: Radar
+// Destroy the enemy ships. Use your radar to find them.
+// Hint: Press 'g' in-game to show where your radar is looking.
+// Hint: Press 'n' to single-step.
+// Hint: Use the oort_set_radar_heading() function to keep your radar pointed at a
+// target, or to search for a new one.
+//
+// Join the Discord at https://discord.gg/vYyu9EhkKH for Oort discussion and
+// tournament results.
+#include "oort.h"
+
+const double BULLET_SPEED = 1000.0; // m/s
+
+void tick(void) {
+    oort_set_radar_heading(oort_radar_heading() + oort_radar_width());
+    ScanResult contact;
+    if (oort_scan(&contact)) {
+        oort_accelerate(vec2_scale(vec2_sub(contact.position, oort_position()), 0.1));
+        oort_fire(0);
+    }
+}
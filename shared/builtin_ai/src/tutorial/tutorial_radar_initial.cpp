@@ -0,0 +1,23 @@
+// language: cpp
+// Tutorial: Radar
+// Destroy the enemy ships. Use your radar to find them.
+// Hint: Press 'g' in-game to show where your radar is looking.
+// Hint: Press 'n' to single-step.
+// Hint: Use the set_radar_heading() function to keep your radar pointed at a
+// target, or to search for a new one.
+//
+// Join the Discord at https://discord.gg/vYyu9EhkKH for Oort discussion and
+// tournament results.
+#include "oort.h"
+
+using namespace oort;
+
+const double BULLET_SPEED = 1000.0; // m/s
+
+void tick() {
+    set_radar_heading(radar_heading() + radar_width());
+    if (auto contact = scan()) {
+        accelerate(0.1 * (contact->position - position()));
+        fire(0);
+    }
+}
@@ -0,0 +1,21 @@
+// language: zig
+// Tutorial: Radar
+// Destroy the enemy ships. Use your radar to find them.
+// Hint: Press 'g' in-game to show where your radar is looking.
+// Hint: Press 'n' to single-step.
+// Hint: Use the oort.setRadarHeading() function to keep your radar pointed at a
+// target, or to search for a new one.
+//
+// Join the Discord at https://discord.gg/vYyu9EhkKH for Oort discussion and
+// tournament results.
+const oort = @import("oort.zig");
+
+pub const BULLET_SPEED: f64 = 1000.0; // m/s
+
+pub fn tick() void {
+    oort.setRadarHeading(oort.radarHeading() + oort.radarWidth());
+    if (oort.scan()) |contact| {
+        oort.accelerate(contact.position.sub(oort.position()).scale(0.1));
+        oort.fire(0);
+    }
+}
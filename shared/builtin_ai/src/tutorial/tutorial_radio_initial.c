@@ -0,0 +1,15 @@
+// language: c
+// Tutorial: Radio
+// Destroy the enemy ship. Your radar is broken, but a radio signal on channel
+// 2 will give you its position and velocity.
+#include "oort.h"
+
+void tick(void) {
+    oort_set_radio_channel(0);
+    Message msg;
+    if (oort_receive(&msg)) {
+        oort_debug("msg: [%f, %f, %f, %f]", msg.data[0], msg.data[1], msg.data[2], msg.data[3]);
+    } else {
+        oort_debug("no message received");
+    }
+}
@@ -0,0 +1,16 @@
+// language: cpp
+// Tutorial: Radio
+// Destroy the enemy ship. Your radar is broken, but a radio signal on channel
+// 2 will give you its position and velocity.
+#include "oort.h"
+
+using namespace oort;
+
+void tick() {
+    set_radio_channel(0);
+    if (auto msg = receive()) {
+        debug("msg: [%f, %f, %f, %f]", (*msg)[0], (*msg)[1], (*msg)[2], (*msg)[3]);
+    } else {
+        debug("no message received");
+    }
+}
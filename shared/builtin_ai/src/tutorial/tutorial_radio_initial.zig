@@ -0,0 +1,14 @@
+// language: zig
+// Tutorial: Radio
+// Destroy the enemy ship. Your radar is broken, but a radio signal on channel
+// 2 will give you its position and velocity.
+const oort = @import("oort.zig");
+
+pub fn tick() void {
+    oort.setRadioChannel(0);
+    if (oort.receive()) |msg| {
+        oort.debug("msg: {any}", .{msg});
+    } else {
+        oort.debug("no message received", .{});
+    }
+}
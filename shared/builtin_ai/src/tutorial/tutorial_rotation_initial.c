@@ -0,0 +1,19 @@
+// language: c
+// Tutorial: Rotation
+// Destroy the asteroid. The target is in a random
+// location given by the "oort_target()" function.
+//
+// You can get the angle between a vector and the x-axis: vec2_angle(vec2(a, b))
+// And compare an angle with your ship's heading: oort_angle_diff(oort_heading(), angle)
+//
+// If oort_angle_diff returns a positive number you need to turn left, or if negative then right.
+// The turn function takes a speed argument, where positive speeds result in turning left
+// and negative speeds will turn right.
+#include "oort.h"
+
+void tick(void) {
+    // Hint: "oort_angle_diff(oort_heading(), vec2_angle(vec2_sub(oort_target(), oort_position())))"
+    // returns the direction your ship needs to turn to face the target.
+    oort_turn(1.0);
+    oort_fire(0);
+}
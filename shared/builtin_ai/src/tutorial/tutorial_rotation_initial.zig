@@ -0,0 +1,19 @@
+// language: zig
+// Tutorial: Rotation
+// Destroy the asteroid. The target is in a random
+// location given by the "oort.target()" function.
+//
+// You can get the angle between a vector and the x-axis: oort.vec2(a, b).angle()
+// And compare an angle with your ship's heading: oort.angleDiff(oort.heading(), angle)
+//
+// If angleDiff returns a positive number you need to turn left, or if negative then right.
+// The turn function takes a speed argument, where positive speeds result in turning left
+// and negative speeds will turn right.
+const oort = @import("oort.zig");
+
+pub fn tick() void {
+    // Hint: "oort.angleDiff(oort.heading(), oort.target().sub(oort.position()).angle())"
+    // returns the direction your ship needs to turn to face the target.
+    oort.turn(1.0);
+    oort.fire(0);
+}
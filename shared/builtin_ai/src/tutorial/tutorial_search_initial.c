@@ -0,0 +1,15 @@
+// language: c
+// Tutorial: Search
+// Destroy the enemy ship. It is initially outside of your radar range.
+// Hint: The oort_set_radar_width() function can be used to create a tighter radar
+// beam that's effective at longer distances.
+#include "oort.h"
+
+void tick(void) {
+    oort_set_radar_heading(oort_radar_heading() + OORT_TAU / 6.0);
+    ScanResult contact;
+    if (oort_scan(&contact)) {
+        oort_accelerate(vec2_scale(vec2_sub(contact.position, oort_position()), 0.1));
+        oort_fire(0);
+    }
+}
@@ -0,0 +1,16 @@
+// language: cpp
+// Tutorial: Search
+// Destroy the enemy ship. It is initially outside of your radar range.
+// Hint: The set_radar_width() function can be used to create a tighter radar
+// beam that's effective at longer distances.
+#include "oort.h"
+
+using namespace oort;
+
+void tick() {
+    set_radar_heading(radar_heading() + TAU / 6.0);
+    if (auto contact = scan()) {
+        accelerate(0.1 * (contact->position - position()));
+        fire(0);
+    }
+}
@@ -0,0 +1,14 @@
+// language: zig
+// Tutorial: Search
+// Destroy the enemy ship. It is initially outside of your radar range.
+// Hint: The oort.setRadarWidth() function can be used to create a tighter radar
+// beam that's effective at longer distances.
+const oort = @import("oort.zig");
+
+pub fn tick() void {
+    oort.setRadarHeading(oort.radarHeading() + oort.TAU / 6.0);
+    if (oort.scan()) |contact| {
+        oort.accelerate(contact.position.sub(oort.position()).scale(0.1));
+        oort.fire(0);
+    }
+}
@@ -0,0 +1,13 @@
+// language: c
+// Tutorial: Squadron
+// Destroy the enemy ships. They now shoot back.
+#include "oort.h"
+
+void tick(void) {
+    oort_set_radar_heading(oort_radar_heading() + OORT_TAU / 6.0);
+    ScanResult contact;
+    if (oort_scan(&contact)) {
+        oort_accelerate(vec2_scale(vec2_sub(contact.position, oort_position()), 0.1));
+        oort_fire(0);
+    }
+}
@@ -0,0 +1,14 @@
+// language: cpp
+// Tutorial: Squadron
+// Destroy the enemy ships. They now shoot back.
+#include "oort.h"
+
+using namespace oort;
+
+void tick() {
+    set_radar_heading(radar_heading() + TAU / 6.0);
+    if (auto contact = scan()) {
+        accelerate(0.1 * (contact->position - position()));
+        fire(0);
+    }
+}
@@ -0,0 +1,12 @@
+// language: zig
+// Tutorial: Squadron
+// Destroy the enemy ships. They now shoot back.
+const oort = @import("oort.zig");
+
+pub fn tick() void {
+    oort.setRadarHeading(oort.radarHeading() + oort.TAU / 6.0);
+    if (oort.scan()) |contact| {
+        oort.accelerate(contact.position.sub(oort.position()).scale(0.1));
+        oort.fire(0);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::Language;
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+pub const DEFAULT_MAX_BYTES: u64 = 256 << 20;
+
+/// Content-addressed store of compiled WASM.
+///
+/// Entries are keyed by a hash of the source code, its language and the
+/// compiler version, so a toolchain upgrade never returns stale output. The
+/// least recently used entries are evicted once the total size exceeds
+/// `max_bytes`.
+pub struct Cache {
+    dir: PathBuf,
+    max_bytes: u64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub bytes: u64,
+    pub max_bytes: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl Cache {
+    pub fn new(dir: &Path, max_bytes: u64) -> Result<Cache> {
+        fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            max_bytes,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        })
+    }
+
+    pub fn key(language: Language, compiler_version: &str, code: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        hasher.update([0]);
+        hasher.update(language.name().as_bytes());
+        hasher.update([0]);
+        hasher.update(compiler_version.as_bytes());
+        hasher.update([0]);
+        hasher.update(code.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.wasm"))
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.path(key);
+        match fs::read(&path) {
+            Ok(wasm) => {
+                // Mark as recently used.
+                if let Ok(file) = fs::File::options().write(true).open(&path) {
+                    let _ = file.set_modified(SystemTime::now());
+                }
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                log::debug!("Compiler cache hit for {}", key);
+                Some(wasm)
+            }
+            Err(_) => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                log::debug!("Compiler cache miss for {}", key);
+                None
+            }
+        }
+    }
+
+    pub fn put(&self, key: &str, wasm: &[u8]) -> Result<()> {
+        if wasm.len() as u64 > self.max_bytes {
+            return Ok(());
+        }
+        // Write then rename so concurrent readers never see a partial file.
+        let tmp_path = self.dir.join(format!("{key}.tmp"));
+        fs::write(&tmp_path, wasm)?;
+        fs::rename(&tmp_path, self.path(key))?;
+        self.evict()
+    }
+
+    /// Removes least recently used entries until the cache fits in `max_bytes`.
+    fn evict(&self) -> Result<()> {
+        let mut entries = self.entries()?;
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        if total <= self.max_bytes {
+            return Ok(());
+        }
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        for (path, len, _) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            log::debug!("Evicting {:?} from compiler cache", path);
+            if fs::remove_file(&path).is_ok() {
+                total -= len;
+            }
+        }
+        Ok(())
+    }
+
+    fn entries(&self) -> Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let mut entries = vec![];
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |x| x != "wasm") {
+                continue;
+            }
+            // Another process may have evicted the file already.
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+            entries.push((path, metadata.len(), metadata.modified()?));
+        }
+        Ok(entries)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let entries = self.entries().unwrap_or_default();
+        CacheStats {
+            entries: entries.len(),
+            bytes: entries.iter().map(|(_, len, _)| len).sum(),
+            max_bytes: self.max_bytes,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_key() {
+        use Language::*;
+        assert_eq!(
+            Cache::key(Rust, "rustc 1", "a"),
+            Cache::key(Rust, "rustc 1", "a")
+        );
+        assert_ne!(
+            Cache::key(Rust, "rustc 1", "a"),
+            Cache::key(Rust, "rustc 1", "b")
+        );
+        assert_ne!(
+            Cache::key(Rust, "rustc 1", "a"),
+            Cache::key(Rust, "rustc 2", "a")
+        );
+        // C and C++ share a compiler, and the same source compiles differently.
+        assert_ne!(
+            Cache::key(C, "clang 1", "a"),
+            Cache::key(Cpp, "clang 1", "a")
+        );
+    }
+
+    #[test]
+    fn test_get_put() {
+        let dir = tempdir::TempDir::new("oort_compiler_cache").unwrap();
+        let cache = Cache::new(dir.path(), 1000).unwrap();
+        assert_eq!(cache.get("a"), None);
+        cache.put("a", b"wasm").unwrap();
+        assert_eq!(cache.get("a"), Some(b"wasm".to_vec()));
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.bytes), (1, 4));
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+    }
+
+    #[test]
+    fn test_eviction() {
+        let dir = tempdir::TempDir::new("oort_compiler_cache").unwrap();
+        let cache = Cache::new(dir.path(), 250).unwrap();
+        cache.put("a", &[0; 100]).unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        cache.put("b", &[0; 100]).unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(cache.get("a").is_some());
+        std::thread::sleep(Duration::from_millis(10));
+        cache.put("c", &[0; 100]).unwrap();
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+        assert!(cache.stats().bytes <= 250);
+
+        cache.put("d", &[0; 1000]).unwrap();
+        assert!(cache.get("d").is_none());
+    }
+}
@@ -0,0 +1,164 @@
+use oort_proto::{CompilerDiagnostics, Diagnostic, Severity, Suggestion};
+use serde::Deserialize;
+
+/// Directory of the AI crate's sources in the rustc output.
+const SRC_DIR: &str = "ai/src/";
+
+/// Error returned when rustc rejects the user's code.
+///
+/// Displays as the plain-text compiler output; services can downcast an
+/// `anyhow::Error` to this to return the structured diagnostics.
+#[derive(Debug, Clone)]
+pub struct CompileError {
+    pub diagnostics: CompilerDiagnostics,
+}
+
+impl std::fmt::Display for CompileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rustc failed: {}", self.diagnostics.rendered)
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+// Subset of rustc's --error-format=json output.
+#[derive(Deserialize)]
+struct RustcDiagnostic {
+    message: String,
+    code: Option<RustcCode>,
+    level: String,
+    spans: Vec<RustcSpan>,
+    children: Vec<RustcDiagnostic>,
+    rendered: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RustcCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct RustcSpan {
+    file_name: String,
+    line_start: usize,
+    line_end: usize,
+    column_start: usize,
+    column_end: usize,
+    is_primary: bool,
+    suggested_replacement: Option<String>,
+}
+
+/// Parses rustc's JSON diagnostics, naming files relative to the AI crate's
+/// sources.
+pub fn parse(stderr: &str) -> CompilerDiagnostics {
+    let mut result = CompilerDiagnostics::default();
+    for line in stderr.lines() {
+        let Ok(diagnostic) = serde_json::from_str::<RustcDiagnostic>(line) else {
+            if !line.trim().is_empty() {
+                result.rendered.push_str(line);
+                result.rendered.push('\n');
+            }
+            continue;
+        };
+        if let Some(rendered) = &diagnostic.rendered {
+            result.rendered.push_str(rendered);
+        }
+        let Some(span) = primary_span(&diagnostic.spans) else {
+            continue;
+        };
+        let suggestions = diagnostic
+            .children
+            .iter()
+            .flat_map(|child| {
+                child.spans.iter().filter_map(|span| {
+                    let replacement = span.suggested_replacement.clone()?;
+                    Some(Suggestion {
+                        message: child.message.clone(),
+                        replacement,
+                        file: file_name(span),
+                        line: span.line_start,
+                        column: span.column_start,
+                        end_line: span.line_end,
+                        end_column: span.column_end,
+                    })
+                })
+            })
+            .collect();
+        result.diagnostics.push(Diagnostic {
+            severity: severity(&diagnostic.level),
+            code: diagnostic.code.map(|x| x.code),
+            message: diagnostic.message,
+            file: file_name(span),
+            line: span.line_start,
+            column: span.column_start,
+            end_line: span.line_end,
+            end_column: span.column_end,
+            suggestions,
+        });
+    }
+    result
+}
+
+/// Prefers the primary span in the AI crate.
+fn primary_span(spans: &[RustcSpan]) -> Option<&RustcSpan> {
+    spans
+        .iter()
+        .find(|x| x.is_primary && x.file_name.contains(SRC_DIR))
+        .or_else(|| spans.iter().find(|x| x.is_primary))
+        .or_else(|| spans.first())
+}
+
+fn file_name(span: &RustcSpan) -> String {
+    match span.file_name.split_once(SRC_DIR) {
+        Some((_, path)) => path.to_string(),
+        None => span.file_name.clone(),
+    }
+}
+
+fn severity(level: &str) -> Severity {
+    match level {
+        "warning" => Severity::Warning,
+        "note" | "failure-note" => Severity::Note,
+        "help" => Severity::Help,
+        _ => Severity::Error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let stderr = r#"{"$message_type":"diagnostic","message":"mismatched types","code":{"code":"E0308","explanation":"..."},"level":"error","spans":[{"file_name":"/tmp/oort-ai/ai/src/foo/bar.rs","byte_start":0,"byte_end":1,"line_start":2,"line_end":2,"column_start":18,"column_end":21,"is_primary":true,"text":[],"label":"expected `u32`, found `&str`","suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[{"message":"try using a conversion method","code":null,"level":"help","spans":[{"file_name":"/tmp/oort-ai/ai/src/foo/bar.rs","byte_start":0,"byte_end":1,"line_start":2,"line_end":2,"column_start":18,"column_end":21,"is_primary":true,"text":[],"label":null,"suggested_replacement":"\"a\".parse().unwrap()","suggestion_applicability":"MaybeIncorrect","expansion":null}],"children":[],"rendered":null}],"rendered":"error[E0308]: mismatched types\n"}
+{"$message_type":"diagnostic","message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"error: aborting due to 1 previous error\n"}
+"#;
+        let result = parse(stderr);
+        assert_eq!(
+            result.rendered,
+            "error[E0308]: mismatched types\nerror: aborting due to 1 previous error\n"
+        );
+        assert_eq!(
+            result.diagnostics,
+            vec![Diagnostic {
+                severity: Severity::Error,
+                code: Some("E0308".to_string()),
+                message: "mismatched types".to_string(),
+                file: "foo/bar.rs".to_string(),
+                line: 2,
+                column: 18,
+                end_line: 2,
+                end_column: 21,
+                suggestions: vec![Suggestion {
+                    message: "try using a conversion method".to_string(),
+                    replacement: "\"a\".parse().unwrap()".to_string(),
+                    file: "foo/bar.rs".to_string(),
+                    line: 2,
+                    column: 18,
+                    end_line: 2,
+                    end_column: 21,
+                }],
+            }]
+        );
+    }
+}
@@ -1,14 +1,33 @@
+mod cache;
+mod diagnostics;
+mod sandbox;
 mod sanitizer;
 
-use anyhow::{bail, Result};
+pub use cache::{Cache, CacheStats};
+pub use diagnostics::CompileError;
+pub use sandbox::{check_network_isolation, LimitExceeded, Limits};
+
+use anyhow::{anyhow, bail, Result};
+use base64::Engine;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Prefix of source code that is a base64-encoded WASM module built elsewhere.
+pub const WASM_PREFIX: &str = "WASM:";
 
 pub struct Compiler {
     #[allow(dead_code)]
     tmp_dir: Option<tempdir::TempDir>,
     dir: PathBuf,
     offline: bool,
+    limits: Limits,
     rustc: String,
+    cache: Option<Arc<Cache>>,
+    versions: HashMap<Language, String>,
 }
 
 #[allow(clippy::new_without_default)]
@@ -20,7 +39,10 @@ impl Compiler {
             tmp_dir: Some(tmp_dir),
             dir,
             offline: true,
+            limits: Limits::default(),
             rustc: find_rustc(),
+            cache: cache_from_env(),
+            versions: HashMap::new(),
         }
     }
 
@@ -29,7 +51,10 @@ impl Compiler {
             tmp_dir: None,
             dir: dir.to_path_buf(),
             offline: true,
+            limits: Limits::default(),
             rustc: find_rustc(),
+            cache: cache_from_env(),
+            versions: HashMap::new(),
         }
     }
 
@@ -37,12 +62,84 @@ impl Compiler {
         self.offline = false;
     }
 
+    /// Sets the resource limits for processes compiling user code.
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    pub fn set_cache(&mut self, cache: Arc<Cache>) {
+        self.cache = Some(cache);
+    }
+
+    pub fn cache(&self) -> Option<&Arc<Cache>> {
+        self.cache.as_ref()
+    }
+
+    /// Compiles code in the language named by its `// language:` directive, or
+    /// Rust if there is none.
     pub fn compile(&mut self, code: &str) -> Result<Vec<u8> /* wasm */> {
-        match detect_language(code) {
+        if let Some(wasm) = decode_wasm(code) {
+            return wasm;
+        }
+        let language = detect_language(code)?;
+        self.compile_language(code, language)
+    }
+
+    pub fn compile_language(
+        &mut self,
+        code: &str,
+        language: Language,
+    ) -> Result<Vec<u8> /* wasm */> {
+        let key = if self.cache.is_some() {
+            self.version(language)
+                .map(|version| Cache::key(language, &version, code))
+        } else {
+            None
+        };
+        if let (Some(cache), Some(key)) = (&self.cache, &key) {
+            if let Some(wasm) = cache.get(key) {
+                return Ok(wasm);
+            }
+        }
+
+        let wasm = match language {
             Language::Rust => self.compile_rust(code),
             Language::Cpp => self.compile_cpp(code),
-            Language::Unknown => bail!("Unknown language"),
+            Language::C => self.compile_c(code),
+            Language::Zig => self.compile_zig(code),
+        }?;
+
+        if let (Some(cache), Some(key)) = (&self.cache, &key) {
+            if let Err(e) = cache.put(key, &wasm) {
+                log::warn!("Failed to write to compiler cache: {:?}", e);
+            }
+        }
+        Ok(wasm)
+    }
+
+    /// Returns the toolchain version used for the given language, as part of the
+    /// cache key. Returns None if it can't be determined, in which case results
+    /// must not be cached.
+    pub fn version(&mut self, language: Language) -> Option<String> {
+        if let Some(version) = self.versions.get(&language) {
+            return Some(version.clone());
+        }
+        let (program, args) = match language {
+            Language::Rust => (self.rustc.as_str(), ["-vV"]),
+            Language::Cpp | Language::C | Language::Zig => ("zig", ["version"]),
+        };
+        let version = match std::process::Command::new(program).args(args).output() {
+            Ok(output) if output.status.success() => {
+                String::from_utf8_lossy(&output.stdout).trim().to_string()
+            }
+            _ => String::new(),
+        };
+        if version.is_empty() {
+            log::warn!("Failed to get {} version, not caching", program);
+            return None;
         }
+        self.versions.insert(language, version.clone());
+        Some(version)
     }
 
     pub fn compile_rust(&mut self, code: &str) -> Result<Vec<u8> /* wasm */> {
@@ -83,10 +180,7 @@ impl Compiler {
         )?;
 
         std::fs::create_dir_all(tmp_path.join("ai/src"))?;
-        std::fs::write(
-            tmp_path.join("ai/Cargo.toml"),
-            include_bytes!("../../ai/Cargo.toml"),
-        )?;
+        std::fs::write(tmp_path.join("ai/Cargo.toml"), ai_manifest())?;
         std::fs::write(
             tmp_path.join("ai/src/lib.rs"),
             include_bytes!("../../ai/src/lib.rs"),
@@ -95,27 +189,33 @@ impl Compiler {
             tmp_path.join("ai/src/tick.rs"),
             include_bytes!("../../ai/src/tick.rs"),
         )?;
-        std::fs::write(tmp_path.join("ai/src/user.rs"), code.as_bytes())?;
-
-        let output = std::process::Command::new("cargo")
-            .args([
-                "build",
-                "--manifest-path",
-                tmp_path.join("Cargo.toml").as_os_str().to_str().unwrap(),
-                "--target-dir",
-                tmp_path.join("target").as_os_str().to_str().unwrap(),
-                "-v",
-                "-j1",
-                if self.offline { "--offline" } else { "-v" },
-                "--release",
-                "--target",
-                "wasm32-unknown-unknown",
-            ])
-            .env(
-                "RUSTFLAGS",
-                "-C opt-level=s -C link-arg=-zstack-size=16384 -C llvm-args=-rng-seed=42",
-            )
-            .output()?;
+        self.write_user_code(code)?;
+
+        let limits = Limits {
+            network: !self.offline,
+            ..self.limits.clone()
+        };
+        let output = sandbox::run(
+            std::process::Command::new("cargo")
+                .args([
+                    "build",
+                    "--manifest-path",
+                    tmp_path.join("Cargo.toml").as_os_str().to_str().unwrap(),
+                    "--target-dir",
+                    tmp_path.join("target").as_os_str().to_str().unwrap(),
+                    "-v",
+                    "-j1",
+                    if self.offline { "--offline" } else { "-v" },
+                    "--release",
+                    "--target",
+                    "wasm32-unknown-unknown",
+                ])
+                .env(
+                    "RUSTFLAGS",
+                    "-C opt-level=s -C link-arg=-zstack-size=16384 -C llvm-args=-rng-seed=42",
+                ),
+            &limits,
+        )?;
         if !output.status.success() {
             bail!("cargo failed: {}", std::str::from_utf8(&output.stderr)?);
         }
@@ -125,60 +225,76 @@ impl Compiler {
 
     pub fn compile_rust_fast(&mut self, code: &str) -> Result<Vec<u8> /* wasm */> {
         let tmp_path = &self.dir;
-        std::fs::write(tmp_path.join("ai/src/user.rs"), code.as_bytes())?;
+        self.write_user_code(code)?;
         let rustc_bin_dir = Path::new(&self.rustc).parent().unwrap();
 
-        let output = std::process::Command::new(&self.rustc)
-            .env(
-                "LD_LIBRARY_PATH",
-                &format!("{}/../lib", rustc_bin_dir.display()),
-            )
-            .args([
-                "--crate-name",
-                "oort_ai",
-                "--edition=2021",
-                tmp_path.join("ai/src/lib.rs").as_os_str().to_str().unwrap(),
-                "--crate-type",
-                "cdylib",
-                "-o",
-                tmp_path
-                    .join("target/wasm32-unknown-unknown/release/oort_ai.wasm")
-                    .as_os_str()
-                    .to_str()
-                    .unwrap(),
-                "--target",
-                "wasm32-unknown-unknown",
-                "-C",
-                "strip=debuginfo",
-                "-L",
-                &format!(
-                    "dependency={}",
+        let externs = allowed_crates()
+            .iter()
+            .map(|name| {
+                let name = name.replace('-', "_");
+                let rlib = find_rlib(tmp_path, &name);
+                format!("{}={}", name, rlib.display())
+            })
+            .collect::<Vec<_>>();
+
+        let output = sandbox::run(
+            std::process::Command::new(&self.rustc)
+                .env(
+                    "LD_LIBRARY_PATH",
+                    &format!("{}/../lib", rustc_bin_dir.display()),
+                )
+                .args([
+                    "--crate-name",
+                    "oort_ai",
+                    "--edition=2021",
+                    tmp_path.join("ai/src/lib.rs").as_os_str().to_str().unwrap(),
+                    "--crate-type",
+                    "cdylib",
+                    "-o",
                     tmp_path
-                        .join("target/wasm32-unknown-unknown/release/deps")
+                        .join("target/wasm32-unknown-unknown/release/oort_ai.wasm")
                         .as_os_str()
                         .to_str()
-                        .unwrap()
-                ),
-                "--extern",
-                &format!(
-                    "oort_api={}",
-                    find_rlib(tmp_path, "oort_api")
-                        .as_os_str()
-                        .to_str()
-                        .unwrap()
-                ),
-                "-C",
-                "opt-level=s",
-                "-C",
-                "link-arg=-zstack-size=16384",
-                "-C",
-                "llvm-args=-rng-seed=42",
-                "--remap-path-prefix",
-                &format!("{}=/tmp/oort-ai", tmp_path.display()),
-            ])
-            .output()?;
+                        .unwrap(),
+                    "--target",
+                    "wasm32-unknown-unknown",
+                    "-C",
+                    "strip=debuginfo",
+                    "-L",
+                    &format!(
+                        "dependency={}",
+                        tmp_path
+                            .join("target/wasm32-unknown-unknown/release/deps")
+                            .as_os_str()
+                            .to_str()
+                            .unwrap()
+                    ),
+                    "--extern",
+                    &format!(
+                        "oort_api={}",
+                        find_rlib(tmp_path, "oort_api")
+                            .as_os_str()
+                            .to_str()
+                            .unwrap()
+                    ),
+                    "-C",
+                    "opt-level=s",
+                    "-C",
+                    "link-arg=-zstack-size=16384",
+                    "-C",
+                    "llvm-args=-rng-seed=42",
+                    "--remap-path-prefix",
+                    &format!("{}=/tmp/oort-ai", tmp_path.display()),
+                    "--error-format=json",
+                ])
+                .args(externs.iter().flat_map(|x| ["--extern", x])),
+            &self.limits,
+        )?;
         if !output.status.success() {
-            bail!("rustc failed: {}", std::str::from_utf8(&output.stderr)?);
+            return Err(CompileError {
+                diagnostics: diagnostics::parse(std::str::from_utf8(&output.stderr)?),
+            }
+            .into());
         }
 
         Ok(std::fs::read(tmp_path.join(
@@ -186,6 +302,36 @@ impl Compiler {
         ))?)
     }
 
+    /// Writes the user's code to the AI crate, splitting multi-file code into
+    /// a module tree so rustc resolves `mod foo;` declarations itself.
+    fn write_user_code(&self, code: &str) -> Result<()> {
+        let src_path = self.dir.join("ai/src");
+
+        // Remove modules left over from the previous compile.
+        for entry in std::fs::read_dir(&src_path)? {
+            let entry = entry?;
+            if RESERVED_FILES.iter().any(|x| entry.file_name() == *x) {
+                continue;
+            }
+            if entry.file_type()?.is_dir() {
+                std::fs::remove_dir_all(entry.path())?;
+            } else {
+                std::fs::remove_file(entry.path())?;
+            }
+        }
+
+        let mut files = oort_multifile::split(code);
+        let lib = files.remove("lib.rs").unwrap();
+        for (path, src) in files {
+            check_module_path(&path)?;
+            let path = src_path.join(path);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, src)?;
+        }
+        std::fs::write(src_path.join("user.rs"), lib)?;
+        Ok(())
+    }
+
     pub fn compile_cpp(&mut self, code: &str) -> Result<Vec<u8> /* wasm */> {
         let tmp_path = &self.dir;
         let src_path = tmp_path.join("user.cpp");
@@ -195,13 +341,17 @@ impl Compiler {
             tmp_path.join("oort.h"),
             include_bytes!("../../cpp-api/oort.h"),
         )?;
+        std::fs::write(
+            tmp_path.join("oort_prelude.h"),
+            include_bytes!("../../cpp-api/oort_prelude.h"),
+        )?;
         std::fs::write(
             tmp_path.join("oort.cpp"),
             include_bytes!("../../cpp-api/oort.cpp"),
         )?;
 
-        let output = std::process::Command::new("zig")
-            .args([
+        let output = sandbox::run(
+            std::process::Command::new("zig").args([
                 "c++",
                 "-shared",
                 "-target",
@@ -216,8 +366,55 @@ impl Compiler {
                 src_path.as_os_str().to_str().unwrap(),
                 "-o",
                 dst_path.as_os_str().to_str().unwrap(),
-            ])
-            .output()?;
+            ]),
+            &self.limits,
+        )?;
+        if !output.status.success() {
+            bail!(
+                "compilation failed: {}",
+                std::str::from_utf8(&output.stderr)?
+            );
+        }
+
+        wasm_strip(&dst_path)
+    }
+
+    pub fn compile_c(&mut self, code: &str) -> Result<Vec<u8> /* wasm */> {
+        let tmp_path = &self.dir;
+        let src_path = tmp_path.join("user.c");
+        let dst_path = tmp_path.join("user.wasm");
+        std::fs::write(&src_path, code.as_bytes())?;
+        std::fs::write(
+            tmp_path.join("oort.h"),
+            include_bytes!("../../cpp-api/oort.h"),
+        )?;
+        std::fs::write(
+            tmp_path.join("oort_c.h"),
+            include_bytes!("../../cpp-api/oort_c.h"),
+        )?;
+        std::fs::write(
+            tmp_path.join("oort.c"),
+            include_bytes!("../../cpp-api/oort.c"),
+        )?;
+
+        let output = sandbox::run(
+            std::process::Command::new("zig").args([
+                "cc",
+                "-shared",
+                "-target",
+                "wasm32-wasi",
+                "-fno-stack-protector",
+                "-Oz",
+                "-Wl,--export=SYSTEM_STATE",
+                "-Wl,--export=ENVIRONMENT",
+                "-Wl,--export=PANIC_BUFFER",
+                tmp_path.join("oort.c").as_os_str().to_str().unwrap(),
+                src_path.as_os_str().to_str().unwrap(),
+                "-o",
+                dst_path.as_os_str().to_str().unwrap(),
+            ]),
+            &self.limits,
+        )?;
         if !output.status.success() {
             bail!(
                 "compilation failed: {}",
@@ -225,20 +422,99 @@ impl Compiler {
             );
         }
 
-        let output = std::process::Command::new("wasm-strip")
-            .args([dst_path.as_os_str().to_str().unwrap()])
-            .output()?;
+        wasm_strip(&dst_path)
+    }
+
+    pub fn compile_zig(&mut self, code: &str) -> Result<Vec<u8> /* wasm */> {
+        let tmp_path = &self.dir;
+        let dst_path = tmp_path.join("user.wasm");
+        std::fs::write(tmp_path.join("user.zig"), code.as_bytes())?;
+        std::fs::write(
+            tmp_path.join("oort.zig"),
+            include_bytes!("../../cpp-api/oort.zig"),
+        )?;
+
+        let output = sandbox::run(
+            std::process::Command::new("zig")
+                .current_dir(tmp_path)
+                .args([
+                    "build-lib",
+                    "-target",
+                    "wasm32-freestanding",
+                    "-dynamic",
+                    "-rdynamic",
+                    "-O",
+                    "ReleaseSmall",
+                    "--stack",
+                    "16384",
+                    &format!("-femit-bin={}", dst_path.display()),
+                    "oort.zig",
+                ]),
+            &self.limits,
+        )?;
         if !output.status.success() {
             bail!(
-                "wasm-strip failed: {}",
+                "compilation failed: {}",
                 std::str::from_utf8(&output.stderr)?
             );
         }
 
-        Ok(std::fs::read(&dst_path)?)
+        wasm_strip(&dst_path)
     }
 }
 
+fn wasm_strip(path: &Path) -> Result<Vec<u8> /* wasm */> {
+    let output = std::process::Command::new("wasm-strip")
+        .args([path.as_os_str().to_str().unwrap()])
+        .output()?;
+    if !output.status.success() {
+        bail!(
+            "wasm-strip failed: {}",
+            std::str::from_utf8(&output.stderr)?
+        );
+    }
+
+    Ok(std::fs::read(path)?)
+}
+
+/// Files in the AI crate that user modules can't replace.
+const RESERVED_FILES: &[&str] = &["lib.rs", "tick.rs", "user.rs"];
+
+fn check_module_path(path: &str) -> Result<()> {
+    lazy_static! {
+        static ref RE: Regex =
+            Regex::new(r"^([A-Za-z_][A-Za-z0-9_]*/)*[A-Za-z_][A-Za-z0-9_]*\.rs$").unwrap();
+    }
+    if !RE.is_match(path) {
+        bail!("Invalid module path {:?}", path);
+    }
+    if RESERVED_FILES.contains(&path) {
+        bail!("Module name {:?} is reserved", path);
+    }
+    Ok(())
+}
+
+/// Third-party crates user code may depend on, from `[workspace.dependencies]`
+/// in Cargo.toml.user.
+pub fn allowed_crates() -> Vec<String> {
+    let manifest: toml::Table = include_str!("../../../Cargo.toml.user").parse().unwrap();
+    manifest
+        .get("workspace")
+        .and_then(|x| x.get("dependencies"))
+        .and_then(|x| x.as_table())
+        .map(|x| x.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// The AI crate's manifest with the allowed crates added as dependencies.
+fn ai_manifest() -> String {
+    let mut manifest = include_str!("../../ai/Cargo.toml").to_string();
+    for name in allowed_crates() {
+        manifest.push_str(&format!("{name} = {{ workspace = true }}\n"));
+    }
+    manifest
+}
+
 fn find_rlib(tmp_path: &Path, crate_name: &str) -> PathBuf {
     if let Some(path) = glob::glob(
         tmp_path
@@ -276,20 +552,172 @@ fn find_rustc() -> String {
     }
 }
 
-enum Language {
+/// Opens the cache directory named by OORT_COMPILER_CACHE, if set.
+///
+/// OORT_COMPILER_CACHE_MAX_MB overrides the default size limit.
+fn cache_from_env() -> Option<Arc<Cache>> {
+    let dir = std::env::var("OORT_COMPILER_CACHE").ok()?;
+    let max_bytes = std::env::var("OORT_COMPILER_CACHE_MAX_MB")
+        .ok()
+        .and_then(|x| x.parse::<u64>().ok())
+        .map(|x| x << 20)
+        .unwrap_or(cache::DEFAULT_MAX_BYTES);
+    match Cache::new(Path::new(&dir), max_bytes) {
+        Ok(cache) => Some(Arc::new(cache)),
+        Err(e) => {
+            log::warn!("Failed to open compiler cache {:?}: {:?}", dir, e);
+            None
+        }
+    }
+}
+
+/// Returns the module in an uploaded WASM_PREFIX string, or None if `code` is
+/// source code.
+pub fn decode_wasm(code: &str) -> Option<Result<Vec<u8>>> {
+    let encoded = code.trim().strip_prefix(WASM_PREFIX)?;
+    let wasm = match base64::engine::general_purpose::STANDARD.decode(encoded.trim()) {
+        Ok(wasm) => wasm,
+        Err(e) => return Some(Err(anyhow!("Invalid base64 in uploaded WASM: {}", e))),
+    };
+    if !wasm.starts_with(b"\0asm") {
+        return Some(Err(anyhow!("Uploaded file is not a WASM module")));
+    }
+    Some(Ok(wasm))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
     Rust,
     Cpp,
-    Unknown,
+    C,
+    Zig,
 }
 
-fn detect_language(code: &str) -> Language {
-    log::debug!("Code: {:?}", code);
-    if code.contains("#include") {
-        log::info!("Detected C++");
-        Language::Cpp
-    } else if code.contains("impl Ship") {
-        Language::Rust
-    } else {
-        Language::Unknown
+impl Language {
+    pub const ALL: [Language; 4] = [Language::Rust, Language::Cpp, Language::C, Language::Zig];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::Cpp => "cpp",
+            Language::C => "c",
+            Language::Zig => "zig",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Language::Rust => "rs",
+            Language::Cpp => "cpp",
+            Language::C => "c",
+            Language::Zig => "zig",
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "rust" | "rs" => Ok(Language::Rust),
+            "cpp" | "c++" => Ok(Language::Cpp),
+            "c" => Ok(Language::C),
+            "zig" => Ok(Language::Zig),
+            other => Err(anyhow!("Unknown language {:?}", other)),
+        }
+    }
+}
+
+/// Returns the language named by a `// language: <name>` comment on the first
+/// non-blank line. Code without one is C++ if a line starts with `#include`,
+/// as saved C++ code predates the comment, and Rust otherwise.
+pub fn detect_language(code: &str) -> Result<Language> {
+    let first_line = code.lines().map(str::trim).find(|x| !x.is_empty());
+    match first_line.and_then(|x| x.strip_prefix("// language:")) {
+        Some(name) => name.parse(),
+        None if code.lines().any(|x| x.trim_start().starts_with("#include")) => Ok(Language::Cpp),
+        None => Ok(Language::Rust),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_version() {
+        let mut compiler = Compiler::new();
+        compiler.rustc = "/nonexistent/rustc".to_string();
+        assert_eq!(compiler.version(Language::Rust), None);
+        assert!(compiler.versions.is_empty());
+    }
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("impl Ship {}").unwrap(), Language::Rust);
+        assert_eq!(
+            detect_language("\n// language: zig\npub fn tick() void {}").unwrap(),
+            Language::Zig
+        );
+        assert_eq!(
+            detect_language("// language: C\n#include \"oort.h\"").unwrap(),
+            Language::C
+        );
+        assert_eq!(
+            detect_language("// language: cpp\n#include <oort.h>").unwrap(),
+            Language::Cpp
+        );
+        assert!(detect_language("// language: cobol").is_err());
+        // Without a directive, C++ is recognized by its includes.
+        assert_eq!(detect_language("#include <oort.h>").unwrap(), Language::Cpp);
+        assert_eq!(
+            detect_language("// My ship\n  #include \"oort.h\"\nvoid tick() {}").unwrap(),
+            Language::Cpp
+        );
+        assert_eq!(
+            detect_language("// uses \"#include\"\nimpl Ship {}").unwrap(),
+            Language::Rust
+        );
+    }
+
+    #[test]
+    fn test_decode_wasm() {
+        assert!(decode_wasm("impl Ship {}").is_none());
+        assert_eq!(
+            decode_wasm("WASM:AGFzbQEAAAA=\n").unwrap().unwrap(),
+            b"\0asm\x01\0\0\0"
+        );
+        assert!(decode_wasm("WASM:!!!").unwrap().is_err());
+        assert!(decode_wasm("WASM:aGVsbG8=").unwrap().is_err());
+    }
+
+    #[test]
+    fn test_check_module_path() {
+        assert!(check_module_path("foo.rs").is_ok());
+        assert!(check_module_path("foo/bar.rs").is_ok());
+        assert!(check_module_path("foo/mod.rs").is_ok());
+        assert!(check_module_path("tick.rs").is_err());
+        assert!(check_module_path("../foo.rs").is_err());
+        assert!(check_module_path("/etc/foo.rs").is_err());
+        assert!(check_module_path("foo.txt").is_err());
+    }
+
+    #[test]
+    fn test_ai_manifest() {
+        assert_eq!(allowed_crates(), vec!["nalgebra".to_string()]);
+        assert!(ai_manifest().ends_with("nalgebra = { workspace = true }\n"));
+    }
+
+    #[test]
+    fn test_allowed_crates_locked() {
+        // User code is compiled offline, so every allowed crate must be pinned.
+        let lock = include_str!("../../../Cargo.lock.user");
+        for name in allowed_crates() {
+            assert!(
+                lock.contains(&format!("name = \"{name}\"\n")),
+                "{name} missing from Cargo.lock.user"
+            );
+        }
     }
 }
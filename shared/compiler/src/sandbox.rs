@@ -0,0 +1,384 @@
+//! Runs compiler processes with resource limits and without network access.
+//!
+//! This is not a full sandbox: there is no filesystem isolation, so build
+//! scripts and proc macros can still read anything on the host that the
+//! compiler service's user can.
+use anyhow::Result;
+use std::process::{Command, Output};
+use std::time::Duration;
+
+/// Resource limits applied to each process compiling user code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Limits {
+    /// CPU time, enforced with RLIMIT_CPU.
+    pub cpu_time: Duration,
+    /// Wall-clock time, after which the process group is killed. Catches
+    /// processes that are blocked or sleeping rather than using CPU.
+    pub wall_time: Duration,
+    /// Address space size, enforced with RLIMIT_AS.
+    pub memory_bytes: u64,
+    /// Size of any file written, enforced with RLIMIT_FSIZE. Captured stdout
+    /// and stderr are truncated to the same size as they are read.
+    pub output_bytes: u64,
+    /// Allows network access, which is needed to download crates. Without it
+    /// processes run in a new network namespace, and [`run`] fails if the host
+    /// can't create one.
+    pub network: bool,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            cpu_time: Duration::from_secs(30),
+            wall_time: Duration::from_secs(60),
+            memory_bytes: 4 << 30,
+            output_bytes: 64 << 20,
+            network: false,
+        }
+    }
+}
+
+/// Returned when a compiler process was killed for exceeding a limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimitExceeded {
+    CpuTime(Duration),
+    WallTime(Duration),
+    Memory(u64),
+    Output(u64),
+}
+
+impl std::fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitExceeded::CpuTime(x) => {
+                write!(f, "Compilation exceeded the CPU time limit of {:?}", x)
+            }
+            LimitExceeded::WallTime(x) => {
+                write!(f, "Compilation exceeded the time limit of {:?}", x)
+            }
+            LimitExceeded::Memory(x) => {
+                write!(f, "Compilation exceeded the memory limit of {} MB", x >> 20)
+            }
+            LimitExceeded::Output(x) => write!(
+                f,
+                "Compilation exceeded the output size limit of {} MB",
+                x >> 20
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+/// Runs the command to completion under the given limits.
+#[cfg(target_os = "linux")]
+pub fn run(command: &mut Command, limits: &Limits) -> Result<Output> {
+    use std::os::unix::process::{CommandExt, ExitStatusExt};
+    use std::process::Stdio;
+    use std::time::Instant;
+
+    let isolate_network = !limits.network;
+    if isolate_network {
+        check_network_isolation()?;
+    }
+    let rlimits = limits.clone();
+    // SAFETY: apply only makes async-signal-safe syscalls.
+    unsafe {
+        command.pre_exec(move || apply(&rlimits, isolate_network));
+    }
+    // A process group of its own lets a timeout kill subprocesses too.
+    let mut child = command
+        .process_group(0)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let pid = child.id() as libc::pid_t;
+
+    let cap = limits.output_bytes;
+    let stdout = child.stdout.take().unwrap();
+    let stderr = child.stderr.take().unwrap();
+    let stdout_thread = std::thread::spawn(move || read_capped(stdout, cap));
+    let stderr_thread = std::thread::spawn(move || read_capped(stderr, cap));
+
+    let deadline = Instant::now() + limits.wall_time;
+    let mut timed_out = false;
+    let mut status: libc::c_int = 0;
+    // SAFETY: rusage is plain old data.
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        let flags = if timed_out { 0 } else { libc::WNOHANG };
+        match unsafe { libc::wait4(pid, &mut status, flags, &mut rusage) } {
+            0 => {}
+            x if x == pid => break,
+            _ => {
+                let e = std::io::Error::last_os_error();
+                if e.kind() != std::io::ErrorKind::Interrupted {
+                    return Err(e.into());
+                }
+            }
+        }
+        if !timed_out && Instant::now() >= deadline {
+            unsafe { libc::kill(-pid, libc::SIGKILL) };
+            timed_out = true;
+        } else if !timed_out {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+    if timed_out {
+        // Subprocesses may have outlived the leader.
+        unsafe { libc::kill(-pid, libc::SIGKILL) };
+    }
+
+    let output = Output {
+        status: std::process::ExitStatus::from_raw(status),
+        stdout: stdout_thread.join().unwrap()?,
+        stderr: stderr_thread.join().unwrap()?,
+    };
+    let cpu_time = timeval_duration(rusage.ru_utime) + timeval_duration(rusage.ru_stime);
+    if let Some(e) = check_status(&output, limits, timed_out, cpu_time) {
+        return Err(e.into());
+    }
+    Ok(output)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn run(command: &mut Command, limits: &Limits) -> Result<Output> {
+    log::warn!("Compiler sandbox is only supported on Linux");
+    let mut output = command.output()?;
+    output.stdout.truncate(limits.output_bytes as usize);
+    output.stderr.truncate(limits.output_bytes as usize);
+    Ok(output)
+}
+
+/// Reads up to `cap` bytes, then discards the rest so the writer never blocks.
+#[cfg(target_os = "linux")]
+fn read_capped(mut reader: impl std::io::Read, cap: u64) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+    let mut buf = vec![];
+    reader.by_ref().take(cap).read_to_end(&mut buf)?;
+    std::io::copy(&mut reader, &mut std::io::sink())?;
+    Ok(buf)
+}
+
+#[cfg(target_os = "linux")]
+fn timeval_duration(tv: libc::timeval) -> Duration {
+    Duration::from_secs(tv.tv_sec as u64) + Duration::from_micros(tv.tv_usec as u64)
+}
+
+/// Checks that this host lets unprivileged processes create a network
+/// namespace. Many containers block user namespaces.
+#[cfg(target_os = "linux")]
+pub fn check_network_isolation() -> Result<()> {
+    use std::sync::OnceLock;
+    static SUPPORTED: OnceLock<std::result::Result<(), String>> = OnceLock::new();
+    SUPPORTED
+        .get_or_init(|| {
+            use std::os::unix::process::CommandExt;
+            let mut command = Command::new("true");
+            // SAFETY: unshare_network only makes async-signal-safe syscalls.
+            unsafe {
+                command.pre_exec(unshare_network);
+            }
+            match command.status() {
+                Ok(status) if status.success() => Ok(()),
+                Ok(status) => Err(status.to_string()),
+                Err(e) => Err(e.to_string()),
+            }
+        })
+        .clone()
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Can't create a network namespace for compiler processes: {}",
+                e
+            )
+        })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn check_network_isolation() -> Result<()> {
+    anyhow::bail!("Network isolation for compiler processes is only supported on Linux")
+}
+
+#[cfg(target_os = "linux")]
+fn unshare_network() -> std::io::Result<()> {
+    // A new user namespace lets unprivileged processes create a network
+    // namespace, which only contains a loopback interface that is down.
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Runs in the child between fork and exec.
+#[cfg(target_os = "linux")]
+fn apply(limits: &Limits, isolate_network: bool) -> std::io::Result<()> {
+    let setrlimit = |resource, soft: u64, hard: u64| {
+        let rlimit = libc::rlimit {
+            rlim_cur: soft,
+            rlim_max: hard,
+        };
+        if unsafe { libc::setrlimit(resource, &rlimit) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    };
+
+    // The soft limit sends SIGXCPU, with SIGKILL a second later.
+    let cpu_secs = limits.cpu_time.as_secs().max(1);
+    setrlimit(libc::RLIMIT_CPU, cpu_secs, cpu_secs + 1)?;
+    setrlimit(libc::RLIMIT_AS, limits.memory_bytes, limits.memory_bytes)?;
+    setrlimit(libc::RLIMIT_FSIZE, limits.output_bytes, limits.output_bytes)?;
+    setrlimit(libc::RLIMIT_CORE, 0, 0)?;
+
+    if isolate_network {
+        unshare_network()?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn check_status(
+    output: &Output,
+    limits: &Limits,
+    timed_out: bool,
+    cpu_time: Duration,
+) -> Option<LimitExceeded> {
+    use std::os::unix::process::ExitStatusExt;
+    match output.status.signal()? {
+        _ if timed_out => Some(LimitExceeded::WallTime(limits.wall_time)),
+        libc::SIGXCPU => Some(LimitExceeded::CpuTime(limits.cpu_time)),
+        // SIGKILL follows SIGXCPU if the process ignores it.
+        libc::SIGKILL if cpu_time >= limits.cpu_time.max(Duration::from_secs(1)) => {
+            Some(LimitExceeded::CpuTime(limits.cpu_time))
+        }
+        libc::SIGXFSZ => Some(LimitExceeded::Output(limits.output_bytes)),
+        libc::SIGABRT if is_out_of_memory(&output.stderr) => {
+            Some(LimitExceeded::Memory(limits.memory_bytes))
+        }
+        _ => None,
+    }
+}
+
+/// Rust and LLVM abort with these messages when allocation fails.
+fn is_out_of_memory(stderr: &[u8]) -> bool {
+    let stderr = String::from_utf8_lossy(stderr);
+    stderr.contains("memory allocation of") || stderr.contains("LLVM ERROR: out of memory")
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_cpu_time() {
+        let limits = Limits {
+            cpu_time: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let err = run(
+            Command::new("sh").args(["-c", "while :; do :; done"]),
+            &limits,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<LimitExceeded>(),
+            Some(&LimitExceeded::CpuTime(limits.cpu_time))
+        );
+    }
+
+    #[test]
+    fn test_output_size() {
+        let dir = tempdir::TempDir::new("oort_sandbox").unwrap();
+        let limits = Limits {
+            output_bytes: 1 << 20,
+            ..Default::default()
+        };
+        let err = run(
+            Command::new("sh")
+                .args(["-c", "exec head -c 2000000 /dev/zero > out"])
+                .current_dir(dir.path()),
+            &limits,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<LimitExceeded>(),
+            Some(&LimitExceeded::Output(limits.output_bytes))
+        );
+    }
+
+    #[test]
+    fn test_wall_time() {
+        let limits = Limits {
+            wall_time: Duration::from_millis(200),
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let err = run(Command::new("sh").args(["-c", "sleep 10"]), &limits).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<LimitExceeded>(),
+            Some(&LimitExceeded::WallTime(limits.wall_time))
+        );
+        // The sleep subprocess is killed along with the shell.
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_kill_is_not_cpu_time() {
+        use std::os::unix::process::ExitStatusExt;
+        let output = run(
+            Command::new("sh").args(["-c", "kill -9 $$"]),
+            &Limits::default(),
+        )
+        .unwrap();
+        assert_eq!(output.status.signal(), Some(libc::SIGKILL));
+    }
+
+    #[test]
+    fn test_stdout_capped() {
+        let limits = Limits {
+            output_bytes: 1 << 20,
+            ..Default::default()
+        };
+        let output = run(
+            Command::new("sh").args(["-c", "head -c 3000000 /dev/zero; echo done >&2"]),
+            &limits,
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout.len(), 1 << 20);
+        assert_eq!(output.stderr, b"done\n");
+    }
+
+    #[test]
+    fn test_success() {
+        let limits = Limits {
+            network: true,
+            ..Default::default()
+        };
+        let output = run(Command::new("echo").arg("hello"), &limits).unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"hello\n");
+    }
+
+    #[test]
+    fn test_network_isolated() {
+        if check_network_isolation().is_err() {
+            return;
+        }
+        // /proc/net shows the process's own network namespace.
+        let output = run(
+            Command::new("sh").args(["-c", "tail -n +3 /proc/net/dev"]),
+            &Limits::default(),
+        )
+        .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let interfaces: Vec<_> = stdout
+            .lines()
+            .filter_map(|x| x.split(':').next())
+            .map(str::trim)
+            .collect();
+        assert_eq!(interfaces, vec!["lo"]);
+    }
+}
@@ -1,21 +1,124 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use lazy_static::lazy_static;
+use proc_macro2::{Delimiter, Ident, Spacing, TokenStream, TokenTree};
 use regex::Regex;
+use std::str::FromStr;
 
+/// Macros that read files on the compiler host.
+const FORBIDDEN_MACROS: &[&str] = &["include", "include_bytes", "include_str"];
+
+/// Checks that the code can't read files on the compiler host.
+///
+/// The check runs on tokens rather than text, so comments and strings are
+/// ignored. Because `macro_rules!` can't create new identifiers, any forbidden
+/// macro in the expanded code must appear as a token in the source, including
+/// inside macro bodies. Attributes are harder: a macro can assemble a
+/// `path = ".."` attribute from tokens passed as arguments, so `path` is
+/// rejected anywhere in macro arguments and attributes can't be named by a
+/// macro variable. Raw identifiers like `r#include_str` name the same macro
+/// or attribute, so they are compared without the prefix.
 pub fn check(text: &str) -> Result<()> {
+    match TokenStream::from_str(text) {
+        Ok(tokens) => check_tokens(tokens, false),
+        // Let rustc report the syntax error, unless the text check fails.
+        Err(_) => check_text(text),
+    }
+}
+
+fn check_tokens(tokens: TokenStream, in_macro_args: bool) -> Result<()> {
+    let tokens: Vec<_> = tokens.into_iter().collect();
+    for (i, tree) in tokens.iter().enumerate() {
+        let prev = |n: usize| i.checked_sub(n).map(|j| &tokens[j]);
+        match tree {
+            TokenTree::Ident(ident) => {
+                let name = ident_name(ident);
+                if FORBIDDEN_MACROS.contains(&name.as_str()) {
+                    bail!("Code did not pass sanitizer (found {:?})", name);
+                }
+                // Macro arguments can be rearranged into a `#[path]`
+                // attribute, e.g. `n!(path, =)` with `$a $b` in the body.
+                if in_macro_args && name == "path" {
+                    bail!("Code did not pass sanitizer (found \"path\" in macro arguments)");
+                }
+                // A macro variable may be bound to `path`.
+                if in_macro_args && is_punct(prev(1), '$') && is_assignment(tokens.get(i + 1)) {
+                    bail!("Code did not pass sanitizer (found \"$var =\" in macro arguments)");
+                }
+            }
+            TokenTree::Group(group) => {
+                // Inner attributes have a `!` between the `#` and the brackets.
+                let is_attribute = group.delimiter() == Delimiter::Bracket
+                    && (is_punct(prev(1), '#')
+                        || (is_punct(prev(1), '!') && is_punct(prev(2), '#')));
+                if is_attribute {
+                    check_attribute(group.stream())?;
+                }
+                let is_macro_call =
+                    is_punct(prev(1), '!') && matches!(prev(2), Some(TokenTree::Ident(_)));
+                check_tokens(group.stream(), in_macro_args || is_macro_call)?;
+            }
+            TokenTree::Punct(_) | TokenTree::Literal(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Rejects `#[path]`, including inside `cfg_attr`, and attributes named by a
+/// macro variable, including a whole `#[$meta]`.
+fn check_attribute(tokens: TokenStream) -> Result<()> {
+    let tokens: Vec<_> = tokens.into_iter().collect();
+    let mut at_name = true;
+    for (i, tree) in tokens.iter().enumerate() {
+        match tree {
+            TokenTree::Ident(ident) if at_name && ident_name(ident) == "path" => {
+                bail!("Code did not pass sanitizer (found #[path] attribute)");
+            }
+            TokenTree::Punct(punct) if at_name && punct.as_char() == '$' => {
+                bail!("Code did not pass sanitizer (found attribute named by a macro variable)");
+            }
+            TokenTree::Group(group) if matches!(i.checked_sub(1).map(|j| &tokens[j]), Some(TokenTree::Ident(x)) if ident_name(x) == "cfg_attr") =>
+            {
+                check_attribute(group.stream())?;
+            }
+            _ => {}
+        }
+        at_name = is_punct(Some(tree), ',');
+    }
+    Ok(())
+}
+
+/// The identifier without any `r#` prefix.
+fn ident_name(ident: &Ident) -> String {
+    let name = ident.to_string();
+    match name.strip_prefix("r#") {
+        Some(name) => name.to_string(),
+        None => name,
+    }
+}
+
+fn is_punct(tree: Option<&TokenTree>, c: char) -> bool {
+    matches!(tree, Some(TokenTree::Punct(punct)) if punct.as_char() == c)
+}
+
+/// Matches `=` but not `==` or `=>`.
+fn is_assignment(tree: Option<&TokenTree>) -> bool {
+    matches!(tree, Some(TokenTree::Punct(punct)) if punct.as_char() == '=' && punct.spacing() == Spacing::Alone)
+}
+
+/// Conservative check for code that doesn't tokenize.
+fn check_text(text: &str) -> Result<()> {
     lazy_static! {
-        static ref RE: Regex =
-            Regex::new(r"\b(macro_rules|include|include_bytes|include_str)(\b|!)").unwrap();
+        static ref RE: Regex = Regex::new(r"\b(include|include_bytes|include_str)(\b|!)").unwrap();
     }
     if let Some(m) = RE.find(text) {
-        anyhow::bail!("Code did not pass sanitizer (found {:?})", m.as_str());
+        bail!("Code did not pass sanitizer (found {:?})", m.as_str());
     }
 
     lazy_static! {
         static ref RE2: Regex = Regex::new(r"#\[[^]]*path").unwrap();
     }
     if let Some(m) = RE2.find(text) {
-        anyhow::bail!("Code did not pass sanitizer (found {:?})", m.as_str());
+        bail!("Code did not pass sanitizer (found {:?})", m.as_str());
     }
     Ok(())
 }
@@ -26,10 +129,26 @@ mod tests {
 
     #[test]
     fn test_include_macros() {
-        assert!(check("... macro_rules! ...").is_err());
         assert!(check("... include! ...").is_err());
         assert!(check("... include_bytes! ...").is_err());
         assert!(check("... include_str! ...").is_err());
+        assert!(check("const X: &str = std::include_str!(\"/etc/passwd\");").is_err());
+        assert!(check(
+            "macro_rules! m { ($i:ident) => { $i!(\"/etc/passwd\") } } m!(include_str);"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_raw_identifiers() {
+        assert!(check("const X: &str = r#include_str!(\"/etc/hostname\");").is_err());
+        assert!(check("const X: &[u8] = core::r#include_bytes!(\"/etc/hostname\");").is_err());
+        assert!(check("r#include!(\"/etc/hostname\");").is_err());
+        assert!(check("#[r#path = \"/abs/secret.rs\"] mod foo;").is_err());
+        assert!(check("#[cfg_attr(all(), r#path = \"/abs/secret.rs\")] mod foo;").is_err());
+        assert!(check("#[r#cfg_attr(all(), path = \"/abs/secret.rs\")] mod foo;").is_err());
+        assert!(check("m!(r#path = \"/abs/secret.rs\");").is_err());
+        check("fn f(r#type: u32) -> u32 { r#type }").unwrap();
     }
 
     #[test]
@@ -37,5 +156,37 @@ mod tests {
         assert!(check("... #[path = \"/dev/random\"] ...").is_err());
         assert!(check("... #[\npath = \"/dev/random\"] ...").is_err());
         assert!(check("... #[\t  path\n= \"/dev/random\"] ...").is_err());
+        assert!(check("#![path = \"/dev/random\"] mod foo;").is_err());
+        assert!(check("#[cfg_attr(all(), path = \"/dev/random\")] mod foo;").is_err());
+        assert!(
+            check("macro_rules! m { ($a:ident) => { #[$a = \"/dev/random\"] mod foo; } }").is_err()
+        );
+        assert!(check("macro_rules! m { ($($t:tt)*) => { #[$($t)*] mod foo; } }").is_err());
+        assert!(check("m!(path = \"/dev/random\");").is_err());
+        assert!(check("m!(cfg_attr(all(), path = \"/dev/random\"));").is_err());
+        assert!(check("macro_rules! n { ($k:ident) => { m!($k = \"/dev/random\"); } }").is_err());
+        assert!(check(
+            "macro_rules! o { ($m:meta) => { #[$m] mod foo; } } \
+             macro_rules! n { ($a:tt, $b:tt) => { o!($a $b \"/etc/passwd\"); } } \
+             n!(path, =);"
+        )
+        .is_err());
+        assert!(check("macro_rules! m { ($(#[$m:meta])* $i:item) => { $(#[$m])* $i } }").is_err());
+        assert!(check("fn f(path: u32) { assert!(path == 2); }").is_err());
+    }
+
+    #[test]
+    fn test_allowed() {
+        check("macro_rules! square { ($x:expr) => { $x * $x } }").unwrap();
+        check("// include_str!(\"foo\")\nconst X: &str = \"#[path = x]\";").unwrap();
+        check("macro_rules! m { ($doc:expr) => { #[doc = $doc] fn f() {} } }").unwrap();
+        check("#[allow(path_statements)] fn f(path: &str) -> &str { path }").unwrap();
+        check("fn f(path: u32) -> u32 { let path = path + 1; path * 2 }").unwrap();
+    }
+
+    #[test]
+    fn test_untokenizable() {
+        assert!(check("\"unterminated include_str!").is_err());
+        check("\"unterminated").unwrap();
     }
 }
@@ -0,0 +1,33 @@
+#include "oort.h"
+
+uint64_t SYSTEM_STATE[OORT_SYSTEM_STATE_SIZE];
+uint8_t ENVIRONMENT[OORT_ENVIRONMENT_SIZE];
+uint8_t PANIC_BUFFER[OORT_PANIC_BUFFER_SIZE];
+
+char oort_debug_text[OORT_MAX_DEBUG_TEXT];
+size_t oort_debug_text_length;
+struct OortLine oort_lines[OORT_MAX_LINES];
+size_t oort_num_lines;
+struct OortText oort_texts[OORT_MAX_TEXTS];
+size_t oort_num_texts;
+
+int main(void) {
+    return 0;
+}
+
+void tick(void);
+
+__attribute__((export_name("tick")))
+void sys_tick(void) {
+    oort_debug_text_length = 0;
+    oort_num_lines = 0;
+    oort_num_texts = 0;
+    PANIC_BUFFER[0] = 0;
+    tick();
+    write_f64(DebugTextPointer, (double)(uint32_t)(uintptr_t)oort_debug_text);
+    write_f64(DebugTextLength, (double)oort_debug_text_length);
+    write_f64(DebugLinesPointer, (double)(uint32_t)(uintptr_t)oort_lines);
+    write_f64(DebugLinesLength, (double)oort_num_lines);
+    write_f64(DrawnTextPointer, (double)(uint32_t)(uintptr_t)oort_texts);
+    write_f64(DrawnTextLength, (double)oort_num_texts);
+}
@@ -1,9 +1,9 @@
 #include "oort.h"
 
 extern "C" {
-    uint64_t SYSTEM_STATE[128];
-    uint8_t ENVIRONMENT[1024];
-    uint8_t PANIC_BUFFER[1024];
+    uint64_t SYSTEM_STATE[OORT_SYSTEM_STATE_SIZE];
+    uint8_t ENVIRONMENT[OORT_ENVIRONMENT_SIZE];
+    uint8_t PANIC_BUFFER[OORT_PANIC_BUFFER_SIZE];
 }
 
 void std::__libcpp_verbose_abort(char const* format, ...) {
@@ -19,5 +19,8 @@ void tick();
 
 __attribute__((export_name("tick")))
 void sys_tick() {
+    oort::sys::reset();
+    PANIC_BUFFER[0] = 0;
     tick();
+    oort::sys::update();
 }
@@ -1,177 +1,177 @@
+// Generated from shared/api by shared/cpp-api. Do not edit.
+// Regenerate with `cargo run -p oort_cpp_api`.
+
 #ifndef OORT_H
 #define OORT_H
 
-#include <cstdint>
-#include <cstring>
-#include <cstdlib>
-
-extern "C" {
-    extern uint64_t SYSTEM_STATE[128];
-    extern uint8_t ENVIRONMENT[1024];
-    extern uint8_t PANIC_BUFFER[1024];
-}
-
-enum SystemState {
-    Class,
-    Seed,
-    PositionX,
-    PositionY,
-    VelocityX,
-    VelocityY,
-    Heading,
-    AngularVelocity,
-
-    AccelerateX,
-    AccelerateY,
-    Torque,
-
-    Aim0,
-    Aim1,
-    Aim2,
-    Aim3,
-
-    Fire0,
-    Fire1,
-    Fire2,
-    Fire3,
-
-    Explode,
-
-    RadarHeading,
-    RadarWidth,
-    RadarContactFound,
-    RadarContactClass,
-    RadarContactPositionX,
-    RadarContactPositionY,
-    RadarContactVelocityX,
-    RadarContactVelocityY,
-
-    DebugTextPointer,
-    DebugTextLength,
-
-    MaxForwardAcceleration,
-    MaxLateralAcceleration,
-    MaxAngularAcceleration,
-
-    DebugLinesPointer,
-    DebugLinesLength,
-
-    RadarMinDistance,
-    RadarMaxDistance,
-
-    CurrentTick,
-    MaxBackwardAcceleration,
-
-    ActivateAbility,
-
-    Radio0Channel, // TODO collapse into command word
-    Radio0Send,
-    Radio0Receive,
-    Radio0Data0,
-    Radio0Data1,
-    Radio0Data2,
-    Radio0Data3,
+#include <stdint.h>
+#include <string.h>
 
-    Radio1Channel,
-    Radio1Send,
-    Radio1Receive,
-    Radio1Data0,
-    Radio1Data1,
-    Radio1Data2,
-    Radio1Data3,
+#define OORT_SYSTEM_STATE_SIZE 128
+#define OORT_ENVIRONMENT_SIZE 1024
+#define OORT_PANIC_BUFFER_SIZE 1024
 
-    Radio2Channel,
-    Radio2Send,
-    Radio2Receive,
-    Radio2Data0,
-    Radio2Data1,
-    Radio2Data2,
-    Radio2Data3,
-
-    Radio3Channel,
-    Radio3Send,
-    Radio3Receive,
-    Radio3Data0,
-    Radio3Data1,
-    Radio3Data2,
-    Radio3Data3,
-
-    Radio4Channel,
-    Radio4Send,
-    Radio4Receive,
-    Radio4Data0,
-    Radio4Data1,
-    Radio4Data2,
-    Radio4Data3,
-
-    Radio5Channel,
-    Radio5Send,
-    Radio5Receive,
-    Radio5Data0,
-    Radio5Data1,
-    Radio5Data2,
-    Radio5Data3,
-
-    Radio6Channel,
-    Radio6Send,
-    Radio6Receive,
-    Radio6Data0,
-    Radio6Data1,
-    Radio6Data2,
-    Radio6Data3,
-
-    Radio7Channel,
-    Radio7Send,
-    Radio7Receive,
-    Radio7Data0,
-    Radio7Data1,
-    Radio7Data2,
-    Radio7Data3,
-
-    // TODO not part of interface
-    SelectedRadio,
-
-    DrawnTextPointer,
-    DrawnTextLength,
-
-    RadarEcmMode,
-
-    Health,
-    Fuel,
-
-    RadarContactRssi,
-    RadarContactSnr,
+#ifdef __cplusplus
+extern "C" {
+#endif
 
-    ReloadTicks0,
-    ReloadTicks1,
-    ReloadTicks2,
-    ReloadTicks3,
+extern uint64_t SYSTEM_STATE[OORT_SYSTEM_STATE_SIZE];
+extern uint8_t ENVIRONMENT[OORT_ENVIRONMENT_SIZE];
+extern uint8_t PANIC_BUFFER[OORT_PANIC_BUFFER_SIZE];
 
-    Id,
+#ifdef __cplusplus
+}
+#endif
 
-    Size,
+enum SystemState {
+    Class = 0,
+    Seed = 1,
+    PositionX = 2,
+    PositionY = 3,
+    VelocityX = 4,
+    VelocityY = 5,
+    Heading = 6,
+    AngularVelocity = 7,
+    AccelerateX = 8,
+    AccelerateY = 9,
+    Torque = 10,
+    Aim0 = 11,
+    Aim1 = 12,
+    Aim2 = 13,
+    Aim3 = 14,
+    Fire0 = 15,
+    Fire1 = 16,
+    Fire2 = 17,
+    Fire3 = 18,
+    Explode = 19,
+    RadarHeading = 20,
+    RadarWidth = 21,
+    RadarContactFound = 22,
+    RadarContactClass = 23,
+    RadarContactPositionX = 24,
+    RadarContactPositionY = 25,
+    RadarContactVelocityX = 26,
+    RadarContactVelocityY = 27,
+    DebugTextPointer = 28,
+    DebugTextLength = 29,
+    MaxForwardAcceleration = 30,
+    MaxLateralAcceleration = 31,
+    MaxAngularAcceleration = 32,
+    DebugLinesPointer = 33,
+    DebugLinesLength = 34,
+    RadarMinDistance = 35,
+    RadarMaxDistance = 36,
+    CurrentTick = 37,
+    MaxBackwardAcceleration = 38,
+    ActivateAbility = 39,
+    Radio0Channel = 40,
+    Radio0Send = 41,
+    Radio0Receive = 42,
+    Radio0Data0 = 43,
+    Radio0Data1 = 44,
+    Radio0Data2 = 45,
+    Radio0Data3 = 46,
+    Radio1Channel = 47,
+    Radio1Send = 48,
+    Radio1Receive = 49,
+    Radio1Data0 = 50,
+    Radio1Data1 = 51,
+    Radio1Data2 = 52,
+    Radio1Data3 = 53,
+    Radio2Channel = 54,
+    Radio2Send = 55,
+    Radio2Receive = 56,
+    Radio2Data0 = 57,
+    Radio2Data1 = 58,
+    Radio2Data2 = 59,
+    Radio2Data3 = 60,
+    Radio3Channel = 61,
+    Radio3Send = 62,
+    Radio3Receive = 63,
+    Radio3Data0 = 64,
+    Radio3Data1 = 65,
+    Radio3Data2 = 66,
+    Radio3Data3 = 67,
+    Radio4Channel = 68,
+    Radio4Send = 69,
+    Radio4Receive = 70,
+    Radio4Data0 = 71,
+    Radio4Data1 = 72,
+    Radio4Data2 = 73,
+    Radio4Data3 = 74,
+    Radio5Channel = 75,
+    Radio5Send = 76,
+    Radio5Receive = 77,
+    Radio5Data0 = 78,
+    Radio5Data1 = 79,
+    Radio5Data2 = 80,
+    Radio5Data3 = 81,
+    Radio6Channel = 82,
+    Radio6Send = 83,
+    Radio6Receive = 84,
+    Radio6Data0 = 85,
+    Radio6Data1 = 86,
+    Radio6Data2 = 87,
+    Radio6Data3 = 88,
+    Radio7Channel = 89,
+    Radio7Send = 90,
+    Radio7Receive = 91,
+    Radio7Data0 = 92,
+    Radio7Data1 = 93,
+    Radio7Data2 = 94,
+    Radio7Data3 = 95,
+    SelectedRadio = 96,
+    DrawnTextPointer = 97,
+    DrawnTextLength = 98,
+    RadarEcmMode = 99,
+    Health = 100,
+    Fuel = 101,
+    RadarContactRssi = 102,
+    RadarContactSnr = 103,
+    ReloadTicks0 = 104,
+    ReloadTicks1 = 105,
+    ReloadTicks2 = 106,
+    ReloadTicks3 = 107,
+    Id = 108,
+    RadarWarningCount = 109,
+    RadarWarningBearing0 = 110,
+    RadarWarningBearing1 = 111,
+    RadarWarningBearing2 = 112,
+    RadarWarningBearing3 = 113,
+    RadarWarningRssi0 = 114,
+    RadarWarningRssi1 = 115,
+    RadarWarningRssi2 = 116,
+    RadarWarningRssi3 = 117,
+    Energy = 118,
+    Size = 119,
     MaxSize = 128,
 };
 
-inline uint64_t read_u64(enum SystemState key) {
+static inline uint64_t read_u64(enum SystemState key) {
     return SYSTEM_STATE[key];
 }
 
-inline double read_f64(enum SystemState key) {
+static inline double read_f64(enum SystemState key) {
     uint64_t u64_value = read_u64(key);
     double f64_value;
-    std::memcpy(&f64_value, &u64_value, sizeof(f64_value));
+    memcpy(&f64_value, &u64_value, sizeof(f64_value));
     return f64_value;
 }
 
-inline void write_u64(enum SystemState key, uint64_t value) {
+static inline void write_u64(enum SystemState key, uint64_t value) {
     SYSTEM_STATE[key] = value;
 }
 
-inline void write_f64(enum SystemState key, double value) {
+static inline void write_f64(enum SystemState key, double value) {
     uint64_t u64_value;
-    std::memcpy(&u64_value, &value, sizeof(u64_value));
+    memcpy(&u64_value, &value, sizeof(u64_value));
     write_u64(key, u64_value);
 }
 
+#ifdef __cplusplus
+#include "oort_prelude.h"
+#else
+#include "oort_c.h"
+#endif
+
 #endif
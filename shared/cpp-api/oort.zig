@@ -0,0 +1,844 @@
+// Generated from shared/api by shared/cpp-api. Do not edit.
+// Regenerate with `cargo run -p oort_cpp_api`.
+
+//! Zig equivalent of the Rust `oort_api::prelude`.
+//!
+//! User code lives in user.zig, which must declare `pub fn tick() void` and
+//! can import this file with `const oort = @import("oort.zig");`.
+
+const std = @import("std");
+const user = @import("user.zig");
+
+const SYSTEM_STATE_SIZE = 128;
+const ENVIRONMENT_SIZE = 1024;
+const PANIC_BUFFER_SIZE = 1024;
+
+export var SYSTEM_STATE: [SYSTEM_STATE_SIZE]u64 = [_]u64{0} ** SYSTEM_STATE_SIZE;
+export var ENVIRONMENT: [ENVIRONMENT_SIZE]u8 = [_]u8{0} ** ENVIRONMENT_SIZE;
+export var PANIC_BUFFER: [PANIC_BUFFER_SIZE]u8 = [_]u8{0} ** PANIC_BUFFER_SIZE;
+
+pub const SystemState = enum(u32) {
+    Class = 0,
+    Seed = 1,
+    PositionX = 2,
+    PositionY = 3,
+    VelocityX = 4,
+    VelocityY = 5,
+    Heading = 6,
+    AngularVelocity = 7,
+    AccelerateX = 8,
+    AccelerateY = 9,
+    Torque = 10,
+    Aim0 = 11,
+    Aim1 = 12,
+    Aim2 = 13,
+    Aim3 = 14,
+    Fire0 = 15,
+    Fire1 = 16,
+    Fire2 = 17,
+    Fire3 = 18,
+    Explode = 19,
+    RadarHeading = 20,
+    RadarWidth = 21,
+    RadarContactFound = 22,
+    RadarContactClass = 23,
+    RadarContactPositionX = 24,
+    RadarContactPositionY = 25,
+    RadarContactVelocityX = 26,
+    RadarContactVelocityY = 27,
+    DebugTextPointer = 28,
+    DebugTextLength = 29,
+    MaxForwardAcceleration = 30,
+    MaxLateralAcceleration = 31,
+    MaxAngularAcceleration = 32,
+    DebugLinesPointer = 33,
+    DebugLinesLength = 34,
+    RadarMinDistance = 35,
+    RadarMaxDistance = 36,
+    CurrentTick = 37,
+    MaxBackwardAcceleration = 38,
+    ActivateAbility = 39,
+    Radio0Channel = 40,
+    Radio0Send = 41,
+    Radio0Receive = 42,
+    Radio0Data0 = 43,
+    Radio0Data1 = 44,
+    Radio0Data2 = 45,
+    Radio0Data3 = 46,
+    Radio1Channel = 47,
+    Radio1Send = 48,
+    Radio1Receive = 49,
+    Radio1Data0 = 50,
+    Radio1Data1 = 51,
+    Radio1Data2 = 52,
+    Radio1Data3 = 53,
+    Radio2Channel = 54,
+    Radio2Send = 55,
+    Radio2Receive = 56,
+    Radio2Data0 = 57,
+    Radio2Data1 = 58,
+    Radio2Data2 = 59,
+    Radio2Data3 = 60,
+    Radio3Channel = 61,
+    Radio3Send = 62,
+    Radio3Receive = 63,
+    Radio3Data0 = 64,
+    Radio3Data1 = 65,
+    Radio3Data2 = 66,
+    Radio3Data3 = 67,
+    Radio4Channel = 68,
+    Radio4Send = 69,
+    Radio4Receive = 70,
+    Radio4Data0 = 71,
+    Radio4Data1 = 72,
+    Radio4Data2 = 73,
+    Radio4Data3 = 74,
+    Radio5Channel = 75,
+    Radio5Send = 76,
+    Radio5Receive = 77,
+    Radio5Data0 = 78,
+    Radio5Data1 = 79,
+    Radio5Data2 = 80,
+    Radio5Data3 = 81,
+    Radio6Channel = 82,
+    Radio6Send = 83,
+    Radio6Receive = 84,
+    Radio6Data0 = 85,
+    Radio6Data1 = 86,
+    Radio6Data2 = 87,
+    Radio6Data3 = 88,
+    Radio7Channel = 89,
+    Radio7Send = 90,
+    Radio7Receive = 91,
+    Radio7Data0 = 92,
+    Radio7Data1 = 93,
+    Radio7Data2 = 94,
+    Radio7Data3 = 95,
+    SelectedRadio = 96,
+    DrawnTextPointer = 97,
+    DrawnTextLength = 98,
+    RadarEcmMode = 99,
+    Health = 100,
+    Fuel = 101,
+    RadarContactRssi = 102,
+    RadarContactSnr = 103,
+    ReloadTicks0 = 104,
+    ReloadTicks1 = 105,
+    ReloadTicks2 = 106,
+    ReloadTicks3 = 107,
+    Id = 108,
+    RadarWarningCount = 109,
+    RadarWarningBearing0 = 110,
+    RadarWarningBearing1 = 111,
+    RadarWarningBearing2 = 112,
+    RadarWarningBearing3 = 113,
+    RadarWarningRssi0 = 114,
+    RadarWarningRssi1 = 115,
+    RadarWarningRssi2 = 116,
+    RadarWarningRssi3 = 117,
+    Energy = 118,
+    Size = 119,
+    MaxSize = 128,
+};
+
+pub fn readU64(key: SystemState) u64 {
+    return SYSTEM_STATE[@intFromEnum(key)];
+}
+
+pub fn readF64(key: SystemState) f64 {
+    return @bitCast(readU64(key));
+}
+
+pub fn writeU64(key: SystemState, value: u64) void {
+    SYSTEM_STATE[@intFromEnum(key)] = value;
+}
+
+pub fn writeF64(key: SystemState, value: f64) void {
+    writeU64(key, @bitCast(value));
+}
+
+fn offset(key: SystemState, n: usize) SystemState {
+    return @enumFromInt(@intFromEnum(key) + @as(u32, @intCast(n)));
+}
+
+pub const PI: f64 = std.math.pi;
+pub const TAU: f64 = 2.0 * PI;
+
+/// Identifiers for each class of ship.
+pub const Class = enum(u32) {
+    Fighter = 0,
+    Frigate = 1,
+    Cruiser = 2,
+    Asteroid = 3,
+    Target = 4,
+    Missile = 5,
+    Torpedo = 6,
+    Unknown = 7,
+};
+
+/// Electronic Counter Measures (ECM) modes.
+pub const EcmMode = enum(u32) {
+    None = 0,
+    Noise = 1,
+};
+
+/// Special abilities available to different ship classes.
+pub const Ability = enum(u32) {
+    None = 0,
+    Boost = 1,
+    ShapedCharge = 2,
+    Decoy = 3,
+    Shield = 4,
+    Cloak = 5,
+    PointDefense = 6,
+    TractorBeam = 7,
+};
+
+
+/// A two-dimensional vector.
+pub const Vec2 = struct {
+    x: f64,
+    y: f64,
+
+    pub fn add(a: Vec2, b: Vec2) Vec2 {
+        return vec2(a.x + b.x, a.y + b.y);
+    }
+
+    pub fn sub(a: Vec2, b: Vec2) Vec2 {
+        return vec2(a.x - b.x, a.y - b.y);
+    }
+
+    pub fn scale(a: Vec2, b: f64) Vec2 {
+        return vec2(a.x * b, a.y * b);
+    }
+
+    pub fn div(a: Vec2, b: f64) Vec2 {
+        return vec2(a.x / b, a.y / b);
+    }
+
+    /// Returns the length (or distance from origin).
+    pub fn length(a: Vec2) f64 {
+        return @sqrt(a.x * a.x + a.y * a.y);
+    }
+
+    /// Returns a normalized vector with the same direction but length of 1.
+    pub fn normalize(a: Vec2) Vec2 {
+        return a.div(a.length());
+    }
+
+    /// Returns the distance to `b`.
+    pub fn distance(a: Vec2, b: Vec2) f64 {
+        return a.sub(b).length();
+    }
+
+    /// Returns the dot product with `b`.
+    pub fn dot(a: Vec2, b: Vec2) f64 {
+        return a.x * b.x + a.y * b.y;
+    }
+
+    /// Returns the angle of the vector (in radians).
+    pub fn angle(a: Vec2) f64 {
+        const result = std.math.atan2(a.y, a.x);
+        return if (result < 0.0) result + TAU else result;
+    }
+
+    /// Returns this vector rotated by the given angle (in radians).
+    pub fn rotate(a: Vec2, radians: f64) Vec2 {
+        const c = @cos(radians);
+        const s = @sin(radians);
+        return vec2(a.x * c - a.y * s, a.x * s + a.y * c);
+    }
+};
+
+/// Returns a Vec2 with the given coordinates.
+pub fn vec2(x: f64, y: f64) Vec2 {
+    return .{ .x = x, .y = y };
+}
+
+/// Message sent and received on the radio.
+pub const Message = [4]f64;
+
+/// A radar contact.
+pub const ScanResult = struct {
+    /// The contact's class.
+    class: Class,
+    /// The contact's approximate position.
+    position: Vec2,
+    /// The contact's approximate velocity.
+    velocity: Vec2,
+    /// The received signal strength measured in dBm.
+    rssi: f64,
+    /// The signal-to-noise ratio measured in dB.
+    snr: f64,
+};
+
+/// An enemy radar illuminating this ship.
+pub const RadarWarning = struct {
+    /// The direction to the enemy radar (in radians).
+    bearing: f64,
+    /// The received signal strength measured in dBm.
+    rssi: f64,
+};
+
+/// List of active abilities for an entity.
+pub const ActiveAbilities = struct {
+    bits: u64,
+
+    /// Get whether an ability is active.
+    pub fn getAbility(self: ActiveAbilities, ability: Ability) bool {
+        return (self.bits >> @as(u6, @intCast(@intFromEnum(ability)))) & 1 != 0;
+    }
+};
+
+pub const MAX_RADIOS: usize = 8;
+pub const RADIO_STRIDE: usize = 7;
+/// The time between each simulation tick.
+pub const TICK_LENGTH: f64 = 1.0 / 60.0;
+/// The maximum number of radars reported by [`radar_warnings`].
+pub const MAX_RADAR_WARNINGS: usize = 4;
+
+fn abs(x: f64) f64 {
+    return if (x < 0.0) -x else x;
+}
+
+fn toClass(value: f64) Class {
+    const n: u32 = @intFromFloat(value);
+    return if (n > @intFromEnum(Class.Unknown)) .Unknown else @enumFromInt(n);
+}
+
+fn radioState(radio0_state: SystemState) SystemState {
+    const index: usize = @intFromFloat(readF64(.SelectedRadio));
+    if (index >= MAX_RADIOS) {
+        @panic("invalid radio index");
+    }
+    return offset(radio0_state, RADIO_STRIDE * index);
+}
+
+/// Returns the value of an environment variable set by the scenario.
+pub fn getenv(key: []const u8) ?[]const u8 {
+    // Format is key=value\nkey=value\n... ending with a null byte.
+    const env = std.mem.sliceTo(&ENVIRONMENT, 0);
+    var it = std.mem.splitScalar(u8, env, '\n');
+    while (it.next()) |line| {
+        if (line.len > key.len and std.mem.startsWith(u8, line, key) and line[key.len] == '=') {
+            return line[key.len + 1 ..];
+        }
+    }
+    return null;
+}
+
+/// Returns a per-ship ID that is unique within a team.
+pub fn id() u32 {
+    return @intFromFloat(readF64(.Id));
+}
+
+/// Returns the ship class (Fighter, Cruiser, etc).
+pub fn class() Class {
+    return toClass(readF64(.Class));
+}
+
+/// Returns a random number useful for initializing a random number generator.
+pub fn seed() u64 {
+    return @intFromFloat(readF64(.Seed));
+}
+
+/// Returns the scenario name.
+pub fn scenarioName() []const u8 {
+    return getenv("SCENARIO_NAME") orelse "unknown";
+}
+
+/// Returns the world size in meters.
+pub fn worldSize() f64 {
+    const value = getenv("WORLD_SIZE") orelse return 0.0;
+    return std.fmt.parseFloat(f64, value) catch 0.0;
+}
+
+/// Returns the time between each simulation tick in this scenario.
+///
+/// This is usually TICK_LENGTH, but scenarios can change it.
+pub fn tickLength() f64 {
+    const value = getenv("TICK_LENGTH") orelse return TICK_LENGTH;
+    return std.fmt.parseFloat(f64, value) catch TICK_LENGTH;
+}
+
+/// Returns the current position (in meters).
+pub fn position() Vec2 {
+    return vec2(readF64(.PositionX), readF64(.PositionY));
+}
+
+/// Returns the current velocity (in m/s).
+pub fn velocity() Vec2 {
+    return vec2(readF64(.VelocityX), readF64(.VelocityY));
+}
+
+/// Returns the current heading (in radians).
+pub fn heading() f64 {
+    return readF64(.Heading);
+}
+
+/// Returns the current angular velocity (in radians/s).
+pub fn angularVelocity() f64 {
+    return readF64(.AngularVelocity);
+}
+
+/// Returns the maximum forward acceleration (in m/s²).
+pub fn maxForwardAcceleration() f64 {
+    return readF64(.MaxForwardAcceleration);
+}
+
+/// Returns the maximum backward acceleration (in m/s²).
+pub fn maxBackwardAcceleration() f64 {
+    return readF64(.MaxBackwardAcceleration);
+}
+
+/// Returns the maximum lateral acceleration (in m/s²).
+pub fn maxLateralAcceleration() f64 {
+    return readF64(.MaxLateralAcceleration);
+}
+
+/// Returns the maximum angular acceleration (in radians/s²).
+pub fn maxAngularAcceleration() f64 {
+    return readF64(.MaxAngularAcceleration);
+}
+
+/// Sets the linear acceleration for the next tick (in m/s²).
+pub fn accelerate(acceleration: Vec2) void {
+    var a = acceleration.rotate(-heading());
+    if (a.x > maxForwardAcceleration()) {
+        a = a.scale(maxForwardAcceleration() / a.x);
+    }
+    if (a.x < -maxBackwardAcceleration()) {
+        a = a.scale(maxBackwardAcceleration() / -a.x);
+    }
+    if (abs(a.y) > maxLateralAcceleration()) {
+        a = a.scale(maxLateralAcceleration() / abs(a.y));
+    }
+    writeF64(.AccelerateX, a.x);
+    writeF64(.AccelerateY, a.y);
+}
+
+/// Sets the angular acceleration for the next tick (in radians/s²).
+pub fn torque(angular_acceleration: f64) void {
+    writeF64(.Torque, angular_acceleration);
+}
+
+/// Rotates the ship at the given speed (in radians/s).
+pub fn turn(speed: f64) void {
+    const max = maxAngularAcceleration() * 0.2;
+    const delta = std.math.clamp(speed, -max, max) - angularVelocity();
+    torque(if (delta < 0.0) -maxAngularAcceleration() else maxAngularAcceleration());
+}
+
+/// Aims a turreted weapon. `radians` is the heading to aim at.
+pub fn aim(index: usize, radians: f64) void {
+    if (index < 4) {
+        writeF64(offset(.Aim0, index), radians);
+    }
+}
+
+/// Fires a weapon.
+pub fn fire(index: usize) void {
+    if (index < 4) {
+        writeF64(offset(.Fire0, index), 1.0);
+    }
+}
+
+/// Returns the number of ticks until a weapon is ready to fire.
+pub fn reloadTicks(index: usize) u32 {
+    if (index >= 4) {
+        return 0;
+    }
+    return @intFromFloat(readF64(offset(.ReloadTicks0, index)));
+}
+
+/// Self-destructs, producing a damaging explosion.
+pub fn explode() void {
+    writeF64(.Explode, 1.0);
+}
+
+/// Returns the current health.
+pub fn health() f64 {
+    return readF64(.Health);
+}
+
+/// Returns the current fuel (delta-v).
+pub fn fuel() f64 {
+    return readF64(.Fuel);
+}
+
+/// Returns the current energy.
+///
+/// Guns, missile launchers, radar and abilities may draw from it, and it
+/// regenerates over time. Returns infinity if the ship doesn't use energy.
+pub fn energy() f64 {
+    return readF64(.Energy);
+}
+
+/// Returns the heading the radar is pointed at.
+pub fn radarHeading() f64 {
+    return readF64(.RadarHeading);
+}
+
+/// Sets the heading to point the radar at. It takes effect next tick.
+pub fn setRadarHeading(radians: f64) void {
+    writeF64(.RadarHeading, radians);
+}
+
+/// Returns the current radar width (in radians).
+pub fn radarWidth() f64 {
+    return readF64(.RadarWidth);
+}
+
+/// Sets the radar width (in radians). It takes effect next tick.
+pub fn setRadarWidth(width: f64) void {
+    writeF64(.RadarWidth, width);
+}
+
+/// Gets the current minimum distance filter of the radar (in meters).
+pub fn radarMinDistance() f64 {
+    return readF64(.RadarMinDistance);
+}
+
+/// Sets the minimum distance filter of the radar (in meters).
+pub fn setRadarMinDistance(dist: f64) void {
+    writeF64(.RadarMinDistance, dist);
+}
+
+/// Gets the current maximum distance filter of the radar (in meters).
+pub fn radarMaxDistance() f64 {
+    return readF64(.RadarMaxDistance);
+}
+
+/// Sets the maximum distance filter of the radar (in meters).
+pub fn setRadarMaxDistance(dist: f64) void {
+    writeF64(.RadarMaxDistance, dist);
+}
+
+/// Gets the Electronic Counter Measures (ECM) mode.
+pub fn radarEcmMode() EcmMode {
+    const value: u32 = @intFromFloat(readF64(.RadarEcmMode));
+    return if (value == @intFromEnum(EcmMode.Noise)) .Noise else .None;
+}
+
+/// Sets the Electronic Counter Measures (ECM) mode.
+pub fn setRadarEcmMode(mode: EcmMode) void {
+    writeF64(.RadarEcmMode, @floatFromInt(@intFromEnum(mode)));
+}
+
+/// Finds the radar contact with the highest signal strength.
+pub fn scan() ?ScanResult {
+    if (readF64(.RadarContactFound) == 0.0) {
+        return null;
+    }
+    return .{
+        .class = toClass(readF64(.RadarContactClass)),
+        .position = vec2(readF64(.RadarContactPositionX), readF64(.RadarContactPositionY)),
+        .velocity = vec2(readF64(.RadarContactVelocityX), readF64(.RadarContactVelocityY)),
+        .rssi = readF64(.RadarContactRssi),
+        .snr = readF64(.RadarContactSnr),
+    };
+}
+
+/// Finds the enemy radars whose beams are pointed at this ship, strongest first.
+///
+/// Returns the filled part of `buffer`.
+pub fn radarWarnings(buffer: *[MAX_RADAR_WARNINGS]RadarWarning) []RadarWarning {
+    const count = @min(@as(usize, @intFromFloat(readF64(.RadarWarningCount))), MAX_RADAR_WARNINGS);
+    for (buffer[0..count], 0..) |*warning, i| {
+        warning.* = .{
+            .bearing = readF64(offset(.RadarWarningBearing0, i)),
+            .rssi = readF64(offset(.RadarWarningRssi0, i)),
+        };
+    }
+    return buffer[0..count];
+}
+
+/// Select the radio to control with subsequent API calls.
+pub fn selectRadio(index: usize) void {
+    writeF64(.SelectedRadio, @floatFromInt(@min(index, MAX_RADIOS - 1)));
+}
+
+/// Sets the channel to send and receive radio transmissions on.
+pub fn setRadioChannel(channel: usize) void {
+    writeF64(radioState(.Radio0Channel), @floatFromInt(channel));
+}
+
+/// Gets the current radio channel.
+pub fn getRadioChannel() usize {
+    return @intFromFloat(readF64(radioState(.Radio0Channel)));
+}
+
+/// Sends a radio message. The message will be received on the next tick.
+pub fn send(msg: Message) void {
+    writeF64(radioState(.Radio0Send), 1.0);
+    for (msg, 0..) |value, i| {
+        writeF64(offset(radioState(.Radio0Data0), i), value);
+    }
+}
+
+/// Returns the received radio message.
+pub fn receive() ?Message {
+    if (readF64(radioState(.Radio0Receive)) == 0.0) {
+        return null;
+    }
+    var msg: Message = undefined;
+    for (&msg, 0..) |*value, i| {
+        value.* = readF64(offset(radioState(.Radio0Data0), i));
+    }
+    return msg;
+}
+
+/// Sends a radio message of up to 32 bytes.
+pub fn sendBytes(msg: []const u8) void {
+    var bytes = [_]u8{0} ** 32;
+    const n = @min(msg.len, bytes.len);
+    @memcpy(bytes[0..n], msg[0..n]);
+    writeF64(radioState(.Radio0Send), 1.0);
+    for (0..4) |i| {
+        writeU64(offset(radioState(.Radio0Data0), i), @bitCast(bytes[8 * i ..][0..8].*));
+    }
+}
+
+/// Returns the received 32 byte radio message.
+pub fn receiveBytes() ?[32]u8 {
+    if (readF64(radioState(.Radio0Receive)) == 0.0) {
+        return null;
+    }
+    var bytes: [32]u8 = undefined;
+    for (0..4) |i| {
+        bytes[8 * i ..][0..8].* = @bitCast(readU64(offset(radioState(.Radio0Data0), i)));
+    }
+    return bytes;
+}
+
+/// Returns the number of ticks elapsed since the simulation began.
+pub fn currentTick() u32 {
+    return @intFromFloat(readF64(.CurrentTick));
+}
+
+/// Returns the number of seconds elapsed since the simulation began.
+pub fn currentTime() f64 {
+    return readF64(.CurrentTick) * tickLength();
+}
+
+/// Activates a special ability.
+pub fn activateAbility(ability: Ability) void {
+    writeU64(.ActivateAbility, readU64(.ActivateAbility) | (@as(u64, 1) << @as(u6, @intCast(@intFromEnum(ability)))));
+}
+
+/// Deactivates a special ability.
+pub fn deactivateAbility(ability: Ability) void {
+    writeU64(.ActivateAbility, readU64(.ActivateAbility) & ~(@as(u64, 1) << @as(u6, @intCast(@intFromEnum(ability)))));
+}
+
+/// Returns the active abilities.
+pub fn activeAbilities() ActiveAbilities {
+    return .{ .bits = readU64(.ActivateAbility) };
+}
+
+/// Returns the position of the target set by the scenario.
+/// Only used in tutorials.
+pub fn target() Vec2 {
+    return vec2(readF64(.RadarContactPositionX), readF64(.RadarContactPositionY));
+}
+
+/// Returns the velocity of the target set by the scenario.
+/// Only used in tutorials.
+pub fn targetVelocity() Vec2 {
+    return vec2(readF64(.RadarContactVelocityX), readF64(.RadarContactVelocityY));
+}
+
+/// Returns the smallest rotation between angles `a` and `b`.
+///
+/// A positive result is a counter-clockwise rotation and negative is clockwise.
+pub fn angleDiff(a: f64, b: f64) f64 {
+    const c = @mod(b - a, TAU);
+    return if (c > PI) c - TAU else c;
+}
+
+// Same generator as the Rust API (oorandom::Rand64).
+const Rng = struct {
+    state: u128,
+    inc: u128,
+
+    fn init(seed_value: u128) Rng {
+        var r = Rng{ .state = 0, .inc = (0x2FE0E169FFBD06E35BC307BD4D2F814F << 1) | 1 };
+        _ = r.next();
+        r.state +%= seed_value;
+        _ = r.next();
+        return r;
+    }
+
+    fn next(self: *Rng) u64 {
+        const old = self.state;
+        self.state = old *% 0x2360ED051FC65DA44385DF649FCCF645 +% self.inc;
+        const xorshifted: u64 = @truncate(((old >> 29) ^ old) >> 58);
+        const rot: u6 = @intCast(old >> 122);
+        return std.math.rotr(u64, xorshifted, rot);
+    }
+
+    fn nextFloat(self: *Rng) f64 {
+        return @as(f64, @floatFromInt(self.next() >> 10)) * (1.0 / @as(f64, 1 << 54));
+    }
+};
+
+var rng: ?Rng = null;
+
+/// Returns a random number between `low` and `high`.
+pub fn rand(low: f64, high: f64) f64 {
+    if (rng == null) {
+        rng = Rng.init(seed());
+    }
+    return rng.?.nextFloat() * (high - low) + low;
+}
+
+/// Creates a 24-bit RGB color from the arguments.
+pub fn rgb(r: u8, g: u8, b: u8) u32 {
+    return @as(u32, r) << 16 | @as(u32, g) << 8 | @as(u32, b);
+}
+
+const Line = extern struct {
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+    color: u32,
+};
+
+const Text = extern struct {
+    x: f64,
+    y: f64,
+    color: u32,
+    length: u8,
+    text: [11]u8,
+};
+
+const MAX_DEBUG_TEXT = 4096;
+const MAX_LINES = 1024;
+const MAX_TEXTS = 128;
+
+var debug_text: [MAX_DEBUG_TEXT]u8 = undefined;
+var debug_text_length: usize = 0;
+var lines: [MAX_LINES]Line = undefined;
+var num_lines: usize = 0;
+var texts: [MAX_TEXTS]Text = undefined;
+var num_texts: usize = 0;
+
+/// Draws a line visible in debug mode.
+///
+/// Up to 1024 lines can be drawn per ship, per tick.
+pub fn drawLine(a: Vec2, b: Vec2, color: u32) void {
+    if (num_lines >= MAX_LINES) {
+        return;
+    }
+    lines[num_lines] = .{ .x0 = a.x, .y0 = a.y, .x1 = b.x, .y1 = b.y, .color = color };
+    num_lines += 1;
+}
+
+/// Draws a regular polygon visible in debug mode.
+pub fn drawPolygon(center: Vec2, radius: f64, sides: i32, radians: f64, color: u32) void {
+    const delta = TAU / @as(f64, @floatFromInt(sides));
+    var p = vec2(radius, 0.0).rotate(radians);
+    var i: i32 = 0;
+    while (i < sides) : (i += 1) {
+        const p2 = p.rotate(delta);
+        drawLine(center.add(p), center.add(p2), color);
+        p = p2;
+    }
+}
+
+/// Draws a triangle visible in debug mode.
+pub fn drawTriangle(center: Vec2, radius: f64, color: u32) void {
+    const x = @sqrt(3.0) * radius / 2.0;
+    const y = radius / 2.0;
+    const points = [_]Vec2{
+        center.add(vec2(0.0, radius)),
+        center.add(vec2(-x, -y)),
+        center.add(vec2(x, -y)),
+    };
+    for (0..3) |i| {
+        drawLine(points[i], points[(i + 1) % 3], color);
+    }
+}
+
+/// Draws a square visible in debug mode.
+pub fn drawSquare(center: Vec2, radius: f64, color: u32) void {
+    const o = radius / @sqrt(2.0);
+    var p = vec2(o, o);
+    for (0..4) |_| {
+        const p2 = vec2(-p.y, p.x);
+        drawLine(center.add(p), center.add(p2), color);
+        p = p2;
+    }
+}
+
+/// Draws a diamond visible in debug mode.
+pub fn drawDiamond(center: Vec2, radius: f64, color: u32) void {
+    var p = vec2(radius, 0.0);
+    for (0..4) |_| {
+        const p2 = vec2(-p.y, p.x);
+        drawLine(center.add(p), center.add(p2), color);
+        p = p2;
+    }
+}
+
+/// Adds text to be displayed when the ship is selected by clicking on it.
+///
+/// Takes the same arguments as `std.fmt.format`.
+pub fn debug(comptime fmt: []const u8, args: anytype) void {
+    const buf = debug_text[debug_text_length..];
+    if (buf.len < 2) {
+        return;
+    }
+    const written = std.fmt.bufPrint(buf[0 .. buf.len - 1], fmt, args) catch return;
+    debug_text_length += written.len;
+    debug_text[debug_text_length] = '\n';
+    debug_text_length += 1;
+}
+
+/// Adds text to be drawn in the world, visible in debug mode.
+///
+/// Up to 128 strings can be drawn per ship, per tick.
+pub fn drawText(topleft: Vec2, color: u32, comptime fmt: []const u8, args: anytype) void {
+    if (num_texts >= MAX_TEXTS) {
+        return;
+    }
+    var buf: [64]u8 = undefined;
+    const s = std.fmt.bufPrint(&buf, fmt, args) catch buf[0..];
+    const text = &texts[num_texts];
+    num_texts += 1;
+    text.x = topleft.x;
+    text.y = topleft.y;
+    text.color = color;
+    text.length = @intCast(@min(s.len, text.text.len));
+    @memcpy(text.text[0..text.length], s[0..text.length]);
+}
+
+fn reset() void {
+    debug_text_length = 0;
+    num_lines = 0;
+    num_texts = 0;
+    PANIC_BUFFER[0] = 0;
+}
+
+fn update() void {
+    writeF64(.DebugTextPointer, @floatFromInt(@intFromPtr(&debug_text)));
+    writeF64(.DebugTextLength, @floatFromInt(debug_text_length));
+    writeF64(.DebugLinesPointer, @floatFromInt(@intFromPtr(&lines)));
+    writeF64(.DebugLinesLength, @floatFromInt(num_lines));
+    writeF64(.DrawnTextPointer, @floatFromInt(@intFromPtr(&texts)));
+    writeF64(.DrawnTextLength, @floatFromInt(num_texts));
+}
+
+pub fn panic(msg: []const u8, _: ?*std.builtin.StackTrace, _: ?usize) noreturn {
+    const n = @min(msg.len, PANIC_BUFFER.len - 1);
+    @memcpy(PANIC_BUFFER[0..n], msg[0..n]);
+    PANIC_BUFFER[n] = 0;
+    @trap();
+}
+
+export fn tick() void {
+    reset();
+    user.tick();
+    update();
+}
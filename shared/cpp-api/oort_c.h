@@ -0,0 +1,670 @@
+// Generated from shared/api by shared/cpp-api. Do not edit.
+// Regenerate with `cargo run -p oort_cpp_api`.
+
+#ifndef OORT_C_H
+#define OORT_C_H
+
+// C equivalent of the Rust `oort_api::prelude`. Included by oort.h.
+//
+// Functions are prefixed with `oort_` to avoid clashing with the C library.
+
+#include "oort.h"
+
+#include <math.h>
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#define OORT_PI 3.14159265358979323846
+#define OORT_TAU (2.0 * OORT_PI)
+
+/// Identifiers for each class of ship.
+typedef enum ShipClass {
+    ShipClass_Fighter = 0,
+    ShipClass_Frigate = 1,
+    ShipClass_Cruiser = 2,
+    ShipClass_Asteroid = 3,
+    ShipClass_Target = 4,
+    ShipClass_Missile = 5,
+    ShipClass_Torpedo = 6,
+    ShipClass_Unknown = 7,
+} ShipClass;
+
+/// Electronic Counter Measures (ECM) modes.
+typedef enum EcmMode {
+    EcmMode_None = 0,
+    EcmMode_Noise = 1,
+} EcmMode;
+
+/// Special abilities available to different ship classes.
+typedef enum Ability {
+    Ability_None = 0,
+    Ability_Boost = 1,
+    Ability_ShapedCharge = 2,
+    Ability_Decoy = 3,
+    Ability_Shield = 4,
+    Ability_Cloak = 5,
+    Ability_PointDefense = 6,
+    Ability_TractorBeam = 7,
+} Ability;
+
+
+/// A two-dimensional vector.
+typedef struct Vec2 {
+    double x;
+    double y;
+} Vec2;
+
+/// Returns a Vec2 with the given coordinates.
+static inline Vec2 vec2(double x, double y) {
+    Vec2 v = {x, y};
+    return v;
+}
+
+static inline Vec2 vec2_add(Vec2 a, Vec2 b) { return vec2(a.x + b.x, a.y + b.y); }
+static inline Vec2 vec2_sub(Vec2 a, Vec2 b) { return vec2(a.x - b.x, a.y - b.y); }
+static inline Vec2 vec2_scale(Vec2 a, double b) { return vec2(a.x * b, a.y * b); }
+static inline Vec2 vec2_div(Vec2 a, double b) { return vec2(a.x / b, a.y / b); }
+
+/// Returns the length (or distance from origin).
+static inline double vec2_length(Vec2 a) { return sqrt(a.x * a.x + a.y * a.y); }
+
+/// Returns a normalized vector with the same direction but length of 1.
+static inline Vec2 vec2_normalize(Vec2 a) { return vec2_div(a, vec2_length(a)); }
+
+/// Returns the distance between `a` and `b`.
+static inline double vec2_distance(Vec2 a, Vec2 b) { return vec2_length(vec2_sub(a, b)); }
+
+/// Returns the dot product of `a` and `b`.
+static inline double vec2_dot(Vec2 a, Vec2 b) { return a.x * b.x + a.y * b.y; }
+
+/// Returns the angle of the vector (in radians).
+static inline double vec2_angle(Vec2 a) {
+    double angle = atan2(a.y, a.x);
+    if (angle < 0.0) {
+        angle += OORT_TAU;
+    }
+    return angle;
+}
+
+/// Returns the vector rotated by the given angle (in radians).
+static inline Vec2 vec2_rotate(Vec2 a, double angle) {
+    double c = cos(angle);
+    double s = sin(angle);
+    return vec2(a.x * c - a.y * s, a.x * s + a.y * c);
+}
+
+/// Message sent and received on the radio.
+typedef struct Message {
+    double data[4];
+} Message;
+
+/// A radar contact.
+typedef struct ScanResult {
+    /// The contact's class.
+    ShipClass class_;
+    /// The contact's approximate position.
+    Vec2 position;
+    /// The contact's approximate velocity.
+    Vec2 velocity;
+    /// The received signal strength measured in dBm.
+    double rssi;
+    /// The signal-to-noise ratio measured in dB.
+    double snr;
+} ScanResult;
+
+/// An enemy radar illuminating this ship.
+typedef struct RadarWarning {
+    /// The direction to the enemy radar (in radians).
+    double bearing;
+    /// The received signal strength measured in dBm.
+    double rssi;
+} RadarWarning;
+
+#define OORT_MAX_RADIOS 8
+#define OORT_RADIO_STRIDE 7
+/// The time between each simulation tick.
+#define OORT_TICK_LENGTH (1.0 / 60.0)
+/// The maximum number of radars reported by [`radar_warnings`].
+#define OORT_MAX_RADAR_WARNINGS (4)
+
+// Debug drawing buffers, defined in oort.c.
+struct OortLine {
+    double x0;
+    double y0;
+    double x1;
+    double y1;
+    uint32_t color;
+};
+
+struct OortText {
+    double x;
+    double y;
+    uint32_t color;
+    uint8_t length;
+    char text[11];
+};
+
+#define OORT_MAX_DEBUG_TEXT 4096
+#define OORT_MAX_LINES 1024
+#define OORT_MAX_TEXTS 128
+
+extern char oort_debug_text[OORT_MAX_DEBUG_TEXT];
+extern size_t oort_debug_text_length;
+extern struct OortLine oort_lines[OORT_MAX_LINES];
+extern size_t oort_num_lines;
+extern struct OortText oort_texts[OORT_MAX_TEXTS];
+extern size_t oort_num_texts;
+
+static inline enum SystemState oort_radio_state(enum SystemState radio0_state) {
+    size_t index = (size_t)read_f64(SelectedRadio);
+    if (index >= OORT_MAX_RADIOS) {
+        abort();
+    }
+    return (enum SystemState)(radio0_state + OORT_RADIO_STRIDE * index);
+}
+
+static inline bool oort_getenv(const char* key, char* value, size_t size) {
+    // Format is key=value\nkey=value\n... ending with a null byte.
+    const char* p = (const char*)ENVIRONMENT;
+    const char* end = memchr(p, 0, OORT_ENVIRONMENT_SIZE);
+    if (!end) {
+        end = p + OORT_ENVIRONMENT_SIZE;
+    }
+    size_t key_len = strlen(key);
+    while (p < end) {
+        const char* eol = memchr(p, '\n', end - p);
+        if (!eol) {
+            eol = end;
+        }
+        if ((size_t)(eol - p) > key_len && strncmp(p, key, key_len) == 0 && p[key_len] == '=') {
+            size_t n = eol - p - key_len - 1;
+            if (n >= size) {
+                n = size - 1;
+            }
+            memcpy(value, p + key_len + 1, n);
+            value[n] = 0;
+            return true;
+        }
+        p = eol + 1;
+    }
+    return false;
+}
+
+/// Returns a per-ship ID that is unique within a team.
+static inline uint32_t oort_id(void) {
+    return (uint32_t)read_f64(Id);
+}
+
+/// Returns the ship class (Fighter, Cruiser, etc).
+static inline ShipClass oort_class(void) {
+    uint32_t value = (uint32_t)read_f64(Class);
+    return value > ShipClass_Unknown ? ShipClass_Unknown : (ShipClass)value;
+}
+
+/// Returns a random number useful for initializing a random number generator.
+static inline uint64_t oort_seed(void) {
+    return (uint64_t)read_f64(Seed);
+}
+
+/// Writes the scenario name into `name`.
+static inline void oort_scenario_name(char* name, size_t size) {
+    if (!oort_getenv("SCENARIO_NAME", name, size)) {
+        snprintf(name, size, "unknown");
+    }
+}
+
+/// Returns the world size in meters.
+static inline double oort_world_size(void) {
+    char value[32];
+    return oort_getenv("WORLD_SIZE", value, sizeof(value)) ? strtod(value, NULL) : 0.0;
+}
+
+/// Returns the time between each simulation tick in this scenario.
+///
+/// This is usually OORT_TICK_LENGTH, but scenarios can change it.
+static inline double oort_tick_length(void) {
+    char value[32];
+    return oort_getenv("TICK_LENGTH", value, sizeof(value)) ? strtod(value, NULL) : OORT_TICK_LENGTH;
+}
+
+/// Returns the current position (in meters).
+static inline Vec2 oort_position(void) {
+    return vec2(read_f64(PositionX), read_f64(PositionY));
+}
+
+/// Returns the current velocity (in m/s).
+static inline Vec2 oort_velocity(void) {
+    return vec2(read_f64(VelocityX), read_f64(VelocityY));
+}
+
+/// Returns the current heading (in radians).
+static inline double oort_heading(void) {
+    return read_f64(Heading);
+}
+
+/// Returns the current angular velocity (in radians/s).
+static inline double oort_angular_velocity(void) {
+    return read_f64(AngularVelocity);
+}
+
+/// Returns the maximum forward acceleration (in m/s²).
+static inline double oort_max_forward_acceleration(void) {
+    return read_f64(MaxForwardAcceleration);
+}
+
+/// Returns the maximum backward acceleration (in m/s²).
+static inline double oort_max_backward_acceleration(void) {
+    return read_f64(MaxBackwardAcceleration);
+}
+
+/// Returns the maximum lateral acceleration (in m/s²).
+static inline double oort_max_lateral_acceleration(void) {
+    return read_f64(MaxLateralAcceleration);
+}
+
+/// Returns the maximum angular acceleration (in radians/s²).
+static inline double oort_max_angular_acceleration(void) {
+    return read_f64(MaxAngularAcceleration);
+}
+
+/// Sets the linear acceleration for the next tick (in m/s²).
+static inline void oort_accelerate(Vec2 acceleration) {
+    acceleration = vec2_rotate(acceleration, -oort_heading());
+    if (acceleration.x > oort_max_forward_acceleration()) {
+        acceleration = vec2_scale(acceleration, oort_max_forward_acceleration() / acceleration.x);
+    }
+    if (acceleration.x < -oort_max_backward_acceleration()) {
+        acceleration = vec2_scale(acceleration, oort_max_backward_acceleration() / -acceleration.x);
+    }
+    if (fabs(acceleration.y) > oort_max_lateral_acceleration()) {
+        acceleration = vec2_scale(acceleration, oort_max_lateral_acceleration() / fabs(acceleration.y));
+    }
+    write_f64(AccelerateX, acceleration.x);
+    write_f64(AccelerateY, acceleration.y);
+}
+
+/// Sets the angular acceleration for the next tick (in radians/s²).
+static inline void oort_torque(double angular_acceleration) {
+    write_f64(Torque, angular_acceleration);
+}
+
+/// Rotates the ship at the given speed (in radians/s).
+static inline void oort_turn(double speed) {
+    double max = oort_max_angular_acceleration() * 0.2;
+    double clamped = speed < -max ? -max : (speed > max ? max : speed);
+    oort_torque(copysign(1.0, clamped - oort_angular_velocity()) * oort_max_angular_acceleration());
+}
+
+/// Aims a turreted weapon. `heading` is in radians.
+static inline void oort_aim(size_t index, double heading) {
+    if (index < 4) {
+        write_f64((enum SystemState)(Aim0 + index), heading);
+    }
+}
+
+/// Fires a weapon.
+static inline void oort_fire(size_t index) {
+    if (index < 4) {
+        write_f64((enum SystemState)(Fire0 + index), 1.0);
+    }
+}
+
+/// Returns the number of ticks until a weapon is ready to fire.
+static inline uint32_t oort_reload_ticks(size_t index) {
+    if (index >= 4) {
+        return 0;
+    }
+    return (uint32_t)read_f64((enum SystemState)(ReloadTicks0 + index));
+}
+
+/// Self-destructs, producing a damaging explosion.
+static inline void oort_explode(void) {
+    write_f64(Explode, 1.0);
+}
+
+/// Returns the current health.
+static inline double oort_health(void) {
+    return read_f64(Health);
+}
+
+/// Returns the current fuel (delta-v).
+static inline double oort_fuel(void) {
+    return read_f64(Fuel);
+}
+
+/// Returns the current energy.
+///
+/// Guns, missile launchers, radar and abilities may draw from it, and it
+/// regenerates over time. Returns infinity if the ship doesn't use energy.
+static inline double oort_energy(void) {
+    return read_f64(Energy);
+}
+
+/// Returns the heading the radar is pointed at.
+static inline double oort_radar_heading(void) {
+    return read_f64(RadarHeading);
+}
+
+/// Sets the heading to point the radar at. It takes effect next tick.
+static inline void oort_set_radar_heading(double heading) {
+    write_f64(RadarHeading, heading);
+}
+
+/// Returns the current radar width (in radians).
+static inline double oort_radar_width(void) {
+    return read_f64(RadarWidth);
+}
+
+/// Sets the radar width (in radians). It takes effect next tick.
+static inline void oort_set_radar_width(double width) {
+    write_f64(RadarWidth, width);
+}
+
+/// Gets the current minimum distance filter of the radar (in meters).
+static inline double oort_radar_min_distance(void) {
+    return read_f64(RadarMinDistance);
+}
+
+/// Sets the minimum distance filter of the radar (in meters).
+static inline void oort_set_radar_min_distance(double dist) {
+    write_f64(RadarMinDistance, dist);
+}
+
+/// Gets the current maximum distance filter of the radar (in meters).
+static inline double oort_radar_max_distance(void) {
+    return read_f64(RadarMaxDistance);
+}
+
+/// Sets the maximum distance filter of the radar (in meters).
+static inline void oort_set_radar_max_distance(double dist) {
+    write_f64(RadarMaxDistance, dist);
+}
+
+/// Gets the Electronic Counter Measures (ECM) mode.
+static inline EcmMode oort_radar_ecm_mode(void) {
+    return (uint32_t)read_f64(RadarEcmMode) == EcmMode_Noise ? EcmMode_Noise : EcmMode_None;
+}
+
+/// Sets the Electronic Counter Measures (ECM) mode.
+static inline void oort_set_radar_ecm_mode(EcmMode mode) {
+    write_f64(RadarEcmMode, (double)mode);
+}
+
+/// Finds the radar contact with the highest signal strength.
+///
+/// Returns false if there is no contact.
+static inline bool oort_scan(ScanResult* result) {
+    if (read_f64(RadarContactFound) == 0.0) {
+        return false;
+    }
+    uint32_t class_ = (uint32_t)read_f64(RadarContactClass);
+    result->class_ = class_ > ShipClass_Unknown ? ShipClass_Unknown : (ShipClass)class_;
+    result->position = vec2(read_f64(RadarContactPositionX), read_f64(RadarContactPositionY));
+    result->velocity = vec2(read_f64(RadarContactVelocityX), read_f64(RadarContactVelocityY));
+    result->rssi = read_f64(RadarContactRssi);
+    result->snr = read_f64(RadarContactSnr);
+    return true;
+}
+
+/// Finds the enemy radars whose beams are pointed at this ship, strongest first.
+///
+/// `warnings` must have room for OORT_MAX_RADAR_WARNINGS entries. Returns the
+/// number of warnings written.
+static inline size_t oort_radar_warnings(RadarWarning* warnings) {
+    size_t count = (size_t)read_f64(RadarWarningCount);
+    if (count > OORT_MAX_RADAR_WARNINGS) {
+        count = OORT_MAX_RADAR_WARNINGS;
+    }
+    for (size_t i = 0; i < count; i++) {
+        warnings[i].bearing = read_f64((enum SystemState)(RadarWarningBearing0 + i));
+        warnings[i].rssi = read_f64((enum SystemState)(RadarWarningRssi0 + i));
+    }
+    return count;
+}
+
+/// Select the radio to control with subsequent API calls.
+static inline void oort_select_radio(size_t index) {
+    write_f64(SelectedRadio, (double)(index < OORT_MAX_RADIOS ? index : OORT_MAX_RADIOS - 1));
+}
+
+/// Sets the channel to send and receive radio transmissions on.
+static inline void oort_set_radio_channel(size_t channel) {
+    write_f64(oort_radio_state(Radio0Channel), (double)channel);
+}
+
+/// Gets the current radio channel.
+static inline size_t oort_get_radio_channel(void) {
+    return (size_t)read_f64(oort_radio_state(Radio0Channel));
+}
+
+/// Sends a radio message. The message will be received on the next tick.
+static inline void oort_send(Message msg) {
+    write_f64(oort_radio_state(Radio0Send), 1.0);
+    for (size_t i = 0; i < 4; i++) {
+        write_f64((enum SystemState)(oort_radio_state(Radio0Data0) + i), msg.data[i]);
+    }
+}
+
+/// Receives a radio message. Returns false if no message was received.
+static inline bool oort_receive(Message* msg) {
+    if (read_f64(oort_radio_state(Radio0Receive)) == 0.0) {
+        return false;
+    }
+    for (size_t i = 0; i < 4; i++) {
+        msg->data[i] = read_f64((enum SystemState)(oort_radio_state(Radio0Data0) + i));
+    }
+    return true;
+}
+
+/// Sends a radio message of up to 32 bytes.
+static inline void oort_send_bytes(const uint8_t* msg, size_t length) {
+    uint8_t bytes[32] = {0};
+    memcpy(bytes, msg, length < sizeof(bytes) ? length : sizeof(bytes));
+    write_f64(oort_radio_state(Radio0Send), 1.0);
+    for (size_t i = 0; i < 4; i++) {
+        uint64_t value;
+        memcpy(&value, bytes + 8 * i, sizeof(value));
+        write_u64((enum SystemState)(oort_radio_state(Radio0Data0) + i), value);
+    }
+}
+
+/// Receives a 32 byte radio message. Returns false if no message was received.
+static inline bool oort_receive_bytes(uint8_t bytes[32]) {
+    if (read_f64(oort_radio_state(Radio0Receive)) == 0.0) {
+        return false;
+    }
+    for (size_t i = 0; i < 4; i++) {
+        uint64_t value = read_u64((enum SystemState)(oort_radio_state(Radio0Data0) + i));
+        memcpy(bytes + 8 * i, &value, sizeof(value));
+    }
+    return true;
+}
+
+/// Returns the number of ticks elapsed since the simulation began.
+static inline uint32_t oort_current_tick(void) {
+    return (uint32_t)read_f64(CurrentTick);
+}
+
+/// Returns the number of seconds elapsed since the simulation began.
+static inline double oort_current_time(void) {
+    return read_f64(CurrentTick) * oort_tick_length();
+}
+
+/// Activates a special ability.
+static inline void oort_activate_ability(Ability ability) {
+    write_u64(ActivateAbility, read_u64(ActivateAbility) | (1ull << ability));
+}
+
+/// Deactivates a special ability.
+static inline void oort_deactivate_ability(Ability ability) {
+    write_u64(ActivateAbility, read_u64(ActivateAbility) & ~(1ull << ability));
+}
+
+/// Returns a bitmask of the active abilities, indexed by Ability.
+static inline uint64_t oort_active_abilities(void) {
+    return read_u64(ActivateAbility);
+}
+
+/// Returns the position of the target set by the scenario.
+/// Only used in tutorials.
+static inline Vec2 oort_target(void) {
+    return vec2(read_f64(RadarContactPositionX), read_f64(RadarContactPositionY));
+}
+
+/// Returns the velocity of the target set by the scenario.
+/// Only used in tutorials.
+static inline Vec2 oort_target_velocity(void) {
+    return vec2(read_f64(RadarContactVelocityX), read_f64(RadarContactVelocityY));
+}
+
+/// Returns the smallest rotation between angles `a` and `b`.
+///
+/// A positive result is a counter-clockwise rotation and negative is clockwise.
+static inline double oort_angle_diff(double a, double b) {
+    double c = fmod(b - a, OORT_TAU);
+    if (c < 0.0) {
+        c += OORT_TAU;
+    }
+    return c > OORT_PI ? c - OORT_TAU : c;
+}
+
+// Same generator as the Rust API (oorandom::Rand64).
+static inline uint64_t oort_rng_next(unsigned __int128* state, unsigned __int128 inc) {
+    const unsigned __int128 multiplier =
+        ((unsigned __int128)0x2360ED051FC65DA4ull << 64) | 0x4385DF649FCCF645ull;
+    unsigned __int128 old = *state;
+    *state = old * multiplier + inc;
+    uint64_t xorshifted = (uint64_t)(((old >> 29) ^ old) >> 58);
+    uint32_t rot = (uint32_t)(old >> 122);
+    return (xorshifted >> rot) | (xorshifted << ((64 - rot) & 63));
+}
+
+/// Returns a random number between `low` and `high`.
+static inline double oort_rand(double low, double high) {
+    static bool initialized = false;
+    static unsigned __int128 state;
+    static unsigned __int128 inc;
+    if (!initialized) {
+        const unsigned __int128 default_inc =
+            ((unsigned __int128)0x2FE0E169FFBD06E3ull << 64) | 0x5BC307BD4D2F814Full;
+        state = 0;
+        inc = (default_inc << 1) | 1;
+        oort_rng_next(&state, inc);
+        state += oort_seed();
+        oort_rng_next(&state, inc);
+        initialized = true;
+    }
+    uint64_t value = oort_rng_next(&state, inc) >> 10;
+    return (double)value * (1.0 / (double)(1ull << 54)) * (high - low) + low;
+}
+
+/// Creates a 24-bit RGB color from the arguments.
+static inline uint32_t oort_rgb(uint8_t r, uint8_t g, uint8_t b) {
+    return (uint32_t)r << 16 | (uint32_t)g << 8 | (uint32_t)b;
+}
+
+/// Draws a line visible in debug mode.
+///
+/// Up to 1024 lines can be drawn per ship, per tick.
+static inline void oort_draw_line(Vec2 a, Vec2 b, uint32_t color) {
+    if (oort_num_lines >= OORT_MAX_LINES) {
+        return;
+    }
+    struct OortLine line = {a.x, a.y, b.x, b.y, color};
+    oort_lines[oort_num_lines++] = line;
+}
+
+/// Draws a regular polygon visible in debug mode.
+static inline void oort_draw_polygon(Vec2 center, double radius, int32_t sides, double angle, uint32_t color) {
+    double delta_angle = OORT_TAU / sides;
+    Vec2 p = vec2_rotate(vec2(radius, 0.0), angle);
+    for (int32_t i = 0; i < sides; i++) {
+        Vec2 p2 = vec2_rotate(p, delta_angle);
+        oort_draw_line(vec2_add(center, p), vec2_add(center, p2), color);
+        p = p2;
+    }
+}
+
+/// Draws a triangle visible in debug mode.
+static inline void oort_draw_triangle(Vec2 center, double radius, uint32_t color) {
+    double x = sqrt(3.0) * radius / 2.0;
+    double y = radius / 2.0;
+    Vec2 points[3] = {
+        vec2_add(center, vec2(0.0, radius)),
+        vec2_add(center, vec2(-x, -y)),
+        vec2_add(center, vec2(x, -y)),
+    };
+    for (int i = 0; i < 3; i++) {
+        oort_draw_line(points[i], points[(i + 1) % 3], color);
+    }
+}
+
+/// Draws a square visible in debug mode.
+static inline void oort_draw_square(Vec2 center, double radius, uint32_t color) {
+    double offset = radius / sqrt(2.0);
+    Vec2 p = vec2(offset, offset);
+    for (int i = 0; i < 4; i++) {
+        Vec2 p2 = vec2(-p.y, p.x);
+        oort_draw_line(vec2_add(center, p), vec2_add(center, p2), color);
+        p = p2;
+    }
+}
+
+/// Draws a diamond visible in debug mode.
+static inline void oort_draw_diamond(Vec2 center, double radius, uint32_t color) {
+    Vec2 p = vec2(radius, 0.0);
+    for (int i = 0; i < 4; i++) {
+        Vec2 p2 = vec2(-p.y, p.x);
+        oort_draw_line(vec2_add(center, p), vec2_add(center, p2), color);
+        p = p2;
+    }
+}
+
+/// Adds text to be displayed when the ship is selected by clicking on it.
+///
+/// Works just like printf.
+__attribute__((format(printf, 1, 2)))
+static inline void oort_debug(const char* format, ...) {
+    size_t remaining = OORT_MAX_DEBUG_TEXT - oort_debug_text_length;
+    if (remaining < 2) {
+        return;
+    }
+    va_list args;
+    va_start(args, format);
+    int n = vsnprintf(oort_debug_text + oort_debug_text_length, remaining - 1, format, args);
+    va_end(args);
+    if (n < 0) {
+        return;
+    }
+    oort_debug_text_length += (size_t)n < remaining - 2 ? (size_t)n : remaining - 2;
+    oort_debug_text[oort_debug_text_length++] = '\n';
+}
+
+/// Adds text to be drawn in the world, visible in debug mode.
+///
+/// Works like printf. Up to 128 strings can be drawn per ship, per tick.
+__attribute__((format(printf, 3, 4)))
+static inline void oort_draw_text(Vec2 topleft, uint32_t color, const char* format, ...) {
+    if (oort_num_texts >= OORT_MAX_TEXTS) {
+        return;
+    }
+    char buf[64];
+    va_list args;
+    va_start(args, format);
+    int n = vsnprintf(buf, sizeof(buf), format, args);
+    va_end(args);
+    if (n < 0) {
+        return;
+    }
+    struct OortText* text = &oort_texts[oort_num_texts++];
+    text->x = topleft.x;
+    text->y = topleft.y;
+    text->color = color;
+    text->length = (uint8_t)((size_t)n < sizeof(text->text) ? (size_t)n : sizeof(text->text));
+    memcpy(text->text, buf, text->length);
+}
+
+#endif
@@ -0,0 +1,815 @@
+// Generated from shared/api by shared/cpp-api. Do not edit.
+// Regenerate with `cargo run -p oort_cpp_api`.
+
+#ifndef OORT_PRELUDE_H
+#define OORT_PRELUDE_H
+
+// C++ equivalent of the Rust `oort_api::prelude`. Included by oort.h.
+
+#include "oort.h"
+
+#include <algorithm>
+#include <array>
+#include <cmath>
+#include <cstdarg>
+#include <cstdio>
+#include <cstdlib>
+#include <cstring>
+#include <optional>
+#include <string>
+#include <vector>
+
+namespace oort {
+
+constexpr double PI = 3.14159265358979323846;
+constexpr double TAU = 2.0 * PI;
+
+/// Identifiers for each class of ship.
+enum class ShipClass : uint32_t {
+    Fighter = 0,
+    Frigate = 1,
+    Cruiser = 2,
+    Asteroid = 3,
+    Target = 4,
+    Missile = 5,
+    Torpedo = 6,
+    Unknown = 7,
+};
+
+/// Electronic Counter Measures (ECM) modes.
+enum class EcmMode : uint32_t {
+    None = 0,
+    Noise = 1,
+};
+
+/// Special abilities available to different ship classes.
+enum class Ability : uint32_t {
+    None = 0,
+    Boost = 1,
+    ShapedCharge = 2,
+    Decoy = 3,
+    Shield = 4,
+    Cloak = 5,
+    PointDefense = 6,
+    TractorBeam = 7,
+};
+
+
+/// A two-dimensional vector.
+struct Vec2 {
+    double x = 0.0;
+    double y = 0.0;
+
+    /// Returns the length (or distance from origin).
+    double length() const { return std::sqrt(x * x + y * y); }
+
+    /// Returns a normalized vector with the same direction but length of 1.
+    Vec2 normalize() const { double l = length(); return Vec2{x / l, y / l}; }
+
+    /// Returns the distance to `other`.
+    double distance(Vec2 other) const { return Vec2{x - other.x, y - other.y}.length(); }
+
+    /// Returns the dot product with `other`.
+    double dot(Vec2 other) const { return x * other.x + y * other.y; }
+
+    /// Returns the angle of the vector (in radians).
+    double angle() const {
+        double a = std::atan2(y, x);
+        if (a < 0.0) {
+            a += TAU;
+        }
+        return a;
+    }
+
+    /// Returns this vector rotated by the given angle (in radians).
+    Vec2 rotate(double angle) const {
+        double c = std::cos(angle);
+        double s = std::sin(angle);
+        return Vec2{x * c - y * s, x * s + y * c};
+    }
+};
+
+inline Vec2 operator+(Vec2 a, Vec2 b) { return Vec2{a.x + b.x, a.y + b.y}; }
+inline Vec2 operator-(Vec2 a, Vec2 b) { return Vec2{a.x - b.x, a.y - b.y}; }
+inline Vec2 operator-(Vec2 a) { return Vec2{-a.x, -a.y}; }
+inline Vec2 operator*(Vec2 a, double b) { return Vec2{a.x * b, a.y * b}; }
+inline Vec2 operator*(double a, Vec2 b) { return Vec2{a * b.x, a * b.y}; }
+inline Vec2 operator/(Vec2 a, double b) { return Vec2{a.x / b, a.y / b}; }
+inline Vec2& operator+=(Vec2& a, Vec2 b) { return a = a + b; }
+inline Vec2& operator-=(Vec2& a, Vec2 b) { return a = a - b; }
+inline Vec2& operator*=(Vec2& a, double b) { return a = a * b; }
+inline Vec2& operator/=(Vec2& a, double b) { return a = a / b; }
+inline bool operator==(Vec2 a, Vec2 b) { return a.x == b.x && a.y == b.y; }
+inline bool operator!=(Vec2 a, Vec2 b) { return !(a == b); }
+
+/// Returns a Vec2 with the given coordinates.
+inline Vec2 vec2(double x, double y) { return Vec2{x, y}; }
+
+/// Message sent and received on the radio.
+using Message = std::array<double, 4>;
+
+/// A radar contact.
+struct ScanResult {
+    /// The contact's class.
+    ShipClass class_;
+    /// The contact's approximate position.
+    Vec2 position;
+    /// The contact's approximate velocity.
+    Vec2 velocity;
+    /// The received signal strength measured in dBm.
+    double rssi;
+    /// The signal-to-noise ratio measured in dB.
+    double snr;
+};
+
+/// An enemy radar illuminating this ship.
+struct RadarWarning {
+    /// The direction to the enemy radar (in radians).
+    double bearing;
+    /// The received signal strength measured in dBm.
+    double rssi;
+};
+
+/// List of active abilities for an entity.
+struct ActiveAbilities {
+    uint64_t bits;
+
+    /// Get whether an ability is active.
+    bool get_ability(Ability ability) const { return (bits >> (uint64_t)ability) & 1; }
+};
+
+constexpr size_t MAX_RADIOS = 8;
+constexpr size_t RADIO_STRIDE = 7;
+/// The time between each simulation tick.
+constexpr double TICK_LENGTH = 1.0 / 60.0;
+/// The maximum number of radars reported by [`radar_warnings`].
+constexpr size_t MAX_RADAR_WARNINGS = 4;
+
+inline uint32_t id();
+inline ShipClass ship_class();
+inline uint64_t seed();
+inline std::string scenario_name();
+inline double world_size();
+inline double tick_length();
+inline Vec2 position();
+inline Vec2 velocity();
+inline double heading();
+inline double angular_velocity();
+inline void accelerate(Vec2 acceleration);
+inline void turn(double speed);
+inline void torque(double angular_acceleration);
+inline void aim(size_t index, double heading);
+inline void fire(size_t index);
+inline uint32_t reload_ticks(size_t index);
+inline void explode();
+inline double health();
+inline double fuel();
+inline double energy();
+inline double radar_heading();
+inline void set_radar_heading(double heading);
+inline double radar_width();
+inline void set_radar_width(double width);
+inline double radar_min_distance();
+inline void set_radar_min_distance(double dist);
+inline double radar_max_distance();
+inline void set_radar_max_distance(double dist);
+inline EcmMode radar_ecm_mode();
+inline void set_radar_ecm_mode(EcmMode mode);
+inline std::optional<ScanResult> scan();
+inline std::vector<RadarWarning> radar_warnings();
+inline void select_radio(size_t index);
+inline void set_radio_channel(size_t channel);
+inline size_t get_radio_channel();
+inline void send(Message msg);
+inline std::optional<Message> receive();
+inline void send_bytes(const uint8_t* msg, size_t length);
+inline std::optional<std::array<uint8_t, 32>> receive_bytes();
+inline double max_forward_acceleration();
+inline double max_backward_acceleration();
+inline double max_lateral_acceleration();
+inline double max_angular_acceleration();
+inline uint32_t current_tick();
+inline double current_time();
+inline void activate_ability(Ability ability);
+inline void deactivate_ability(Ability ability);
+inline ActiveAbilities active_abilities();
+inline Vec2 target();
+inline Vec2 target_velocity();
+inline double angle_diff(double a, double b);
+inline double rand(double low, double high);
+inline uint32_t rgb(uint8_t r, uint8_t g, uint8_t b);
+inline void draw_line(Vec2 a, Vec2 b, uint32_t color);
+inline void draw_polygon(Vec2 center, double radius, int32_t sides, double angle, uint32_t color);
+inline void draw_triangle(Vec2 center, double radius, uint32_t color);
+inline void draw_square(Vec2 center, double radius, uint32_t color);
+inline void draw_diamond(Vec2 center, double radius, uint32_t color);
+
+namespace sys {
+
+inline std::optional<std::string> getenv(const char* key) {
+    // Format is key=value\nkey=value\n... ending with a null byte.
+    const char* p = (const char*)ENVIRONMENT;
+    const char* end = p + strnlen(p, OORT_ENVIRONMENT_SIZE);
+    size_t key_len = std::strlen(key);
+    while (p < end) {
+        const char* eol = std::find(p, end, '\n');
+        if ((size_t)(eol - p) > key_len && std::strncmp(p, key, key_len) == 0 && p[key_len] == '=') {
+            return std::string(p + key_len + 1, eol);
+        }
+        p = eol + 1;
+    }
+    return std::nullopt;
+}
+
+// Same generator as the Rust API (oorandom::Rand64).
+struct Rng {
+    __uint128_t state;
+    __uint128_t inc;
+
+    explicit Rng(__uint128_t seed) {
+        const __uint128_t default_inc =
+            ((__uint128_t)0x2FE0E169FFBD06E3ull << 64) | 0x5BC307BD4D2F814Full;
+        state = 0;
+        inc = (default_inc << 1) | 1;
+        next();
+        state += seed;
+        next();
+    }
+
+    uint64_t next() {
+        const __uint128_t multiplier =
+            ((__uint128_t)0x2360ED051FC65DA4ull << 64) | 0x4385DF649FCCF645ull;
+        __uint128_t old = state;
+        state = old * multiplier + inc;
+        uint64_t xorshifted = (uint64_t)(((old >> 29) ^ old) >> 58);
+        uint32_t rot = (uint32_t)(old >> 122);
+        return (xorshifted >> rot) | (xorshifted << ((64 - rot) & 63));
+    }
+
+    double next_float() {
+        return (double)(next() >> 10) * (1.0 / (double)(1ull << 54));
+    }
+};
+
+inline Rng& rng() {
+    static Rng rng(seed());
+    return rng;
+}
+
+struct Line {
+    double x0;
+    double y0;
+    double x1;
+    double y1;
+    uint32_t color;
+};
+
+struct Text {
+    double x;
+    double y;
+    uint32_t color;
+    uint8_t length;
+    char text[11];
+};
+
+constexpr size_t MAX_LINES = 1024;
+constexpr size_t MAX_TEXTS = 128;
+
+inline std::string debug_text;
+inline Line lines[MAX_LINES];
+inline size_t num_lines = 0;
+inline Text texts[MAX_TEXTS];
+inline size_t num_texts = 0;
+
+inline void reset() {
+    debug_text.clear();
+    num_lines = 0;
+    num_texts = 0;
+}
+
+inline void update() {
+    write_f64(DebugTextPointer, (double)(uint32_t)(uintptr_t)debug_text.data());
+    write_f64(DebugTextLength, (double)debug_text.size());
+    write_f64(DebugLinesPointer, (double)(uint32_t)(uintptr_t)lines);
+    write_f64(DebugLinesLength, (double)num_lines);
+    write_f64(DrawnTextPointer, (double)(uint32_t)(uintptr_t)texts);
+    write_f64(DrawnTextLength, (double)num_texts);
+}
+
+inline SystemState radio_state(SystemState radio0_state) {
+    size_t index = (size_t)read_f64(SelectedRadio);
+    if (index >= MAX_RADIOS) {
+        std::abort();
+    }
+    return (SystemState)(radio0_state + RADIO_STRIDE * index);
+}
+
+}  // namespace sys
+
+/// Returns a per-ship ID that is unique within a team.
+inline uint32_t id() {
+    return (uint32_t)(read_f64(SystemState::Id));
+}
+
+/// Returns the ship [`Class`] (Fighter, Cruiser, etc).
+inline ShipClass ship_class() {
+    uint32_t value = (uint32_t)read_f64(SystemState::Class);
+    if (value > (uint32_t)ShipClass::Unknown) {
+        return ShipClass::Unknown;
+    }
+    return (ShipClass)value;
+}
+
+/// Returns a random number useful for initializing a random number generator.
+inline uint64_t seed() {
+    return (uint64_t)read_f64(SystemState::Seed);
+}
+
+/// Returns the scenario name.
+inline std::string scenario_name() {
+    return sys::getenv("SCENARIO_NAME").value_or("unknown");
+}
+
+/// Returns the world size in meters.
+inline double world_size() {
+    auto value = sys::getenv("WORLD_SIZE");
+    return value ? std::strtod(value->c_str(), nullptr) : 0.0;
+}
+
+/// Returns the time between each simulation tick in this scenario.
+///
+/// This is usually [`TICK_LENGTH`], but scenarios can change it.
+inline double tick_length() {
+    auto value = sys::getenv("TICK_LENGTH");
+    return value ? std::strtod(value->c_str(), nullptr) : TICK_LENGTH;
+}
+
+/// Returns the current position (in meters).
+inline Vec2 position() {
+    return vec2(read_f64(SystemState::PositionX), read_f64(SystemState::PositionY));
+}
+
+/// Returns the current velocity (in m/s).
+inline Vec2 velocity() {
+    return vec2(read_f64(SystemState::VelocityX), read_f64(SystemState::VelocityY));
+}
+
+/// Returns the current heading (in radians).
+inline double heading() {
+    return read_f64(SystemState::Heading);
+}
+
+/// Returns the current angular velocity (in radians/s).
+inline double angular_velocity() {
+    return read_f64(SystemState::AngularVelocity);
+}
+
+/// Sets the linear acceleration for the next tick (in m/s²).
+inline void accelerate(Vec2 acceleration) {
+    acceleration = acceleration.rotate(-heading());
+    if (acceleration.x > max_forward_acceleration()) {
+        acceleration *= max_forward_acceleration() / acceleration.x;
+    }
+    if (acceleration.x < -max_backward_acceleration()) {
+        acceleration *= max_backward_acceleration() / -acceleration.x;
+    }
+    if (std::fabs(acceleration.y) > max_lateral_acceleration()) {
+        acceleration *= max_lateral_acceleration() / std::fabs(acceleration.y);
+    }
+    write_f64(SystemState::AccelerateX, acceleration.x);
+    write_f64(SystemState::AccelerateY, acceleration.y);
+}
+
+/// Rotates the ship at the given speed (in radians/s).
+///
+/// Internally this uses `torque()`. Reaching the commanded speed takes time.
+inline void turn(double speed) {
+    double max = max_angular_acceleration() * 0.2;
+    double error = std::clamp(speed, -max, max) - angular_velocity();
+    torque(std::copysign(1.0, error) * max_angular_acceleration());
+}
+
+/// Sets the angular acceleration for the next tick (in radians/s²).
+///
+/// This is lower-level than turn() and can be used to turn faster.
+inline void torque(double angular_acceleration) {
+    write_f64(SystemState::Torque, angular_acceleration);
+}
+
+/// Aims a turreted weapon.
+///
+/// `index` selects the weapon.
+/// `heading` is in radians.
+inline void aim(size_t index, double heading) {
+    if (index >= 4) {
+        return;
+    }
+    write_f64((SystemState)(SystemState::Aim0 + index), heading);
+}
+
+/// Fires a weapon.
+///
+/// `index` selects the weapon.
+inline void fire(size_t index) {
+    if (index >= 4) {
+        return;
+    }
+    write_f64((SystemState)(SystemState::Fire0 + index), 1.0);
+}
+
+/// Returns the number of ticks until a weapon is ready to fire.
+///
+/// `index` selects the weapon. Returns 0 if the weapon is ready.
+inline uint32_t reload_ticks(size_t index) {
+    if (index >= 4) {
+        return 0;
+    }
+    return (uint32_t)read_f64((SystemState)(SystemState::ReloadTicks0 + index));
+}
+
+/// Self-destructs, producing a damaging explosion.
+///
+/// This is commonly used by missiles.
+inline void explode() {
+    write_f64(SystemState::Explode, 1.0);
+}
+
+/// Returns the current health.
+inline double health() {
+    return read_f64(SystemState::Health);
+}
+
+/// Returns the current fuel (delta-v).
+inline double fuel() {
+    return read_f64(SystemState::Fuel);
+}
+
+/// Returns the current energy.
+///
+/// Guns, missile launchers, radar and abilities may draw from it, and it
+/// regenerates over time. Returns infinity if the ship doesn't use energy.
+inline double energy() {
+    return read_f64(SystemState::Energy);
+}
+
+/// Returns the heading the radar is pointed at.
+inline double radar_heading() {
+    return read_f64(SystemState::RadarHeading);
+}
+
+/// Sets the heading to point the radar at.
+///
+/// It takes effect next tick.
+inline void set_radar_heading(double heading) {
+    write_f64(SystemState::RadarHeading, heading);
+}
+
+/// Returns the current radar width (in radians).
+///
+/// This is the field of view of the radar.
+inline double radar_width() {
+    return read_f64(SystemState::RadarWidth);
+}
+
+/// Sets the radar width (in radians).
+///
+/// This is the field of view of the radar.
+/// It takes effect next tick.
+inline void set_radar_width(double width) {
+    write_f64(SystemState::RadarWidth, width);
+}
+
+/// Gets the current minimum distance filter of the radar (in meters).
+inline double radar_min_distance() {
+    return read_f64(SystemState::RadarMinDistance);
+}
+
+/// Sets the minimum distance filter of the radar (in meters).
+///
+/// It takes effect next tick.
+inline void set_radar_min_distance(double dist) {
+    write_f64(SystemState::RadarMinDistance, dist);
+}
+
+/// Gets the current maximum distance filter of the radar (in meters).
+inline double radar_max_distance() {
+    return read_f64(SystemState::RadarMaxDistance);
+}
+
+/// Sets the maximum distance filter of the radar (in meters).
+///
+/// It takes effect next tick.
+inline void set_radar_max_distance(double dist) {
+    write_f64(SystemState::RadarMaxDistance, dist);
+}
+
+/// Gets the Electronic Counter Measures (ECM) mode.
+inline EcmMode radar_ecm_mode() {
+    switch ((uint32_t)read_f64(SystemState::RadarEcmMode)) {
+    case (uint32_t)EcmMode::Noise:
+        return EcmMode::Noise;
+    default:
+        return EcmMode::None;
+    }
+}
+
+/// Sets the Electronic Counter Measures (ECM) mode.
+inline void set_radar_ecm_mode(EcmMode mode) {
+    write_f64(SystemState::RadarEcmMode, (double)((uint32_t)(mode)));
+}
+
+/// Returns the radar contact with the highest signal strength.
+inline std::optional<ScanResult> scan() {
+    if (read_f64(SystemState::RadarContactFound) == 0.0) {
+        return std::nullopt;
+    }
+    uint32_t class_ = (uint32_t)read_f64(SystemState::RadarContactClass);
+    return ScanResult{
+        class_ > (uint32_t)ShipClass::Unknown ? ShipClass::Unknown : (ShipClass)class_,
+        vec2(read_f64(SystemState::RadarContactPositionX), read_f64(SystemState::RadarContactPositionY)),
+        vec2(read_f64(SystemState::RadarContactVelocityX), read_f64(SystemState::RadarContactVelocityY)),
+        read_f64(SystemState::RadarContactRssi),
+        read_f64(SystemState::RadarContactSnr),
+    };
+}
+
+/// Returns the enemy radars whose beams are pointed at this ship, strongest
+/// first.
+///
+/// This is a passive sensor and can pick up a radar from much further away
+/// than that radar can detect this ship. At most [`MAX_RADAR_WARNINGS`]
+/// radars are reported.
+inline std::vector<RadarWarning> radar_warnings() {
+    size_t count = std::min((size_t)read_f64(SystemState::RadarWarningCount), MAX_RADAR_WARNINGS);
+    std::vector<RadarWarning> warnings;
+    for (size_t i = 0; i < count; i++) {
+        warnings.push_back(RadarWarning{
+            read_f64((SystemState)(SystemState::RadarWarningBearing0 + i)),
+            read_f64((SystemState)(SystemState::RadarWarningRssi0 + i)),
+        });
+    }
+    return warnings;
+}
+
+/// Select the radio to control with subsequent API calls.
+inline void select_radio(size_t index) {
+    write_f64(SystemState::SelectedRadio, (double)std::min(index, MAX_RADIOS - 1));
+}
+
+/// Sets the channel to send and receive radio transmissions on.
+///
+/// Takes effect next tick.
+inline void set_radio_channel(size_t channel) {
+    write_f64(sys::radio_state(SystemState::Radio0Channel), (double)channel);
+}
+
+/// Gets the current radio channel.
+inline size_t get_radio_channel() {
+    return (size_t)read_f64(sys::radio_state(SystemState::Radio0Channel));
+}
+
+/// Sends a radio message.
+///
+/// The message will be received on the next tick.
+///
+/// If you want to send arbitrary data, consider using [`send_bytes`] instead.
+inline void send(Message msg) {
+    write_f64(sys::radio_state(SystemState::Radio0Send), 1.0);
+    for (size_t i = 0; i < msg.size(); i++) {
+        write_f64((SystemState)(sys::radio_state(SystemState::Radio0Data0) + i), msg[i]);
+    }
+}
+
+/// Returns the received radio message.
+inline std::optional<Message> receive() {
+    if (read_f64(sys::radio_state(SystemState::Radio0Receive)) == 0.0) {
+        return std::nullopt;
+    }
+    Message msg;
+    for (size_t i = 0; i < msg.size(); i++) {
+        msg[i] = read_f64((SystemState)(sys::radio_state(SystemState::Radio0Data0) + i));
+    }
+    return msg;
+}
+
+/// Sends a radio message.
+/// The message will be zero-filled or truncated to be 32 bytes long.
+///
+/// The message will be received on the next tick.
+///
+/// If you only want to send [`f64`]s consider using [`send`] instead.
+inline void send_bytes(const uint8_t* msg, size_t length) {
+    uint8_t bytes[32] = {};
+    std::memcpy(bytes, msg, std::min(length, sizeof(bytes)));
+    write_f64(sys::radio_state(SystemState::Radio0Send), 1.0);
+    for (size_t i = 0; i < 4; i++) {
+        uint64_t value;
+        std::memcpy(&value, bytes + 8 * i, sizeof(value));
+        write_u64((SystemState)(sys::radio_state(SystemState::Radio0Data0) + i), value);
+    }
+}
+
+/// Returns the received radio message.
+inline std::optional<std::array<uint8_t, 32>> receive_bytes() {
+    if (read_f64(sys::radio_state(SystemState::Radio0Receive)) == 0.0) {
+        return std::nullopt;
+    }
+    std::array<uint8_t, 32> bytes;
+    for (size_t i = 0; i < 4; i++) {
+        uint64_t value = read_u64((SystemState)(sys::radio_state(SystemState::Radio0Data0) + i));
+        std::memcpy(bytes.data() + 8 * i, &value, sizeof(value));
+    }
+    return bytes;
+}
+
+/// Returns the maximum forward acceleration (in m/s²).
+inline double max_forward_acceleration() {
+    return read_f64(SystemState::MaxForwardAcceleration);
+}
+
+/// Returns the maximum backward acceleration (in m/s²).
+inline double max_backward_acceleration() {
+    return read_f64(SystemState::MaxBackwardAcceleration);
+}
+
+/// Returns the maximum lateral acceleration (in m/s²).
+inline double max_lateral_acceleration() {
+    return read_f64(SystemState::MaxLateralAcceleration);
+}
+
+/// Returns the maximum angular acceleration (in radians/s²).
+inline double max_angular_acceleration() {
+    return read_f64(SystemState::MaxAngularAcceleration);
+}
+
+/// Returns the number of ticks elapsed since the simulation began.
+inline uint32_t current_tick() {
+    return (uint32_t)(read_f64(SystemState::CurrentTick));
+}
+
+/// Returns the number of seconds elapsed since the simulation began.
+inline double current_time() {
+    return read_f64(SystemState::CurrentTick) * tick_length();
+}
+
+/// Activates a special ability.
+inline void activate_ability(Ability ability) {
+    write_u64(SystemState::ActivateAbility, read_u64(SystemState::ActivateAbility) | (1ull << (uint64_t)ability));
+}
+
+/// Deactivates a special ability.
+inline void deactivate_ability(Ability ability) {
+    write_u64(SystemState::ActivateAbility, read_u64(SystemState::ActivateAbility) & ~(1ull << (uint64_t)ability));
+}
+
+/// Get a copy of the active abilities. Useful for querying which abilities are currently active.
+inline ActiveAbilities active_abilities() {
+    return ActiveAbilities{read_u64(SystemState::ActivateAbility)};
+}
+
+/// Returns the position of the target set by the scenario.
+/// Only used in tutorials.
+inline Vec2 target() {
+    return vec2(read_f64(SystemState::RadarContactPositionX), read_f64(SystemState::RadarContactPositionY));
+}
+
+/// Returns the velocity of the target set by the scenario.
+/// Only used in tutorials.
+inline Vec2 target_velocity() {
+    return vec2(read_f64(SystemState::RadarContactVelocityX), read_f64(SystemState::RadarContactVelocityY));
+}
+
+/// Returns the smallest rotation between angles `a` and `b`.
+///
+/// A positive result is a counter-clockwise rotation and negative is clockwise.
+inline double angle_diff(double a, double b) {
+    double c = std::fmod(b - a, TAU);
+    if (c < 0.0) {
+        c += TAU;
+    }
+    return c > PI ? c - TAU : c;
+}
+
+/// Returns a random number between `low` and `high`.
+inline double rand(double low, double high) {
+    return sys::rng().next_float() * (high - low) + low;
+}
+
+/// Creates a 24-bit RGB color from the arguments.
+inline uint32_t rgb(uint8_t r, uint8_t g, uint8_t b) {
+    return (uint32_t)r << 16 | (uint32_t)g << 8 | (uint32_t)b;
+}
+
+/// Draws a line visible in debug mode.
+///
+/// `a` and `b` are positions in world coordinates.
+/// `color` is 24-bit RGB.
+///
+/// Up to 1024 lines can be drawn per ship, per tick. This quota is also consumed
+/// by the various shape drawing functions.
+inline void draw_line(Vec2 a, Vec2 b, uint32_t color) {
+    if (sys::num_lines >= sys::MAX_LINES) {
+        return;
+    }
+    sys::lines[sys::num_lines++] = sys::Line{a.x, a.y, b.x, b.y, color};
+}
+
+/// Draws a regular polygon visible in debug mode.
+///
+/// `center` is a position in world coordinates.
+/// `color` is 24-bit RGB.
+inline void draw_polygon(Vec2 center, double radius, int32_t sides, double angle, uint32_t color) {
+    double delta_angle = TAU / sides;
+    Vec2 p = vec2(radius, 0.0).rotate(angle);
+    for (int32_t i = 0; i < sides; i++) {
+        Vec2 p2 = p.rotate(delta_angle);
+        draw_line(center + p, center + p2, color);
+        p = p2;
+    }
+}
+
+/// Draws a triangle visible in debug mode.
+///
+/// `center` is a position in world coordinates.
+/// `color` is 24-bit RGB.
+inline void draw_triangle(Vec2 center, double radius, uint32_t color) {
+    double x = std::sqrt(3.0) * radius / 2.0;
+    double y = radius / 2.0;
+    Vec2 points[] = {center + vec2(0.0, radius), center + vec2(-x, -y), center + vec2(x, -y)};
+    for (int i = 0; i < 3; i++) {
+        draw_line(points[i], points[(i + 1) % 3], color);
+    }
+}
+
+/// Draws a square visible in debug mode.
+///
+/// `center` is a position in world coordinates.
+/// `color` is 24-bit RGB.
+inline void draw_square(Vec2 center, double radius, uint32_t color) {
+    double offset = radius / std::sqrt(2.0);
+    Vec2 p = vec2(offset, offset);
+    for (int i = 0; i < 4; i++) {
+        Vec2 p2 = vec2(-p.y, p.x);
+        draw_line(center + p, center + p2, color);
+        p = p2;
+    }
+}
+
+/// Draws a diamond visible in debug mode.
+///
+/// `center` is a position in world coordinates.
+/// `color` is 24-bit RGB.
+inline void draw_diamond(Vec2 center, double radius, uint32_t color) {
+    Vec2 p = vec2(radius, 0.0);
+    for (int i = 0; i < 4; i++) {
+        Vec2 p2 = vec2(-p.y, p.x);
+        draw_line(center + p, center + p2, color);
+        p = p2;
+    }
+}
+
+
+/// Adds text to be displayed when the ship is selected by clicking on it.
+///
+/// Works just like printf.
+__attribute__((format(printf, 1, 2)))
+inline void debug(const char* format, ...) {
+    char buf[1024];
+    va_list args;
+    va_start(args, format);
+    int n = std::vsnprintf(buf, sizeof(buf), format, args);
+    va_end(args);
+    if (n < 0) {
+        return;
+    }
+    sys::debug_text.append(buf, std::min((size_t)n, sizeof(buf) - 1));
+    sys::debug_text.push_back('\n');
+}
+
+/// Adds text to be drawn in the world, visible in debug mode.
+///
+/// Works like printf. Up to 128 strings can be drawn per ship, per tick.
+__attribute__((format(printf, 3, 4)))
+inline void draw_text(Vec2 topleft, uint32_t color, const char* format, ...) {
+    if (sys::num_texts >= sys::MAX_TEXTS) {
+        return;
+    }
+    char buf[64];
+    va_list args;
+    va_start(args, format);
+    int n = std::vsnprintf(buf, sizeof(buf), format, args);
+    va_end(args);
+    if (n < 0) {
+        return;
+    }
+    sys::Text& text = sys::texts[sys::num_texts++];
+    text.x = topleft.x;
+    text.y = topleft.y;
+    text.color = color;
+    text.length = (uint8_t)std::min((size_t)n, sizeof(text.text));
+    std::memcpy(text.text, buf, text.length);
+}
+
+}  // namespace oort
+
+#endif
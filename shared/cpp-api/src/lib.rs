@@ -0,0 +1,544 @@
+//! Generates the C, C++ and Zig APIs from the Rust API in `oort_api`.
+//!
+//! The `SystemState` layout, buffer sizes, and the prelude functions are read
+//! from the Rust source so the other languages can't drift from it. The C and
+//! Zig preludes are hand-written templates, checked to cover every function in
+//! the Rust prelude. Run
+//! `cargo run -p oort_cpp_api` after changing `shared/api` to update the
+//! checked-in files.
+
+mod manual;
+
+use anyhow::{anyhow, bail, Result};
+use manual::MANUAL;
+use std::collections::HashSet;
+use std::fmt::Write;
+use syn::{
+    Attribute, BinOp, Expr, ExprLit, FnArg, Item, ItemEnum, Lit, Pat, ReturnType, Stmt, Type, UnOp,
+    Visibility,
+};
+
+const API_SOURCE: &str = include_str!("../../api/src/lib.rs");
+const PANIC_SOURCE: &str = include_str!("../../api/src/panic.rs");
+const PRELUDE_TEMPLATE: &str = include_str!("prelude.h.in");
+const C_PRELUDE_TEMPLATE: &str = include_str!("c_prelude.h.in");
+const ZIG_TEMPLATE: &str = include_str!("oort.zig.in");
+
+pub const HEADER_FILENAME: &str = "oort.h";
+pub const PRELUDE_FILENAME: &str = "oort_prelude.h";
+pub const C_PRELUDE_FILENAME: &str = "oort_c.h";
+pub const ZIG_FILENAME: &str = "oort.zig";
+
+const PREAMBLE: &str = "// Generated from shared/api by shared/cpp-api. Do not edit.
+// Regenerate with `cargo run -p oort_cpp_api`.
+";
+
+/// Modules of `oort_api` re-exported by the Rust prelude.
+const PRELUDE_MODULES: &[&str] = &["api", "math", "rng", "dbg"];
+
+/// Enums exposed to C, C++ and Zig, with their C/C++ names.
+const ENUMS: &[(&str, &str)] = &[
+    ("Class", "ShipClass"),
+    ("EcmMode", "EcmMode"),
+    ("Ability", "Ability"),
+];
+
+/// Returns the contents of oort.h.
+pub fn generate_header() -> Result<String> {
+    let api = syn::parse_file(API_SOURCE)?;
+    let panic = syn::parse_file(PANIC_SOURCE)?;
+    let system_state = enum_values(find_enum(&api.items, "SystemState")?)?;
+    let system_state_size = lookup(&system_state, "MaxSize")?;
+    let environment_size = find_const(&api.items, "MAX_ENVIRONMENT_SIZE")?;
+    let panic_buffer_size = find_const(&panic.items, "PANIC_BUFFER_SIZE")?;
+
+    let mut out = String::new();
+    writeln!(out, "{PREAMBLE}")?;
+    writeln!(out, "#ifndef OORT_H\n#define OORT_H\n")?;
+    writeln!(out, "#include <stdint.h>\n#include <string.h>\n")?;
+    writeln!(out, "#define OORT_SYSTEM_STATE_SIZE {system_state_size}")?;
+    writeln!(out, "#define OORT_ENVIRONMENT_SIZE {environment_size}")?;
+    writeln!(out, "#define OORT_PANIC_BUFFER_SIZE {panic_buffer_size}\n")?;
+    writeln!(out, "#ifdef __cplusplus\nextern \"C\" {{\n#endif\n")?;
+    writeln!(out, "extern uint64_t SYSTEM_STATE[OORT_SYSTEM_STATE_SIZE];")?;
+    writeln!(out, "extern uint8_t ENVIRONMENT[OORT_ENVIRONMENT_SIZE];")?;
+    writeln!(
+        out,
+        "extern uint8_t PANIC_BUFFER[OORT_PANIC_BUFFER_SIZE];\n"
+    )?;
+    writeln!(out, "#ifdef __cplusplus\n}}\n#endif\n")?;
+
+    writeln!(out, "enum SystemState {{")?;
+    for (name, value) in &system_state {
+        writeln!(out, "    {name} = {value},")?;
+    }
+    writeln!(out, "}};\n")?;
+
+    out.push_str(
+        "static inline uint64_t read_u64(enum SystemState key) {
+    return SYSTEM_STATE[key];
+}
+
+static inline double read_f64(enum SystemState key) {
+    uint64_t u64_value = read_u64(key);
+    double f64_value;
+    memcpy(&f64_value, &u64_value, sizeof(f64_value));
+    return f64_value;
+}
+
+static inline void write_u64(enum SystemState key, uint64_t value) {
+    SYSTEM_STATE[key] = value;
+}
+
+static inline void write_f64(enum SystemState key, double value) {
+    uint64_t u64_value;
+    memcpy(&u64_value, &value, sizeof(u64_value));
+    write_u64(key, u64_value);
+}
+
+#ifdef __cplusplus
+#include \"oort_prelude.h\"
+#else
+#include \"oort_c.h\"
+#endif
+
+#endif
+",
+    );
+    Ok(out)
+}
+
+/// Returns the contents of oort_prelude.h.
+pub fn generate_prelude() -> Result<String> {
+    let api = syn::parse_file(API_SOURCE)?;
+    let system_state = enum_values(find_enum(&api.items, "SystemState")?)?;
+
+    let mut enums = String::new();
+    for (rust_name, cpp_name) in ENUMS {
+        let item = find_enum(&api.items, rust_name)?;
+        write_doc(&mut enums, &item.attrs)?;
+        writeln!(enums, "enum class {cpp_name} : uint32_t {{")?;
+        for (name, value) in enum_values(item)? {
+            writeln!(enums, "    {name} = {value},")?;
+        }
+        writeln!(enums, "}};\n")?;
+    }
+
+    let mut constants = String::new();
+    let (max_radios, radio_stride) = radio_constants(&api.items, &system_state)?;
+    writeln!(constants, "constexpr size_t MAX_RADIOS = {max_radios};")?;
+    writeln!(constants, "constexpr size_t RADIO_STRIDE = {radio_stride};")?;
+
+    let mut declarations = String::new();
+    let mut definitions = String::new();
+    let mut used_manual = HashSet::new();
+    for module in PRELUDE_MODULES {
+        for item in find_mod(&api.items, module)? {
+            match item {
+                Item::Const(item) if is_public(&item.vis, &item.attrs) => {
+                    let (Some(ty), Some(value)) = (cpp_type(&item.ty), cpp_expr(&item.expr)) else {
+                        bail!("Can't translate constant {module}::{}", item.ident);
+                    };
+                    write_doc(&mut constants, &item.attrs)?;
+                    writeln!(constants, "constexpr {ty} {} = {value};", item.ident)?;
+                }
+                Item::Fn(item) if is_public(&item.vis, &item.attrs) => {
+                    let name = item.sig.ident.to_string();
+                    let (signature, body) = if let Some(manual) =
+                        MANUAL.iter().find(|x| x.name == name)
+                    {
+                        used_manual.insert(manual.name);
+                        (manual.signature.to_string(), manual.body.to_string())
+                    } else if let Some(translated) = translate_fn(item) {
+                        translated
+                    } else {
+                        bail!(
+                                "Can't translate {module}::{name} to C++, add it to shared/cpp-api/src/manual.rs"
+                            );
+                    };
+                    writeln!(declarations, "inline {signature};")?;
+                    write_doc(&mut definitions, &item.attrs)?;
+                    writeln!(definitions, "inline {signature} {{{body}}}\n")?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(manual) = MANUAL.iter().find(|x| !used_manual.contains(x.name)) {
+        bail!(
+            "Manual C++ translation of {:?} doesn't match any Rust function",
+            manual.name
+        );
+    }
+
+    let prelude = PRELUDE_TEMPLATE
+        .replace("// @ENUMS@\n", &enums)
+        .replace("// @CONSTANTS@\n", &constants)
+        .replace("// @DECLARATIONS@\n", &declarations)
+        .replace("// @DEFINITIONS@\n", &definitions);
+    Ok(format!("{PREAMBLE}\n{prelude}"))
+}
+
+/// Returns the contents of oort_c.h.
+pub fn generate_c_prelude() -> Result<String> {
+    let api = syn::parse_file(API_SOURCE)?;
+    let system_state = enum_values(find_enum(&api.items, "SystemState")?)?;
+
+    let mut enums = String::new();
+    for (rust_name, c_name) in ENUMS {
+        let item = find_enum(&api.items, rust_name)?;
+        write_doc(&mut enums, &item.attrs)?;
+        writeln!(enums, "typedef enum {c_name} {{")?;
+        for (name, value) in enum_values(item)? {
+            writeln!(enums, "    {c_name}_{name} = {value},")?;
+        }
+        writeln!(enums, "}} {c_name};\n")?;
+    }
+
+    let mut constants = String::new();
+    let (max_radios, radio_stride) = radio_constants(&api.items, &system_state)?;
+    writeln!(constants, "#define OORT_MAX_RADIOS {max_radios}")?;
+    writeln!(constants, "#define OORT_RADIO_STRIDE {radio_stride}")?;
+    for (item, value) in prelude_consts(&api.items)? {
+        write_doc(&mut constants, &item.attrs)?;
+        writeln!(constants, "#define OORT_{} ({value})", item.ident)?;
+    }
+
+    for item in prelude_fns(&api.items)? {
+        let name = item.sig.ident.to_string();
+        if !C_PRELUDE_TEMPLATE.contains(&format!(" oort_{name}(")) {
+            bail!("oort_{name} is missing from shared/cpp-api/src/c_prelude.h.in");
+        }
+    }
+
+    let prelude = C_PRELUDE_TEMPLATE
+        .replace("// @ENUMS@\n", &enums)
+        .replace("// @CONSTANTS@\n", &constants);
+    Ok(format!("{PREAMBLE}\n{prelude}"))
+}
+
+/// Returns the contents of oort.zig.
+pub fn generate_zig() -> Result<String> {
+    let api = syn::parse_file(API_SOURCE)?;
+    let panic = syn::parse_file(PANIC_SOURCE)?;
+    let system_state = enum_values(find_enum(&api.items, "SystemState")?)?;
+
+    let mut sizes = String::new();
+    writeln!(
+        sizes,
+        "const SYSTEM_STATE_SIZE = {};",
+        lookup(&system_state, "MaxSize")?
+    )?;
+    writeln!(
+        sizes,
+        "const ENVIRONMENT_SIZE = {};",
+        find_const(&api.items, "MAX_ENVIRONMENT_SIZE")?
+    )?;
+    writeln!(
+        sizes,
+        "const PANIC_BUFFER_SIZE = {};",
+        find_const(&panic.items, "PANIC_BUFFER_SIZE")?
+    )?;
+
+    let mut system_state_enum = String::new();
+    writeln!(system_state_enum, "pub const SystemState = enum(u32) {{")?;
+    for (name, value) in &system_state {
+        writeln!(system_state_enum, "    {name} = {value},")?;
+    }
+    writeln!(system_state_enum, "}};")?;
+
+    let mut enums = String::new();
+    for (rust_name, _) in ENUMS {
+        let item = find_enum(&api.items, rust_name)?;
+        write_doc(&mut enums, &item.attrs)?;
+        writeln!(enums, "pub const {rust_name} = enum(u32) {{")?;
+        for (name, value) in enum_values(item)? {
+            writeln!(enums, "    {name} = {value},")?;
+        }
+        writeln!(enums, "}};\n")?;
+    }
+
+    let mut constants = String::new();
+    let (max_radios, radio_stride) = radio_constants(&api.items, &system_state)?;
+    writeln!(constants, "pub const MAX_RADIOS: usize = {max_radios};")?;
+    writeln!(constants, "pub const RADIO_STRIDE: usize = {radio_stride};")?;
+    for (item, value) in prelude_consts(&api.items)? {
+        let ty = match cpp_type(&item.ty).as_deref() {
+            Some("double") => "f64",
+            Some("size_t") => "usize",
+            _ => bail!("Can't translate constant {} to Zig", item.ident),
+        };
+        write_doc(&mut constants, &item.attrs)?;
+        writeln!(constants, "pub const {}: {ty} = {value};", item.ident)?;
+    }
+
+    for item in prelude_fns(&api.items)? {
+        let name = camel_case(&item.sig.ident.to_string());
+        if !ZIG_TEMPLATE.contains(&format!("pub fn {name}(")) {
+            bail!("{name} is missing from shared/cpp-api/src/oort.zig.in");
+        }
+    }
+
+    let zig = ZIG_TEMPLATE
+        .replace("// @SIZES@\n", &sizes)
+        .replace("// @SYSTEM_STATE@\n", &system_state_enum)
+        .replace("// @ENUMS@\n", &enums)
+        .replace("// @CONSTANTS@\n", &constants);
+    Ok(format!("{PREAMBLE}\n{zig}"))
+}
+
+/// Returns the public constants in the Rust prelude with their translated values.
+fn prelude_consts(items: &[Item]) -> Result<Vec<(&syn::ItemConst, String)>> {
+    let mut consts = vec![];
+    for module in PRELUDE_MODULES {
+        for item in find_mod(items, module)? {
+            if let Item::Const(item) = item {
+                if is_public(&item.vis, &item.attrs) {
+                    let Some(value) = cpp_expr(&item.expr) else {
+                        bail!("Can't translate constant {module}::{}", item.ident);
+                    };
+                    consts.push((item, value));
+                }
+            }
+        }
+    }
+    Ok(consts)
+}
+
+/// Returns the public functions in the Rust prelude.
+fn prelude_fns(items: &[Item]) -> Result<Vec<&syn::ItemFn>> {
+    let mut fns = vec![];
+    for module in PRELUDE_MODULES {
+        for item in find_mod(items, module)? {
+            if let Item::Fn(item) = item {
+                if is_public(&item.vis, &item.attrs) {
+                    fns.push(item);
+                }
+            }
+        }
+    }
+    Ok(fns)
+}
+
+/// Returns MAX_RADIOS and the number of `SystemState` slots per radio.
+fn radio_constants(items: &[Item], system_state: &[(String, i64)]) -> Result<(i64, i64)> {
+    let radio_internal = find_mod(find_mod(items, "api")?, "radio_internal")?;
+    Ok((
+        find_const(radio_internal, "MAX_RADIOS")?,
+        lookup(system_state, "Radio1Channel")? - lookup(system_state, "Radio0Channel")?,
+    ))
+}
+
+fn camel_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn find_enum<'a>(items: &'a [Item], name: &str) -> Result<&'a ItemEnum> {
+    items
+        .iter()
+        .find_map(|item| match item {
+            Item::Enum(x) if x.ident == name => Some(x),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("enum {name} not found"))
+}
+
+fn find_mod<'a>(items: &'a [Item], name: &str) -> Result<&'a [Item]> {
+    items
+        .iter()
+        .find_map(|item| match item {
+            Item::Mod(x) if x.ident == name => x.content.as_ref().map(|(_, items)| &items[..]),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("module {name} not found"))
+}
+
+fn find_const(items: &[Item], name: &str) -> Result<i64> {
+    items
+        .iter()
+        .find_map(|item| match item {
+            Item::Const(x) if x.ident == name => int_literal(&x.expr),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("constant {name} not found"))
+}
+
+fn int_literal(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(x), ..
+        }) => x.base10_parse().ok(),
+        _ => None,
+    }
+}
+
+/// Returns the name and value of each variant, following Rust's numbering rules.
+fn enum_values(item: &ItemEnum) -> Result<Vec<(String, i64)>> {
+    let mut next = 0;
+    let mut values = vec![];
+    for variant in &item.variants {
+        if let Some((_, expr)) = &variant.discriminant {
+            next = int_literal(expr)
+                .ok_or_else(|| anyhow!("unsupported discriminant for {}", variant.ident))?;
+        }
+        values.push((variant.ident.to_string(), next));
+        next += 1;
+    }
+    Ok(values)
+}
+
+fn lookup(values: &[(String, i64)], name: &str) -> Result<i64> {
+    values
+        .iter()
+        .find(|(x, _)| x == name)
+        .map(|(_, value)| *value)
+        .ok_or_else(|| anyhow!("variant {name} not found"))
+}
+
+fn is_public(vis: &Visibility, attrs: &[Attribute]) -> bool {
+    let hidden = attrs.iter().any(|attr| {
+        attr.path().is_ident("deprecated")
+            || (attr.path().is_ident("doc")
+                && attr
+                    .meta
+                    .require_list()
+                    .is_ok_and(|x| x.tokens.to_string() == "hidden"))
+    });
+    matches!(vis, Visibility::Public(_)) && !hidden
+}
+
+fn write_doc(out: &mut String, attrs: &[Attribute]) -> Result<()> {
+    for attr in attrs {
+        if let Ok(meta) = attr.meta.require_name_value() {
+            if let Expr::Lit(ExprLit {
+                lit: Lit::Str(s), ..
+            }) = &meta.value
+            {
+                if meta.path.is_ident("doc") {
+                    writeln!(out, "///{}", s.value().trim_end())?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Translates a function whose body is a single expression.
+fn translate_fn(item: &syn::ItemFn) -> Option<(String, String)> {
+    let ret = match &item.sig.output {
+        ReturnType::Default => None,
+        ReturnType::Type(_, ty) => Some(cpp_type(ty)?),
+    };
+    let mut params = vec![];
+    for input in &item.sig.inputs {
+        let FnArg::Typed(arg) = input else {
+            return None;
+        };
+        let Pat::Ident(ident) = &*arg.pat else {
+            return None;
+        };
+        if ident.mutability.is_some() {
+            return None;
+        }
+        params.push(format!("{} {}", cpp_type(&arg.ty)?, ident.ident));
+    }
+
+    let [Stmt::Expr(expr, _)] = &item.block.stmts[..] else {
+        return None;
+    };
+    let expr = cpp_expr(expr)?;
+    let body = match ret {
+        Some(_) => format!("\n    return {expr};\n"),
+        None => format!("\n    {expr};\n"),
+    };
+    let signature = format!(
+        "{} {}({})",
+        ret.as_deref().unwrap_or("void"),
+        item.sig.ident,
+        params.join(", ")
+    );
+    Some((signature, body))
+}
+
+fn cpp_type(ty: &Type) -> Option<String> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let ident = path.path.get_ident()?.to_string();
+    let cpp = match ident.as_str() {
+        "f64" => "double",
+        "f32" => "float",
+        "u8" => "uint8_t",
+        "u32" => "uint32_t",
+        "i32" => "int32_t",
+        "u64" => "uint64_t",
+        "usize" => "size_t",
+        "bool" => "bool",
+        "Vec2" | "EcmMode" | "Ability" | "Message" => &ident,
+        "Class" => "ShipClass",
+        _ => return None,
+    };
+    Some(cpp.to_string())
+}
+
+fn cpp_expr(expr: &Expr) -> Option<String> {
+    Some(match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Float(x), ..
+        }) => x.base10_digits().to_string(),
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(x), ..
+        }) => x.base10_digits().to_string(),
+        Expr::Path(x) => {
+            let segments: Vec<String> = x
+                .path
+                .segments
+                .iter()
+                .map(|x| x.ident.to_string())
+                .filter(|x| x != "super" && x != "crate")
+                .collect();
+            segments.join("::")
+        }
+        Expr::Paren(x) => format!("({})", cpp_expr(&x.expr)?),
+        Expr::Unary(x) if matches!(x.op, UnOp::Neg(_)) => format!("-{}", cpp_expr(&x.expr)?),
+        Expr::Binary(x) => {
+            let op = match x.op {
+                BinOp::Add(_) => "+",
+                BinOp::Sub(_) => "-",
+                BinOp::Mul(_) => "*",
+                BinOp::Div(_) => "/",
+                _ => return None,
+            };
+            format!("{} {} {}", cpp_expr(&x.left)?, op, cpp_expr(&x.right)?)
+        }
+        Expr::Cast(x) => format!("({})({})", cpp_type(&x.ty)?, cpp_expr(&x.expr)?),
+        Expr::Call(x) => {
+            let Expr::Path(func) = &*x.func else {
+                return None;
+            };
+            let name = match func.path.get_ident()?.to_string().as_str() {
+                "read_system_state" => "read_f64".to_string(),
+                "write_system_state" => "write_f64".to_string(),
+                "read_system_state_u64" => "read_u64".to_string(),
+                "write_system_state_u64" => "write_u64".to_string(),
+                name => name.to_string(),
+            };
+            let args = x.args.iter().map(cpp_expr).collect::<Option<Vec<_>>>()?;
+            format!("{}({})", name, args.join(", "))
+        }
+        _ => return None,
+    })
+}
@@ -0,0 +1,22 @@
+use std::path::Path;
+
+fn main() -> anyhow::Result<()> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    std::fs::write(
+        dir.join(oort_cpp_api::HEADER_FILENAME),
+        oort_cpp_api::generate_header()?,
+    )?;
+    std::fs::write(
+        dir.join(oort_cpp_api::PRELUDE_FILENAME),
+        oort_cpp_api::generate_prelude()?,
+    )?;
+    std::fs::write(
+        dir.join(oort_cpp_api::C_PRELUDE_FILENAME),
+        oort_cpp_api::generate_c_prelude()?,
+    )?;
+    std::fs::write(
+        dir.join(oort_cpp_api::ZIG_FILENAME),
+        oort_cpp_api::generate_zig()?,
+    )?;
+    Ok(())
+}
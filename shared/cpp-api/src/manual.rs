@@ -0,0 +1,334 @@
+/// A hand-written C++ translation of a Rust API function.
+pub struct Manual {
+    pub name: &'static str,
+    pub signature: &'static str,
+    pub body: &'static str,
+}
+
+/// Functions whose bodies are too complex to translate automatically.
+///
+/// Every entry must correspond to a public function in the Rust prelude.
+pub const MANUAL: &[Manual] = &[
+    Manual {
+        name: "class",
+        signature: "ShipClass ship_class()",
+        body: r#"
+    uint32_t value = (uint32_t)read_f64(SystemState::Class);
+    if (value > (uint32_t)ShipClass::Unknown) {
+        return ShipClass::Unknown;
+    }
+    return (ShipClass)value;
+"#,
+    },
+    Manual {
+        name: "seed",
+        signature: "uint64_t seed()",
+        body: r#"
+    return (uint64_t)read_f64(SystemState::Seed);
+"#,
+    },
+    Manual {
+        name: "scenario_name",
+        signature: "std::string scenario_name()",
+        body: r#"
+    return sys::getenv("SCENARIO_NAME").value_or("unknown");
+"#,
+    },
+    Manual {
+        name: "world_size",
+        signature: "double world_size()",
+        body: r#"
+    auto value = sys::getenv("WORLD_SIZE");
+    return value ? std::strtod(value->c_str(), nullptr) : 0.0;
+"#,
+    },
+    Manual {
+        name: "tick_length",
+        signature: "double tick_length()",
+        body: r#"
+    auto value = sys::getenv("TICK_LENGTH");
+    return value ? std::strtod(value->c_str(), nullptr) : TICK_LENGTH;
+"#,
+    },
+    Manual {
+        name: "accelerate",
+        signature: "void accelerate(Vec2 acceleration)",
+        body: r#"
+    acceleration = acceleration.rotate(-heading());
+    if (acceleration.x > max_forward_acceleration()) {
+        acceleration *= max_forward_acceleration() / acceleration.x;
+    }
+    if (acceleration.x < -max_backward_acceleration()) {
+        acceleration *= max_backward_acceleration() / -acceleration.x;
+    }
+    if (std::fabs(acceleration.y) > max_lateral_acceleration()) {
+        acceleration *= max_lateral_acceleration() / std::fabs(acceleration.y);
+    }
+    write_f64(SystemState::AccelerateX, acceleration.x);
+    write_f64(SystemState::AccelerateY, acceleration.y);
+"#,
+    },
+    Manual {
+        name: "turn",
+        signature: "void turn(double speed)",
+        body: r#"
+    double max = max_angular_acceleration() * 0.2;
+    double error = std::clamp(speed, -max, max) - angular_velocity();
+    torque(std::copysign(1.0, error) * max_angular_acceleration());
+"#,
+    },
+    Manual {
+        name: "aim",
+        signature: "void aim(size_t index, double heading)",
+        body: r#"
+    if (index >= 4) {
+        return;
+    }
+    write_f64((SystemState)(SystemState::Aim0 + index), heading);
+"#,
+    },
+    Manual {
+        name: "fire",
+        signature: "void fire(size_t index)",
+        body: r#"
+    if (index >= 4) {
+        return;
+    }
+    write_f64((SystemState)(SystemState::Fire0 + index), 1.0);
+"#,
+    },
+    Manual {
+        name: "reload_ticks",
+        signature: "uint32_t reload_ticks(size_t index)",
+        body: r#"
+    if (index >= 4) {
+        return 0;
+    }
+    return (uint32_t)read_f64((SystemState)(SystemState::ReloadTicks0 + index));
+"#,
+    },
+    Manual {
+        name: "radar_ecm_mode",
+        signature: "EcmMode radar_ecm_mode()",
+        body: r#"
+    switch ((uint32_t)read_f64(SystemState::RadarEcmMode)) {
+    case (uint32_t)EcmMode::Noise:
+        return EcmMode::Noise;
+    default:
+        return EcmMode::None;
+    }
+"#,
+    },
+    Manual {
+        name: "scan",
+        signature: "std::optional<ScanResult> scan()",
+        body: r#"
+    if (read_f64(SystemState::RadarContactFound) == 0.0) {
+        return std::nullopt;
+    }
+    uint32_t class_ = (uint32_t)read_f64(SystemState::RadarContactClass);
+    return ScanResult{
+        class_ > (uint32_t)ShipClass::Unknown ? ShipClass::Unknown : (ShipClass)class_,
+        vec2(read_f64(SystemState::RadarContactPositionX), read_f64(SystemState::RadarContactPositionY)),
+        vec2(read_f64(SystemState::RadarContactVelocityX), read_f64(SystemState::RadarContactVelocityY)),
+        read_f64(SystemState::RadarContactRssi),
+        read_f64(SystemState::RadarContactSnr),
+    };
+"#,
+    },
+    Manual {
+        name: "radar_warnings",
+        signature: "std::vector<RadarWarning> radar_warnings()",
+        body: r#"
+    size_t count = std::min((size_t)read_f64(SystemState::RadarWarningCount), MAX_RADAR_WARNINGS);
+    std::vector<RadarWarning> warnings;
+    for (size_t i = 0; i < count; i++) {
+        warnings.push_back(RadarWarning{
+            read_f64((SystemState)(SystemState::RadarWarningBearing0 + i)),
+            read_f64((SystemState)(SystemState::RadarWarningRssi0 + i)),
+        });
+    }
+    return warnings;
+"#,
+    },
+    Manual {
+        name: "select_radio",
+        signature: "void select_radio(size_t index)",
+        body: r#"
+    write_f64(SystemState::SelectedRadio, (double)std::min(index, MAX_RADIOS - 1));
+"#,
+    },
+    Manual {
+        name: "set_radio_channel",
+        signature: "void set_radio_channel(size_t channel)",
+        body: r#"
+    write_f64(sys::radio_state(SystemState::Radio0Channel), (double)channel);
+"#,
+    },
+    Manual {
+        name: "get_radio_channel",
+        signature: "size_t get_radio_channel()",
+        body: r#"
+    return (size_t)read_f64(sys::radio_state(SystemState::Radio0Channel));
+"#,
+    },
+    Manual {
+        name: "send",
+        signature: "void send(Message msg)",
+        body: r#"
+    write_f64(sys::radio_state(SystemState::Radio0Send), 1.0);
+    for (size_t i = 0; i < msg.size(); i++) {
+        write_f64((SystemState)(sys::radio_state(SystemState::Radio0Data0) + i), msg[i]);
+    }
+"#,
+    },
+    Manual {
+        name: "receive",
+        signature: "std::optional<Message> receive()",
+        body: r#"
+    if (read_f64(sys::radio_state(SystemState::Radio0Receive)) == 0.0) {
+        return std::nullopt;
+    }
+    Message msg;
+    for (size_t i = 0; i < msg.size(); i++) {
+        msg[i] = read_f64((SystemState)(sys::radio_state(SystemState::Radio0Data0) + i));
+    }
+    return msg;
+"#,
+    },
+    Manual {
+        name: "send_bytes",
+        signature: "void send_bytes(const uint8_t* msg, size_t length)",
+        body: r#"
+    uint8_t bytes[32] = {};
+    std::memcpy(bytes, msg, std::min(length, sizeof(bytes)));
+    write_f64(sys::radio_state(SystemState::Radio0Send), 1.0);
+    for (size_t i = 0; i < 4; i++) {
+        uint64_t value;
+        std::memcpy(&value, bytes + 8 * i, sizeof(value));
+        write_u64((SystemState)(sys::radio_state(SystemState::Radio0Data0) + i), value);
+    }
+"#,
+    },
+    Manual {
+        name: "receive_bytes",
+        signature: "std::optional<std::array<uint8_t, 32>> receive_bytes()",
+        body: r#"
+    if (read_f64(sys::radio_state(SystemState::Radio0Receive)) == 0.0) {
+        return std::nullopt;
+    }
+    std::array<uint8_t, 32> bytes;
+    for (size_t i = 0; i < 4; i++) {
+        uint64_t value = read_u64((SystemState)(sys::radio_state(SystemState::Radio0Data0) + i));
+        std::memcpy(bytes.data() + 8 * i, &value, sizeof(value));
+    }
+    return bytes;
+"#,
+    },
+    Manual {
+        name: "activate_ability",
+        signature: "void activate_ability(Ability ability)",
+        body: r#"
+    write_u64(SystemState::ActivateAbility, read_u64(SystemState::ActivateAbility) | (1ull << (uint64_t)ability));
+"#,
+    },
+    Manual {
+        name: "deactivate_ability",
+        signature: "void deactivate_ability(Ability ability)",
+        body: r#"
+    write_u64(SystemState::ActivateAbility, read_u64(SystemState::ActivateAbility) & ~(1ull << (uint64_t)ability));
+"#,
+    },
+    Manual {
+        name: "active_abilities",
+        signature: "ActiveAbilities active_abilities()",
+        body: r#"
+    return ActiveAbilities{read_u64(SystemState::ActivateAbility)};
+"#,
+    },
+    Manual {
+        name: "angle_diff",
+        signature: "double angle_diff(double a, double b)",
+        body: r#"
+    double c = std::fmod(b - a, TAU);
+    if (c < 0.0) {
+        c += TAU;
+    }
+    return c > PI ? c - TAU : c;
+"#,
+    },
+    Manual {
+        name: "rand",
+        signature: "double rand(double low, double high)",
+        body: r#"
+    return sys::rng().next_float() * (high - low) + low;
+"#,
+    },
+    Manual {
+        name: "rgb",
+        signature: "uint32_t rgb(uint8_t r, uint8_t g, uint8_t b)",
+        body: r#"
+    return (uint32_t)r << 16 | (uint32_t)g << 8 | (uint32_t)b;
+"#,
+    },
+    Manual {
+        name: "draw_line",
+        signature: "void draw_line(Vec2 a, Vec2 b, uint32_t color)",
+        body: r#"
+    if (sys::num_lines >= sys::MAX_LINES) {
+        return;
+    }
+    sys::lines[sys::num_lines++] = sys::Line{a.x, a.y, b.x, b.y, color};
+"#,
+    },
+    Manual {
+        name: "draw_polygon",
+        signature: "void draw_polygon(Vec2 center, double radius, int32_t sides, double angle, uint32_t color)",
+        body: r#"
+    double delta_angle = TAU / sides;
+    Vec2 p = vec2(radius, 0.0).rotate(angle);
+    for (int32_t i = 0; i < sides; i++) {
+        Vec2 p2 = p.rotate(delta_angle);
+        draw_line(center + p, center + p2, color);
+        p = p2;
+    }
+"#,
+    },
+    Manual {
+        name: "draw_triangle",
+        signature: "void draw_triangle(Vec2 center, double radius, uint32_t color)",
+        body: r#"
+    double x = std::sqrt(3.0) * radius / 2.0;
+    double y = radius / 2.0;
+    Vec2 points[] = {center + vec2(0.0, radius), center + vec2(-x, -y), center + vec2(x, -y)};
+    for (int i = 0; i < 3; i++) {
+        draw_line(points[i], points[(i + 1) % 3], color);
+    }
+"#,
+    },
+    Manual {
+        name: "draw_square",
+        signature: "void draw_square(Vec2 center, double radius, uint32_t color)",
+        body: r#"
+    double offset = radius / std::sqrt(2.0);
+    Vec2 p = vec2(offset, offset);
+    for (int i = 0; i < 4; i++) {
+        Vec2 p2 = vec2(-p.y, p.x);
+        draw_line(center + p, center + p2, color);
+        p = p2;
+    }
+"#,
+    },
+    Manual {
+        name: "draw_diamond",
+        signature: "void draw_diamond(Vec2 center, double radius, uint32_t color)",
+        body: r#"
+    Vec2 p = vec2(radius, 0.0);
+    for (int i = 0; i < 4; i++) {
+        Vec2 p2 = vec2(-p.y, p.x);
+        draw_line(center + p, center + p2, color);
+        p = p2;
+    }
+"#,
+    },
+];
@@ -0,0 +1,31 @@
+#[test]
+fn test_header_up_to_date() {
+    assert!(
+        oort_cpp_api::generate_header().unwrap() == include_str!("../oort.h"),
+        "shared/cpp-api/oort.h is stale, run `cargo run -p oort_cpp_api`"
+    );
+}
+
+#[test]
+fn test_prelude_up_to_date() {
+    assert!(
+        oort_cpp_api::generate_prelude().unwrap() == include_str!("../oort_prelude.h"),
+        "shared/cpp-api/oort_prelude.h is stale, run `cargo run -p oort_cpp_api`"
+    );
+}
+
+#[test]
+fn test_c_prelude_up_to_date() {
+    assert!(
+        oort_cpp_api::generate_c_prelude().unwrap() == include_str!("../oort_c.h"),
+        "shared/cpp-api/oort_c.h is stale, run `cargo run -p oort_cpp_api`"
+    );
+}
+
+#[test]
+fn test_zig_up_to_date() {
+    assert!(
+        oort_cpp_api::generate_zig().unwrap() == include_str!("../oort.zig"),
+        "shared/cpp-api/oort.zig is stale, run `cargo run -p oort_cpp_api`"
+    );
+}
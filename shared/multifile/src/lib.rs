@@ -9,7 +9,7 @@ pub fn join(mut files: HashMap<String, String>) -> Result<String, anyhow::Error>
         .filter(|(_, v)| re.is_match(v))
         .collect::<Vec<_>>();
 
-    let lib = if let Some(src) = files.remove("lib.rs") {
+    let mut lib = if let Some(src) = files.remove("lib.rs") {
         src
     } else if let Some((k, _)) = files_with_ship.first().to_owned() {
         if files_with_ship.len() == 1 {
@@ -21,41 +21,147 @@ pub fn join(mut files: HashMap<String, String>) -> Result<String, anyhow::Error>
         bail!("No lib.rs found");
     };
 
+    join_children(&mut files, "", &mut lib);
+    Ok(lib)
+}
+
+/// Inlines the `mod foo;` declarations in a file whose submodules live in `dir`.
+fn join_children(files: &mut HashMap<String, String>, dir: &str, src: &mut String) {
     let re = regex::Regex::new(r"(pub )?mod (\w+);").unwrap();
-    let new_lib = re
-        .replace_all(&lib, |caps: &regex::Captures| {
+    *src = re
+        .replace_all(src, |caps: &regex::Captures| {
             let pubk = caps.get(1).map(|m| m.as_str()).unwrap_or("");
             let name = caps.get(2).unwrap().as_str();
-            let filename = format!("{}.rs", name);
-            if let Some(src) = files.get(&filename) {
-                format!(
-                    "{}mod {} {{ // start multifile\n{}\n}} // end multifile",
-                    pubk, name, src
-                )
+            let default_path = format!("{dir}{name}.rs");
+            let mod_path = format!("{dir}{name}/mod.rs");
+            let (path, marker) = if files.contains_key(&default_path) {
+                (default_path, String::new())
+            } else if files.contains_key(&mod_path) {
+                (mod_path.clone(), format!(" {mod_path}"))
             } else {
-                format!("std::compile_error!(\"Missing file: {}\");", filename)
-            }
+                return format!("std::compile_error!(\"Missing file: {}\");", default_path);
+            };
+            let mut child = files.remove(&path).unwrap();
+            join_children(files, &format!("{dir}{name}/"), &mut child);
+            format!(
+                "{}mod {} {{ // start multifile{}\n{}\n}} // end multifile",
+                pubk, name, marker, child
+            )
         })
         .into_owned();
-    Ok(new_lib)
+}
+
+/// Returns the path of a module given the directory of its parent's submodules
+/// and the path after the start marker, if any.
+fn module_path(dir: &str, name: &str, explicit: Option<&str>) -> String {
+    match explicit {
+        Some(path) => path.to_string(),
+        None => format!("{dir}{name}.rs"),
+    }
+}
+
+/// Parses a start marker line into (text before `mod`, module name, explicit path).
+fn parse_start(line: &str) -> Option<(&str, &str, Option<&str>)> {
+    lazy_static::lazy_static! {
+        static ref RE: regex::Regex =
+            regex::Regex::new(r"^(.*?)mod (\w+) \{ // start multifile(?: (\S+))?$").unwrap();
+    }
+    let caps = RE.captures(line)?;
+    Some((
+        caps.get(1).unwrap().as_str(),
+        caps.get(2).unwrap().as_str(),
+        caps.get(3).map(|m| m.as_str()),
+    ))
 }
 
 pub fn split(lib: &str) -> HashMap<String, String> {
+    struct Frame {
+        path: String,
+        // Directory containing this module's submodules.
+        dir: String,
+        // Text before `mod` on the start marker line.
+        prefix: String,
+        name: String,
+        lines: Vec<String>,
+    }
+
     let mut files = HashMap::new();
-    let re = regex::Regex::new(
-        r"(pub )?mod (\w+) \{ // start multifile\n(?s:(.*?))\n\} // end multifile",
-    )
-    .unwrap();
-    for caps in re.captures_iter(lib) {
-        let name = caps.get(2).unwrap().as_str();
-        let src = caps.get(3).unwrap().as_str();
-        files.insert(format!("{}.rs", name), src.to_string());
+    let mut stack = vec![Frame {
+        path: "lib.rs".to_string(),
+        dir: String::new(),
+        prefix: String::new(),
+        name: String::new(),
+        lines: vec![],
+    }];
+    for line in lib.split('\n') {
+        if let Some((prefix, name, explicit)) = parse_start(line) {
+            let dir = stack.last().unwrap().dir.clone();
+            stack.push(Frame {
+                path: module_path(&dir, name, explicit),
+                dir: format!("{dir}{name}/"),
+                prefix: prefix.to_string(),
+                name: name.to_string(),
+                lines: vec![],
+            });
+        } else if let (Some(rest), true) =
+            (line.strip_prefix("} // end multifile"), stack.len() > 1)
+        {
+            let frame = stack.pop().unwrap();
+            files.insert(frame.path, frame.lines.join("\n"));
+            stack
+                .last_mut()
+                .unwrap()
+                .lines
+                .push(format!("{}mod {};{}", frame.prefix, frame.name, rest));
+        } else {
+            stack.last_mut().unwrap().lines.push(line.to_string());
+        }
+    }
+    // Unterminated modules are left inline.
+    while stack.len() > 1 {
+        let frame = stack.pop().unwrap();
+        let parent = stack.last_mut().unwrap();
+        parent.lines.push(format!(
+            "{}mod {} {{ // start multifile",
+            frame.prefix, frame.name
+        ));
+        parent.lines.extend(frame.lines);
     }
-    let lib = re.replace_all(lib, "${1}mod $2;").into_owned();
-    files.insert("lib.rs".to_string(), lib);
+    let root = stack.pop().unwrap();
+    files.insert(root.path, root.lines.join("\n"));
     files
 }
 
+/// Maps a 1-based line number in one of the files passed to `join` to the
+/// corresponding line in its output. `file` is None for the root file. Returns
+/// None if the file or line isn't in the output.
+pub fn joined_line(joined: &str, file: Option<&str>, line: usize) -> Option<usize> {
+    // Stack of (filename, submodule directory, current line) with the root
+    // file at the bottom.
+    let mut stack: Vec<(Option<String>, String, usize)> = vec![(None, String::new(), 0)];
+    for (i, text) in joined.lines().enumerate() {
+        if text.starts_with("} // end multifile") && stack.len() > 1 {
+            // The rest of the line came from the line containing `mod foo;`.
+            stack.pop();
+        } else {
+            stack.last_mut().unwrap().2 += 1;
+            let (current_file, _, current_line) = stack.last().unwrap();
+            if current_file.as_deref() == file && *current_line == line {
+                return Some(i + 1);
+            }
+        }
+        if let Some((_, name, explicit)) = parse_start(text) {
+            let dir = stack.last().unwrap().1.clone();
+            stack.push((
+                Some(module_path(&dir, name, explicit)),
+                format!("{dir}{name}/"),
+                0,
+            ));
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
@@ -139,6 +245,61 @@ pub struct Ship {}
         assert_eq!(canonicalize(&splitfiles), canonicalize(&files));
     }
 
+    #[test]
+    fn test_joined_line() {
+        let mut files = std::collections::HashMap::new();
+        files.insert(
+            "lib.rs".to_string(),
+            "mod foo;\nfn a() {}\nmod bar;\nfn b() {}\n".to_string(),
+        );
+        files.insert(
+            "foo.rs".to_string(),
+            "fn foo1() {}\nfn foo2() {}".to_string(),
+        );
+        files.insert("bar.rs".to_string(), "fn bar() {}".to_string());
+        let joined = super::join(files).unwrap();
+        let root: Vec<_> = (1..=5)
+            .map(|i| super::joined_line(&joined, None, i))
+            .collect();
+        assert_eq!(root, vec![Some(1), Some(5), Some(6), Some(9), None]);
+        assert_eq!(super::joined_line(&joined, Some("foo.rs"), 1), Some(2));
+        assert_eq!(super::joined_line(&joined, Some("foo.rs"), 2), Some(3));
+        assert_eq!(super::joined_line(&joined, Some("foo.rs"), 3), None);
+        assert_eq!(super::joined_line(&joined, Some("bar.rs"), 1), Some(7));
+        assert_eq!(super::joined_line(&joined, Some("baz.rs"), 1), None);
+    }
+
+    #[test]
+    fn test_nested() {
+        let mut files = std::collections::HashMap::new();
+        files.insert("lib.rs".to_string(), "mod foo;\nmod baz;\n".to_string());
+        files.insert("foo.rs".to_string(), "pub mod bar;".to_string());
+        files.insert("foo/bar.rs".to_string(), "fn bar() {}".to_string());
+        files.insert("baz/mod.rs".to_string(), "mod qux;".to_string());
+        files.insert("baz/qux.rs".to_string(), "fn qux() {}".to_string());
+        let joined = super::join(files.clone()).unwrap();
+        assert_eq!(
+            joined,
+            "\
+mod foo { // start multifile
+pub mod bar { // start multifile
+fn bar() {}
+} // end multifile
+} // end multifile
+mod baz { // start multifile baz/mod.rs
+mod qux { // start multifile
+fn qux() {}
+} // end multifile
+} // end multifile
+"
+        );
+        assert_eq!(canonicalize(&super::split(&joined)), canonicalize(&files));
+
+        assert_eq!(super::joined_line(&joined, Some("foo/bar.rs"), 1), Some(3));
+        assert_eq!(super::joined_line(&joined, Some("baz/qux.rs"), 1), Some(8));
+        assert_eq!(super::joined_line(&joined, None, 2), Some(6));
+    }
+
     fn canonicalize(map: &HashMap<String, String>) -> Vec<(String, String)> {
         let mut v: Vec<_> = map.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
         v.sort();
@@ -46,6 +46,10 @@ pub enum Telemetry {
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct LeaderboardData {
     pub lowest_time: Vec<TimeLeaderboardRow>,
+    #[serde(default)]
+    pub smallest_code: Vec<CodeSizeLeaderboardRow>,
+    #[serde(default)]
+    pub lowest_cpu: Vec<CpuLeaderboardRow>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
@@ -59,6 +63,25 @@ pub struct TimeLeaderboardRow {
     pub shortcode: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CodeSizeLeaderboardRow {
+    pub userid: String,
+    pub username: Option<String>,
+    pub code_size: usize,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub shortcode: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CpuLeaderboardRow {
+    pub userid: String,
+    pub username: Option<String>,
+    /// Average gas used per ship per tick.
+    pub cpu_gas: f64,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub shortcode: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct LeaderboardSubmission {
     pub scenario_name: String,
@@ -70,10 +93,23 @@ pub struct LeaderboardSubmission {
     pub time: f64,
     pub code_size: usize,
     pub code: String,
+    #[serde(default)]
+    pub cpu_gas: Option<f64>,
+    #[serde(default)]
+    pub verification: Option<LeaderboardVerification>,
 }
 
 impl Eq for LeaderboardSubmission {}
 
+/// Set by the backend once a submission has been re-simulated.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LeaderboardVerification {
+    pub claimed_time: f64,
+    pub claimed_cpu_gas: Option<f64>,
+    /// The claimed time did not match the verified time.
+    pub mismatch: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct TournamentSubmission {
     pub scenario_name: String,
@@ -98,8 +134,14 @@ pub struct ShortcodeUpload {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct TournamentResults {
     pub scenario_name: String,
+    /// Sorted by final standing.
     pub competitors: Vec<TournamentCompetitor>,
     pub win_matrix: Vec<f64>,
+    #[serde(default)]
+    pub format: TournamentFormat,
+    /// Matches played in each round. Empty for round-robin tournaments.
+    #[serde(default)]
+    pub rounds: Vec<TournamentRound>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -107,4 +149,107 @@ pub struct TournamentCompetitor {
     pub username: String,
     pub shortcode: String,
     pub rating: f64,
+    /// Match points, for Swiss tournaments.
+    #[serde(default)]
+    pub points: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TournamentFormat {
+    #[default]
+    RoundRobin,
+    Swiss,
+    SingleElimination,
+    DoubleElimination,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TournamentRound {
+    pub name: String,
+    pub matches: Vec<TournamentMatch>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TournamentMatch {
+    pub username0: String,
+    /// None if username0 received a bye.
+    pub username1: Option<String>,
+    pub wins0: u32,
+    pub wins1: u32,
+    pub draws: u32,
+    /// None for a drawn match.
+    pub winner: Option<String>,
+}
+
+/// A persistent ladder entrant, stored per scenario and user.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LadderEntrant {
+    pub scenario_name: String,
+    pub userid: String,
+    pub username: String,
+    /// Timestamp of the tournament submission currently on the ladder.
+    #[serde(with = "ts_milliseconds")]
+    pub submission_timestamp: DateTime<Utc>,
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+    #[serde(default)]
+    pub history: Vec<LadderHistoryEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LadderHistoryEntry {
+    #[serde(with = "ts_milliseconds")]
+    pub timestamp: DateTime<Utc>,
+    pub rating: f64,
+    pub deviation: f64,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+/// Response body of a failed compile when JSON diagnostics are requested.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct CompilerDiagnostics {
+    /// Human-readable compiler output.
+    pub rendered: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+/// A compiler message located in the user's code.
+///
+/// Lines and columns are 1-based and refer to the file the user wrote, even
+/// when it was joined with others into a single crate.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Error code such as "E0308".
+    pub code: Option<String>,
+    pub message: String,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// A suggested edit replacing the given range.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub message: String,
+    pub replacement: String,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
 }
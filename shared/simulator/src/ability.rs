@@ -0,0 +1,104 @@
+//! Abilities that affect other ships.
+//!
+//! Abilities that only change the ship using them are handled where that
+//! ship's behavior is computed, e.g. boost in `ship` and cloak in `radar`.
+use crate::obstacle;
+use crate::physics::{self, Walls};
+use crate::ship::{ShipClass, ShipHandle};
+use crate::simulation::{Particle, Simulation};
+use nalgebra::{vector, Vector2};
+use oort_api::Ability;
+use std::f64::consts::{PI, TAU};
+
+/// Multiplier for the radar cross section of a cloaked ship.
+pub const CLOAK_RADAR_CROSS_SECTION_FACTOR: f64 = 0.05;
+pub const POINT_DEFENSE_RANGE: f64 = 1000.0;
+pub const TRACTOR_BEAM_RANGE: f64 = 2000.0;
+/// Force (in newtons) pulling the target and the ship together.
+pub const TRACTOR_BEAM_FORCE: f64 = 1e6;
+
+pub(crate) fn tick(sim: &mut Simulation) {
+    let handles: Vec<ShipHandle> = sim.ships.iter().cloned().collect();
+    for handle in handles {
+        if sim.ship(handle).is_ability_active(Ability::PointDefense) {
+            point_defense(sim, handle);
+        }
+        if sim.ship(handle).is_ability_active(Ability::TractorBeam) {
+            tractor_beam(sim, handle);
+        }
+    }
+}
+
+/// Destroys the closest enemy missile or torpedo in range.
+fn point_defense(sim: &mut Simulation, handle: ShipHandle) {
+    let team = sim.ship(handle).data().team;
+    let target = closest_ship(sim, handle, POINT_DEFENSE_RANGE, |other, _| {
+        let ship = sim.ship(other);
+        let data = ship.data();
+        data.team != team
+            && !data.destroyed
+            && matches!(data.class, ShipClass::Missile | ShipClass::Torpedo)
+    });
+    if let Some((target, _)) = target {
+        let position = sim.ship(target).position().vector;
+        let velocity = sim.ship(target).velocity();
+        sim.ship_mut(target).data_mut().destroyed = true;
+        let lifetime = (sim.physics().tick_length * 10.0) as f32;
+        sim.events.particles.push(Particle {
+            position,
+            velocity,
+            color: vector![1.0, 0.4, 0.4, 1.0],
+            lifetime,
+        });
+    }
+}
+
+/// Pulls the closest ship in the radar beam towards this ship, and this ship
+/// towards it.
+fn tractor_beam(sim: &mut Simulation, handle: ShipHandle) {
+    let Some((heading, width)) = sim
+        .ship(handle)
+        .radar()
+        .map(|radar| (radar.heading, radar.width))
+    else {
+        return;
+    };
+    let target = closest_ship(sim, handle, TRACTOR_BEAM_RANGE, |other, d| {
+        let class = sim.ship(other).data().class;
+        let angle = (d.y.atan2(d.x) - heading + PI).rem_euclid(TAU) - PI;
+        class != ShipClass::Planet && class != ShipClass::Beacon && angle.abs() <= width / 2.0
+    });
+    if let Some((target, d)) = target {
+        if d.norm() == 0.0 {
+            return;
+        }
+        let impulse = d.normalize() * (TRACTOR_BEAM_FORCE * sim.physics().tick_length);
+        sim.ship_mut(target).body().apply_impulse(-impulse, true);
+        sim.ship_mut(handle).body().apply_impulse(impulse, true);
+    }
+}
+
+/// Returns the closest ship within range and line of sight that is accepted by
+/// `filter`, along with the displacement to it.
+fn closest_ship(
+    sim: &Simulation,
+    handle: ShipHandle,
+    range: f64,
+    filter: impl Fn(ShipHandle, Vector2<f64>) -> bool,
+) -> Option<(ShipHandle, Vector2<f64>)> {
+    let position = sim.ship(handle).position().vector;
+    let wrap = sim.physics().walls == Walls::Wrap;
+    sim.ships
+        .iter()
+        .filter(|&&other| other != handle)
+        .map(|&other| {
+            let mut d = sim.ship(other).position().vector - position;
+            if wrap {
+                d = physics::wrap_delta(d, sim.world_size());
+            }
+            (other, d)
+        })
+        .filter(|(other, d)| d.norm() < range && filter(*other, *d))
+        .filter(|(_, d)| !obstacle::is_occluded(sim, position.into(), (position + d).into()))
+        .min_by(|a, b| a.1.norm().total_cmp(&b.1.norm()))
+}
@@ -1,10 +1,13 @@
 use std::collections::HashMap;
 
 use super::index_set::{HasIndex, Index};
-use crate::simulation::{Simulation, MAX_WORLD_SIZE, PHYSICS_TICK_LENGTH};
-use crate::{collision, simulation};
+use crate::collision;
+use crate::physics::{self, Walls};
+use crate::ship::ShipHandle;
+use crate::simulation::Simulation;
 use bitvec::vec::BitVec;
 use nalgebra::Vector2;
+use rapier2d_f64::parry::query::RayCast;
 use rapier2d_f64::prelude::*;
 use static_aabb2d_index::*;
 
@@ -86,7 +89,7 @@ pub fn destroy(sim: &mut Simulation, handle: BulletHandle) {
 }
 
 pub fn tick(sim: &mut Simulation) {
-    let dt = PHYSICS_TICK_LENGTH;
+    let dt = sim.physics().tick_length;
     let (indices_by_team, coarse_grids_by_team) = build_indices(sim, dt);
     let mut stack = Vec::new();
     let shape = rapier2d_f64::geometry::Ball { radius: 1.0 };
@@ -107,17 +110,23 @@ pub fn tick(sim: &mut Simulation) {
         let mut needs_collider = false;
         {
             let world_size = sim.world_size();
+            let wrap = sim.physics().walls == Walls::Wrap;
             let body = sim.bodies.get_mut(RigidBodyHandle(handle.index())).unwrap();
             has_collider = !body.colliders().is_empty();
 
-            let position = *body.translation();
+            let mut position = *body.translation();
             if position.x < -world_size / 2.0
                 || position.x > world_size / 2.0
                 || position.y < -world_size / 2.0
                 || position.y > world_size / 2.0
             {
-                destroy(sim, handle);
-                continue;
+                if wrap {
+                    position = physics::wrap_position(position, world_size);
+                    body.set_translation(position, false);
+                } else {
+                    destroy(sim, handle);
+                    continue;
+                }
             }
 
             coarse_grid_hit = coarse_grids_by_team
@@ -129,7 +138,7 @@ pub fn tick(sim: &mut Simulation) {
                     &body.predict_position_using_velocity_and_forces(dt),
                 );
 
-                for (other_team, index) in indices_by_team.iter() {
+                for (other_team, (index, _)) in indices_by_team.iter() {
                     if team != *other_team {
                         needs_collider = needs_collider
                             || index
@@ -145,6 +154,25 @@ pub fn tick(sim: &mut Simulation) {
                     }
                 }
             }
+
+            if !sim.obstacles.is_empty() {
+                let aabb = shape.compute_swept_aabb(
+                    body.position(),
+                    &body.predict_position_using_velocity_and_forces(dt),
+                );
+                needs_collider = needs_collider
+                    || sim
+                        .obstacles
+                        .iter()
+                        .any(|obstacle| obstacle.aabb.intersects(&aabb));
+            }
+        }
+
+        if sim.physics().walls == Walls::Wrap {
+            hit_across_edge(sim, handle, team, &indices_by_team, dt, &mut stack);
+            if !sim.bullets.contains(handle) {
+                continue;
+            }
         }
 
         if needs_collider && !has_collider {
@@ -165,14 +193,11 @@ pub fn tick(sim: &mut Simulation) {
     }
 }
 
-fn build_indices(
-    sim: &Simulation,
-    dt: f64,
-) -> (
-    HashMap<i32, StaticAABB2DIndex<f64>>,
-    HashMap<i32, CoarseGrid>,
-) {
-    let mut aabbs_by_team: HashMap<i32, Vec<Aabb>> = HashMap::new();
+/// Spatial index of a team's ships, along with the handles of the indexed ships.
+type ShipIndex = (StaticAABB2DIndex<f64>, Vec<ShipHandle>);
+
+fn build_indices(sim: &Simulation, dt: f64) -> (HashMap<i32, ShipIndex>, HashMap<i32, CoarseGrid>) {
+    let mut aabbs_by_team: HashMap<i32, Vec<(Aabb, ShipHandle)>> = HashMap::new();
     let mut coarse_grids_by_team: HashMap<i32, CoarseGrid> = HashMap::new();
 
     for handle in sim.ships.iter() {
@@ -182,22 +207,83 @@ fn build_indices(
         let aabb =
             collider.compute_swept_aabb(&body.predict_position_using_velocity_and_forces(dt));
         let team = sim.ship(*handle).data().team;
-        aabbs_by_team.entry(team).or_default().push(aabb);
-        coarse_grids_by_team.entry(team).or_default().insert(aabb);
+        aabbs_by_team.entry(team).or_default().push((aabb, *handle));
+        coarse_grids_by_team
+            .entry(team)
+            .or_insert_with(|| CoarseGrid::new(sim.world_size()))
+            .insert(aabb);
     }
 
-    let mut indices_by_team: HashMap<i32, StaticAABB2DIndex<f64>> = HashMap::new();
+    let mut indices_by_team: HashMap<i32, ShipIndex> = HashMap::new();
     for (team, aabbs) in aabbs_by_team {
         let mut builder = StaticAABB2DIndexBuilder::new(aabbs.len());
-        for aabb in aabbs {
+        let mut handles = Vec::with_capacity(aabbs.len());
+        for (aabb, handle) in aabbs {
             builder.add(aabb.mins.x, aabb.mins.y, aabb.maxs.x, aabb.maxs.y);
+            handles.push(handle);
         }
-        indices_by_team.insert(team, builder.build().unwrap());
+        indices_by_team.insert(team, (builder.build().unwrap(), handles));
     }
 
     (indices_by_team, coarse_grids_by_team)
 }
 
+/// Hits a ship on the other side of the edge of a wrapping world. The physics
+/// engine doesn't know about wrapping, so it only sees ships on the same side
+/// as the bullet.
+fn hit_across_edge(
+    sim: &mut Simulation,
+    handle: BulletHandle,
+    team: i32,
+    indices_by_team: &HashMap<i32, ShipIndex>,
+    dt: f64,
+    stack: &mut Vec<usize>,
+) {
+    let world_size = sim.world_size();
+    let (position, velocity) = {
+        let body = body(sim, handle);
+        (*body.translation(), *body.linvel())
+    };
+    for dx in [-world_size, 0.0, world_size] {
+        for dy in [-world_size, 0.0, world_size] {
+            if dx == 0.0 && dy == 0.0 {
+                continue;
+            }
+            let start = Point::from(position + vector![dx, dy]);
+            let end = start + velocity * dt;
+            let aabb = Aabb::from_points(&[start, end]);
+            for (other_team, (index, handles)) in indices_by_team.iter() {
+                if team == *other_team {
+                    continue;
+                }
+                for i in index.query_iter_with_stack(
+                    aabb.mins.x,
+                    aabb.mins.y,
+                    aabb.maxs.x,
+                    aabb.maxs.y,
+                    stack,
+                ) {
+                    let ship = handles[i];
+                    let ship_accessor = sim.ship(ship);
+                    let ray = Ray::new(start, (velocity - ship_accessor.velocity()) * dt);
+                    let collider = &sim.colliders[ship_accessor.body().colliders()[0]];
+                    if collider
+                        .shape()
+                        .intersects_ray(collider.position(), &ray, 1.0)
+                    {
+                        // Move the bullet next to the ship so the hit is
+                        // handled as if they were on the same side. It wraps
+                        // back on the next tick if it survives.
+                        body_mut(sim, handle).set_translation(start.coords, false);
+                        collision::handle_hit(sim, ship, handle);
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
 fn add_collider(sim: &mut Simulation, handle: BulletHandle) {
     let team = data(sim, handle).team;
     let collider = ColliderBuilder::ball(1.0)
@@ -229,28 +315,36 @@ fn remove_collider(sim: &mut Simulation, handle: BulletHandle) {
 
 struct CoarseGrid {
     cells: BitVec,
+    world_size: f64,
+    world_width: f64,
+    width: i32,
 }
 
 impl CoarseGrid {
     const CELL_SIZE: f64 = 300.0;
     const RECIP_CELL_SIZE: f64 = 1.0 / Self::CELL_SIZE;
-    const WORLD_WIDTH: f64 = simulation::MAX_WORLD_SIZE + Self::CELL_SIZE * 2.0;
-    const WIDTH: i32 = (Self::WORLD_WIDTH / Self::CELL_SIZE) as i32;
 
-    fn to_cell(p: Vector2<f64>) -> usize {
-        let x = ((Self::WORLD_WIDTH / 2.0 + p.x) * Self::RECIP_CELL_SIZE) as i32;
-        let y = ((Self::WORLD_WIDTH / 2.0 + p.y) * Self::RECIP_CELL_SIZE) as i32;
-        (Self::WIDTH * y + x) as usize
+    fn to_cell(&self, p: Vector2<f64>) -> usize {
+        let x = ((self.world_width / 2.0 + p.x) * Self::RECIP_CELL_SIZE) as i32;
+        let y = ((self.world_width / 2.0 + p.y) * Self::RECIP_CELL_SIZE) as i32;
+        (self.width * y + x) as usize
     }
 
-    pub fn new() -> Self {
+    pub fn new(world_size: f64) -> Self {
+        let world_width = world_size + Self::CELL_SIZE * 2.0;
+        let width = (world_width / Self::CELL_SIZE) as i32;
         let mut cells = BitVec::new();
-        cells.resize((Self::WIDTH * Self::WIDTH) as usize, false);
-        Self { cells }
+        cells.resize((width * width) as usize, false);
+        Self {
+            cells,
+            world_size,
+            world_width,
+            width,
+        }
     }
 
     pub fn lookup(&self, p: Vector2<f64>) -> bool {
-        let index: usize = Self::to_cell(p);
+        let index: usize = self.to_cell(p);
         if index < self.cells.len() {
             self.cells[index]
         } else {
@@ -263,23 +357,17 @@ impl CoarseGrid {
         aabb.maxs += vector![Self::CELL_SIZE, Self::CELL_SIZE];
         if let Some(aabb) = aabb.intersection(&Aabb::from_half_extents(
             point![0.0, 0.0],
-            vector![MAX_WORLD_SIZE / 2.0, MAX_WORLD_SIZE / 2.0],
+            vector![self.world_size / 2.0, self.world_size / 2.0],
         )) {
             let w = ((aabb.maxs.x - aabb.mins.x) * Self::RECIP_CELL_SIZE).ceil() as i32;
             let h = ((aabb.maxs.y - aabb.mins.y) * Self::RECIP_CELL_SIZE).ceil() as i32;
-            let min_index = Self::to_cell(aabb.mins.coords);
+            let min_index = self.to_cell(aabb.mins.coords);
             for y in 0..h {
                 for x in 0..w {
-                    let index = (min_index as i32 + x + y * Self::WIDTH) as usize;
+                    let index = (min_index as i32 + x + y * self.width) as usize;
                     self.cells.set(index, true);
                 }
             }
         }
     }
 }
-
-impl Default for CoarseGrid {
-    fn default() -> Self {
-        Self::new()
-    }
-}
@@ -1,14 +1,14 @@
 use crate::bullet::{self, BulletHandle};
 use crate::index_set::HasIndex;
+use crate::physics::Walls;
 use crate::ship::{ShipClass, ShipHandle};
-use crate::simulation::{Particle, Simulation, PHYSICS_TICK_LENGTH};
+use crate::simulation::{Particle, Simulation};
 use nalgebra::{ComplexField, Rotation2, UnitComplex};
 use oort_api::Ability;
 use rand::Rng;
 use rapier2d_f64::prelude::*;
 use std::f64::consts::TAU;
 
-const DAMAGE_FACTOR: f64 = 0.00014;
 const WALL_COLLISION_GROUP: Group = Group::GROUP_1;
 const SHIP_COLLISION_GROUP: Group = Group::GROUP_2;
 const PLANET_COLLISION_GROUP: Group = Group::GROUP_3;
@@ -69,78 +69,79 @@ pub fn beacon_interaction_groups() -> InteractionGroups {
     InteractionGroups::new(Group::GROUP_14, Group::empty())
 }
 
+/// Damages a ship hit by a bullet, or reflects the bullet off its shield.
+pub(crate) fn handle_hit(sim: &mut Simulation, ship: ShipHandle, bullet: BulletHandle) {
+    let (bullet_position, bullet_velocity) = {
+        let body = bullet::body(sim, bullet);
+        (body.position().translation.vector, *body.linvel())
+    };
+    if sim.ship(ship).is_ability_active(Ability::Shield) {
+        let dp = bullet_position - sim.ship(ship).position().vector;
+        let normal = dp.normalize();
+        let new_bullet_velocity = normal * bullet_velocity.magnitude();
+        {
+            let tick_length = sim.physics().tick_length;
+            let body = bullet::body_mut(sim, bullet);
+            body.set_linvel(new_bullet_velocity, false);
+            body.set_translation(bullet_position + new_bullet_velocity * tick_length, false);
+        }
+        bullet::data_mut(sim, bullet).team = sim.ship(ship).data().team;
+        return;
+    }
+    if bullet::data(sim, bullet).team == sim.ship(ship).data().team {
+        bullet::destroy(sim, bullet);
+        return;
+    }
+    let dv = bullet_velocity - sim.ship(ship).velocity();
+    let energy = 0.5 * bullet::data(sim, bullet).mass as f64 * dv.magnitude_squared();
+    let damage = energy * sim.physics().damage_factor;
+    sim.events.hits.push((ship.into(), damage));
+    let tick_length = sim.physics().tick_length;
+    for _ in 0..((damage as i32 / 10).clamp(1, 20)) {
+        let rot = Rotation2::new(sim.rng.gen_range(0.0..TAU));
+        let v = rot.transform_vector(&vector![sim.rng.gen_range(0.0..1000.0), 0.0]);
+        let p = bullet_position + v * sim.rng.gen_range(0.0..0.1);
+        sim.events.particles.push(Particle {
+            position: p,
+            velocity: v,
+            color: vector![1.0, 1.0, 1.0, sim.rng.gen_range(0.5..1.0)],
+            lifetime: (tick_length * 30.0) as f32,
+        });
+    }
+    let ship_destroyed = {
+        let ship_data = sim.ship_data.get_mut(ship.index()).unwrap();
+        ship_data.health -= damage;
+        ship_data.health <= 0.0
+    };
+    if ship_destroyed {
+        for _ in 0..10 {
+            let rot = Rotation2::new(sim.rng.gen_range(0.0..TAU));
+            let v = rot.transform_vector(&vector![sim.rng.gen_range(0.0..200.0), 0.0]);
+            let p = sim.ship(ship).body().position().translation.vector
+                + v * sim.rng.gen_range(0.0..0.1);
+            let lifetime = (ComplexField::log2(sim.ship_data.get(ship.index()).unwrap().mass)
+                * tick_length) as f32;
+            sim.events.particles.push(Particle {
+                position: p,
+                velocity: v,
+                color: vector![1.0, 1.0, 1.0, sim.rng.gen_range(0.5..1.0)],
+                lifetime,
+            });
+        }
+        sim.ship_mut(ship).data_mut().destroyed = true;
+        bullet::data_mut(sim, bullet).mass *= 0.5;
+        let rotation = UnitComplex::new(sim.rng.gen_range(-0.1..0.1));
+        let new_bullet_velocity = rotation.transform_vector(&bullet_velocity);
+        bullet::body_mut(sim, bullet).set_linvel(new_bullet_velocity, false);
+    } else {
+        bullet::destroy(sim, bullet);
+    }
+}
+
 pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
     for event in events {
         if let CollisionEvent::Started(h1, h2, _flags) = event {
             let get_index = |h| sim.colliders.get(h).and_then(|x| x.parent()).map(|x| x.0);
-            let handle_hit = |sim: &mut Simulation, ship, bullet: BulletHandle| {
-                let (bullet_position, bullet_velocity) = {
-                    let body = bullet::body(sim, bullet);
-                    (body.position().translation.vector, *body.linvel())
-                };
-                if sim.ship(ship).is_ability_active(Ability::Shield) {
-                    let dp = bullet_position - sim.ship(ship).position().vector;
-                    let normal = dp.normalize();
-                    let new_bullet_velocity = normal * bullet_velocity.magnitude();
-                    {
-                        let body = bullet::body_mut(sim, bullet);
-                        body.set_linvel(new_bullet_velocity, false);
-                        body.set_translation(
-                            bullet_position + new_bullet_velocity * PHYSICS_TICK_LENGTH,
-                            false,
-                        );
-                    }
-                    bullet::data_mut(sim, bullet).team = sim.ship(ship).data().team;
-                    return;
-                }
-                if bullet::data(sim, bullet).team == sim.ship(ship).data().team {
-                    bullet::destroy(sim, bullet);
-                    return;
-                }
-                let dv = bullet_velocity - sim.ship(ship).velocity();
-                let energy = 0.5 * bullet::data(sim, bullet).mass as f64 * dv.magnitude_squared();
-                let damage = energy * DAMAGE_FACTOR;
-                for _ in 0..((damage as i32 / 10).clamp(1, 20)) {
-                    let rot = Rotation2::new(sim.rng.gen_range(0.0..TAU));
-                    let v = rot.transform_vector(&vector![sim.rng.gen_range(0.0..1000.0), 0.0]);
-                    let p = bullet_position + v * sim.rng.gen_range(0.0..0.1);
-                    sim.events.particles.push(Particle {
-                        position: p,
-                        velocity: v,
-                        color: vector![1.0, 1.0, 1.0, sim.rng.gen_range(0.5..1.0)],
-                        lifetime: (PHYSICS_TICK_LENGTH * 30.0) as f32,
-                    });
-                }
-                let ship_destroyed = {
-                    let ship_data = sim.ship_data.get_mut(ship.index()).unwrap();
-                    ship_data.health -= damage;
-                    ship_data.health <= 0.0
-                };
-                if ship_destroyed {
-                    for _ in 0..10 {
-                        let rot = Rotation2::new(sim.rng.gen_range(0.0..TAU));
-                        let v = rot.transform_vector(&vector![sim.rng.gen_range(0.0..200.0), 0.0]);
-                        let p = sim.ship(ship).body().position().translation.vector
-                            + v * sim.rng.gen_range(0.0..0.1);
-                        let lifetime =
-                            (ComplexField::log2(sim.ship_data.get(ship.index()).unwrap().mass)
-                                * PHYSICS_TICK_LENGTH) as f32;
-                        sim.events.particles.push(Particle {
-                            position: p,
-                            velocity: v,
-                            color: vector![1.0, 1.0, 1.0, sim.rng.gen_range(0.5..1.0)],
-                            lifetime,
-                        });
-                    }
-                    sim.ship_mut(ship).data_mut().destroyed = true;
-                    bullet::data_mut(sim, bullet).mass *= 0.5;
-                    let rotation = UnitComplex::new(sim.rng.gen_range(-0.1..0.1));
-                    let new_bullet_velocity = rotation.transform_vector(&bullet_velocity);
-                    bullet::body_mut(sim, bullet).set_linvel(new_bullet_velocity, false);
-                } else {
-                    bullet::destroy(sim, bullet);
-                }
-            };
             if let (Some(idx1), Some(idx2)) = (get_index(*h1), get_index(*h2)) {
                 #[derive(Ord, Eq, PartialOrd, PartialEq)]
                 enum Collider {
@@ -185,7 +186,11 @@ pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
 }
 
 pub fn add_walls(sim: &mut Simulation) {
+    if sim.physics().walls != Walls::Solid {
+        return;
+    }
     let world_size = sim.world_size();
+    let restitution = sim.physics().wall_restitution;
     let mut make_edge = |x: f64, y: f64, a: f64| {
         let edge_length = world_size;
         let edge_width = 10.0;
@@ -195,7 +200,7 @@ pub fn add_walls(sim: &mut Simulation) {
             .build();
         let body_handle = sim.bodies.insert(rigid_body);
         let collider = ColliderBuilder::cuboid(edge_length / 2.0, edge_width / 2.0)
-            .restitution(1.0)
+            .restitution(restitution)
             .collision_groups(wall_interaction_groups())
             .build();
         sim.colliders
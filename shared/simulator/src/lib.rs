@@ -1,9 +1,13 @@
+pub mod ability;
 pub mod bullet;
 pub mod collision;
 pub mod color;
 pub mod debug;
 pub mod index_set;
 pub mod model;
+pub mod obstacle;
+pub mod physics;
+pub mod pool;
 pub mod radar;
 pub mod radio;
 pub mod rng;
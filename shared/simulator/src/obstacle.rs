@@ -0,0 +1,94 @@
+//! Static polygons placed by a scenario.
+//!
+//! Obstacles behave like the walls at the edge of the world: ships that hit
+//! them explode and bullets are destroyed. They also block radar and radio.
+use crate::collision;
+use crate::physics::{self, Walls};
+use crate::simulation::{Line, Simulation};
+use nalgebra::{vector, Point2};
+use rapier2d_f64::parry::query::RayCast;
+use rapier2d_f64::prelude::*;
+
+#[derive(Clone, Debug)]
+pub struct Obstacle {
+    pub vertices: Vec<Point2<f64>>,
+    pub(crate) aabb: Aabb,
+    collider: ColliderHandle,
+}
+
+/// Adds an obstacle with the given outline, which doesn't need to be convex.
+pub fn create(sim: &mut Simulation, vertices: Vec<Point2<f64>>) {
+    let n = vertices.len() as u32;
+    let indices: Vec<[u32; 2]> = (0..n).map(|i| [i, (i + 1) % n]).collect();
+    let body_handle = sim.bodies.insert(RigidBodyBuilder::fixed().build());
+    let collider = ColliderBuilder::convex_decomposition(&vertices, &indices)
+        .restitution(sim.physics().wall_restitution)
+        .collision_groups(collision::wall_interaction_groups())
+        .build();
+    let collider = sim
+        .colliders
+        .insert_with_parent(collider, body_handle, &mut sim.bodies);
+    let aabb = Aabb::from_points(&vertices);
+    sim.obstacles.push(Obstacle {
+        vertices,
+        aabb,
+        collider,
+    });
+}
+
+/// Returns true if the line between two points passes through an obstacle.
+///
+/// In a wrapping world the line is the shortest path between the points,
+/// which may cross the edge of the world.
+pub fn is_occluded(sim: &Simulation, a: Point2<f64>, b: Point2<f64>) -> bool {
+    if sim.obstacles.is_empty() {
+        return false;
+    }
+    if sim.physics().walls != Walls::Wrap {
+        return is_segment_occluded(sim, a, b);
+    }
+    // Check the parts of the line that lie in neighbouring copies of the
+    // world by shifting it by the world size.
+    let world_size = sim.world_size();
+    let d = physics::wrap_delta(b - a, world_size);
+    for dx in [-world_size, 0.0, world_size] {
+        for dy in [-world_size, 0.0, world_size] {
+            let a = a + vector![dx, dy];
+            if is_segment_occluded(sim, a, a + d) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn is_segment_occluded(sim: &Simulation, a: Point2<f64>, b: Point2<f64>) -> bool {
+    let segment_aabb = Aabb::from_points(&[a, b]);
+    let ray = Ray::new(a, b - a);
+    sim.obstacles.iter().any(|obstacle| {
+        if !obstacle.aabb.intersects(&segment_aabb) {
+            return false;
+        }
+        let collider = &sim.colliders[obstacle.collider];
+        collider
+            .shape()
+            .intersects_ray(collider.position(), &ray, 1.0)
+    })
+}
+
+/// Outlines of the obstacles, for drawing.
+pub fn lines(sim: &Simulation) -> Vec<Line> {
+    let color = vector![0.6, 0.6, 0.6, 1.0];
+    let mut lines = vec![];
+    for obstacle in sim.obstacles.iter() {
+        let n = obstacle.vertices.len();
+        for i in 0..n {
+            lines.push(Line {
+                a: obstacle.vertices[i],
+                b: obstacle.vertices[(i + 1) % n],
+                color,
+            });
+        }
+    }
+    lines
+}
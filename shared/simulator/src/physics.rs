@@ -0,0 +1,100 @@
+//! Physical parameters that a scenario can override.
+use crate::ship::{ShipClass, ShipHandle};
+use crate::simulation::{Simulation, PHYSICS_TICK_LENGTH};
+use nalgebra::Vector2;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Damage per joule of bullet impact energy.
+pub const DEFAULT_DAMAGE_FACTOR: f64 = 0.00014;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Walls {
+    /// Ships bounce off walls at the edge of the world.
+    Solid,
+    /// Ships can leave the world. Bullets are still destroyed at the edge.
+    Open,
+    /// Ships and bullets leaving one edge of the world reappear at the
+    /// opposite edge. Radar and radio use the shortest wrapped distance.
+    Wrap,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PhysicsConfig {
+    /// Simulated seconds per tick.
+    pub tick_length: f64,
+    /// Continuous collision detection substeps per tick.
+    pub max_ccd_substeps: usize,
+    pub damage_factor: f64,
+    /// Restitution of ship colliders, overriding the default for the class.
+    pub restitution: BTreeMap<ShipClass, f64>,
+    pub walls: Walls,
+    pub wall_restitution: f64,
+}
+
+impl PhysicsConfig {
+    pub fn restitution(&self, class: ShipClass) -> f64 {
+        self.restitution
+            .get(&class)
+            .copied()
+            .unwrap_or(match class {
+                ShipClass::Missile => 0.0,
+                _ => 0.1,
+            })
+    }
+
+    /// Environment variables that tell ship code about the physics.
+    pub fn environment(&self) -> BTreeMap<String, String> {
+        let walls = match self.walls {
+            Walls::Solid => "solid",
+            Walls::Open => "open",
+            Walls::Wrap => "wrap",
+        };
+        BTreeMap::from([
+            ("TICK_LENGTH".to_string(), format!("{}", self.tick_length)),
+            (
+                "DAMAGE_FACTOR".to_string(),
+                format!("{}", self.damage_factor),
+            ),
+            ("WALLS".to_string(), walls.to_string()),
+        ])
+    }
+}
+
+/// Returns the shortest displacement equivalent to `d` in a world that wraps
+/// around at `world_size`.
+pub fn wrap_delta(d: Vector2<f64>, world_size: f64) -> Vector2<f64> {
+    d.map(|x| x - world_size * (x / world_size).round())
+}
+
+/// Moves a position outside the world to the equivalent position inside it.
+pub fn wrap_position(p: Vector2<f64>, world_size: f64) -> Vector2<f64> {
+    p.map(|x| (x + world_size / 2.0).rem_euclid(world_size) - world_size / 2.0)
+}
+
+/// Moves ships that have left the world to the opposite edge.
+pub(crate) fn wrap_ships(sim: &mut Simulation) {
+    let world_size = sim.world_size();
+    let handles: Vec<ShipHandle> = sim.ships.iter().cloned().collect();
+    for handle in handles {
+        let mut ship = sim.ship_mut(handle);
+        let body = ship.body();
+        let position = *body.translation();
+        if position.amax() > world_size / 2.0 {
+            body.set_translation(wrap_position(position, world_size), true);
+        }
+    }
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            tick_length: PHYSICS_TICK_LENGTH,
+            max_ccd_substeps: 2,
+            damage_factor: DEFAULT_DAMAGE_FACTOR,
+            restitution: BTreeMap::new(),
+            walls: Walls::Solid,
+            wall_restitution: 1.0,
+        }
+    }
+}
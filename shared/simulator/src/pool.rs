@@ -0,0 +1,125 @@
+//! Runs batches of simulations that share compiled code.
+//!
+//! Compiling a WASM module is much slower than instantiating it, so tools that
+//! run the same AIs on many seeds or in many matches should go through a
+//! `SimulationPool` instead of calling `Simulation::new` each time.
+use crate::scenario::{self, Status};
+use crate::simulation::{Code, Simulation};
+use crate::vm::{CompiledCode, Error};
+use std::collections::HashMap;
+
+/// The outcome of one simulation.
+#[derive(Clone, Debug)]
+pub struct SeedResult {
+    pub seed: u32,
+    pub status: Status,
+    pub score_time: f64,
+    pub time: f64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BatchResults {
+    pub results: Vec<SeedResult>,
+}
+
+impl BatchResults {
+    /// Seeds won by the given team.
+    pub fn wins(&self, team: i32) -> Vec<u32> {
+        self.seeds_with(|status| status == Status::Victory { team })
+    }
+
+    pub fn draws(&self) -> Vec<u32> {
+        self.seeds_with(|status| status == Status::Draw)
+    }
+
+    /// Seeds that failed or ran out of time.
+    pub fn failures(&self) -> Vec<u32> {
+        self.seeds_with(|status| matches!(status, Status::Failed | Status::Running))
+    }
+
+    pub fn average_score_time(&self) -> f64 {
+        self.results.iter().map(|x| x.score_time).sum::<f64>() / self.results.len() as f64
+    }
+
+    fn seeds_with(&self, f: impl Fn(Status) -> bool) -> Vec<u32> {
+        self.results
+            .iter()
+            .filter(|x| f(x.status))
+            .map(|x| x.seed)
+            .collect()
+    }
+}
+
+pub struct SimulationPool {
+    scenario_name: String,
+    compiled: HashMap<Code, Result<CompiledCode, Error>>,
+}
+
+impl SimulationPool {
+    pub fn new(scenario_name: &str) -> Self {
+        Self {
+            scenario_name: scenario_name.to_string(),
+            compiled: HashMap::new(),
+        }
+    }
+
+    /// Compiles code so that later simulations can share it. Code that isn't
+    /// added first is compiled separately for each simulation.
+    pub fn add(&mut self, code: &Code) {
+        if !matches!(code, Code::None) && !self.compiled.contains_key(code) {
+            self.compiled.insert(code.clone(), CompiledCode::new(code));
+        }
+    }
+
+    pub fn simulation(&self, seed: u32, codes: &[Code]) -> Box<Simulation> {
+        Simulation::with_team_controllers(&self.scenario_name, seed, |sim| {
+            for (team, code) in codes.iter().enumerate() {
+                if matches!(code, Code::None) {
+                    continue;
+                }
+                let team_ctrl = match self.compiled.get(code) {
+                    Some(compiled) => compiled
+                        .as_ref()
+                        .map_err(|e| e.clone())
+                        .and_then(|x| x.team_controller()),
+                    None => CompiledCode::new(code).and_then(|x| x.team_controller()),
+                };
+                sim.upload_team_controller(team as i32, team_ctrl);
+            }
+        })
+    }
+
+    /// Runs a simulation until it finishes or reaches the tick limit.
+    pub fn run(&self, seed: u32, codes: &[Code]) -> SeedResult {
+        let mut sim = self.simulation(seed, codes);
+        while sim.status() == Status::Running && sim.tick() < scenario::MAX_TICKS {
+            sim.step();
+        }
+        SeedResult {
+            seed,
+            status: sim.status(),
+            score_time: sim.score_time(),
+            time: sim.time(),
+        }
+    }
+
+    /// Runs the same codes on each seed, in parallel where threads are
+    /// available. Results are in the same order as `seeds`.
+    pub fn run_seeds(&mut self, codes: &[Code], seeds: &[u32]) -> BatchResults {
+        for code in codes {
+            self.add(code);
+        }
+        let pool = &*self;
+        #[cfg(not(target_arch = "wasm32"))]
+        let results = {
+            use rayon::prelude::*;
+            seeds
+                .par_iter()
+                .map(|&seed| pool.run(seed, codes))
+                .collect()
+        };
+        #[cfg(target_arch = "wasm32")]
+        let results = seeds.iter().map(|&seed| pool.run(seed, codes)).collect();
+        BatchResults { results }
+    }
+}
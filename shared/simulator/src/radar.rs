@@ -1,6 +1,9 @@
+use crate::ability;
+use crate::obstacle;
+use crate::physics::Walls;
 use crate::ship::{self, ShipClass, ShipHandle};
 use crate::simulation::{Line, Simulation};
-use crate::{model, rng, simulation};
+use crate::{model, rng};
 use nalgebra::{vector, ComplexField, Point2, Rotation2, Vector2};
 use oort_api::{Ability, EcmMode};
 use rand::Rng;
@@ -33,6 +36,8 @@ pub struct Radar {
     pub min_rssi: f64,
     pub ecm_mode: EcmMode,
     pub result: Option<ScanResult>,
+    /// Enemy radars illuminating this ship, strongest first.
+    pub warnings: Vec<RadarWarning>,
 }
 
 impl Default for Radar {
@@ -50,6 +55,7 @@ impl Default for Radar {
             min_rssi: from_dbm(-100.0),
             ecm_mode: EcmMode::None,
             result: None,
+            warnings: Vec::new(),
         }
     }
 }
@@ -75,16 +81,16 @@ impl Radar {
         self.min_distance
     }
 
-    pub fn set_min_distance(&mut self, dist: f64) {
-        self.min_distance = dist.clamp(0.0, simulation::MAX_WORLD_SIZE * 2.0);
+    pub fn set_min_distance(&mut self, dist: f64, world_size: f64) {
+        self.min_distance = dist.clamp(0.0, world_size * 2.0);
     }
 
     pub fn get_max_distance(&self) -> f64 {
         self.max_distance
     }
 
-    pub fn set_max_distance(&mut self, dist: f64) {
-        self.max_distance = dist.clamp(0.0, simulation::MAX_WORLD_SIZE * 2.0);
+    pub fn set_max_distance(&mut self, dist: f64, world_size: f64) {
+        self.max_distance = dist.clamp(0.0, world_size * 2.0);
     }
 
     pub fn set_ecm_mode(&mut self, mode: EcmMode) {
@@ -94,6 +100,10 @@ impl Radar {
     pub fn scan(&self) -> Option<ScanResult> {
         self.result
     }
+
+    pub fn warnings(&self) -> &[RadarWarning] {
+        &self.warnings
+    }
 }
 
 struct RadarEmitter {
@@ -117,6 +127,7 @@ struct RadarEmitter {
 
 #[derive(Clone)]
 struct RadarReflector {
+    handle: ShipHandle,
     position: Point2<f64>,
     velocity: Vector2<f64>,
     heading: f64,
@@ -143,6 +154,15 @@ pub struct ScanResult {
     pub snr: f64,
 }
 
+/// A radar beam detected by the passive radar warning receiver.
+#[derive(Copy, Clone, Debug)]
+pub struct RadarWarning {
+    /// Direction from the receiver to the emitter.
+    pub bearing: f64,
+    /// Received power in dBm.
+    pub rssi: f64,
+}
+
 #[derive(Clone, Default)]
 struct Reflectors {
     groups: BTreeMap<ReflectorGroupKey, ReflectorGroup>,
@@ -152,6 +172,8 @@ struct Reflectors {
 struct ReflectorGroupKey {
     team: i32,
     radius: i32,
+    /// Blocks radar from reaching reflectors behind it.
+    occluder: bool,
 }
 
 #[derive(Clone, Default)]
@@ -170,7 +192,7 @@ fn from_dbm(x: f64) -> f64 {
 }
 
 #[inline(never)]
-fn build_reflectors(sim: &Simulation) -> Reflectors {
+fn build_reflectors(sim: &Simulation, wrap_size: Option<f64>) -> Reflectors {
     let mut reflector_groups: HashMap<ReflectorGroupKey, Vec<RadarReflector>> = HashMap::new();
 
     for handle in sim.ships.iter() {
@@ -183,6 +205,9 @@ fn build_reflectors(sim: &Simulation) -> Reflectors {
             class = ShipClass::Cruiser;
             radar_cross_section = ship::CRUISER_RADAR_CROSS_SECTION / 2.0;
         }
+        if ship.is_ability_active(Ability::Cloak) {
+            radar_cross_section *= ability::CLOAK_RADAR_CROSS_SECTION_FACTOR;
+        }
         if class == ShipClass::Planet {
             continue;
         }
@@ -201,19 +226,36 @@ fn build_reflectors(sim: &Simulation) -> Reflectors {
         let group_key = ReflectorGroupKey {
             team: ship_data.team,
             radius: ship_data.radar_radius,
+            occluder: is_occluder(ship_data.class),
         };
-        reflector_groups
-            .entry(group_key)
-            .or_default()
-            .push(RadarReflector {
-                position: ship.position().vector.into(),
-                velocity: ship.velocity(),
-                heading: ship.heading(),
-                radar_cross_section,
-                radius: ship_data.radar_radius as f64,
-                class,
-                jammer,
-            });
+        let reflector = RadarReflector {
+            handle: *handle,
+            position: ship.position().vector.into(),
+            velocity: ship.velocity(),
+            heading: ship.heading(),
+            radar_cross_section,
+            radius: ship_data.radar_radius as f64,
+            class,
+            jammer,
+        };
+        let group = reflector_groups.entry(group_key).or_default();
+        if let Some(world_size) = wrap_size {
+            // Add images of the reflector in the neighboring copies of the
+            // world. Emitters only see the closest image.
+            for dx in [-world_size, 0.0, world_size] {
+                for dy in [-world_size, 0.0, world_size] {
+                    let position = reflector.position + vector![dx, dy];
+                    if position.coords.amax() <= world_size {
+                        group.push(RadarReflector {
+                            position,
+                            ..reflector.clone()
+                        });
+                    }
+                }
+            }
+        } else {
+            group.push(reflector);
+        }
     }
 
     let mut result: Reflectors = Default::default();
@@ -253,8 +295,11 @@ fn build_reflectors(sim: &Simulation) -> Reflectors {
 #[inline(never)]
 pub fn tick(sim: &mut Simulation) {
     let handle_snapshot: Vec<ShipHandle> = sim.ships.iter().cloned().collect();
-    let reflectors = build_reflectors(sim);
+    let world_size = sim.world_size();
+    let wrap_size = (sim.physics().walls == Walls::Wrap).then_some(world_size);
+    let reflectors = build_reflectors(sim, wrap_size);
     let mut candidates: Vec<&RadarReflector> = Vec::new();
+    let mut occluders: Vec<&RadarReflector> = Vec::new();
     let planets = sim
         .ships
         .iter()
@@ -262,8 +307,13 @@ pub fn tick(sim: &mut Simulation) {
         .cloned()
         .collect::<Vec<_>>();
     let mut reflector_shapes = HashMap::new();
+    let mut warnings: Vec<(ShipHandle, RadarWarning)> = Vec::new();
 
     for handle in handle_snapshot.iter().cloned() {
+        if !draw_energy(sim, handle) {
+            continue;
+        }
+
         let ship = sim.ship(handle);
         let ship_data = ship.data();
 
@@ -273,11 +323,11 @@ pub fn tick(sim: &mut Simulation) {
             assert!(w < TAU / 2.0);
             let max_distance = compute_max_detection_range(radar, 40.0 /*cruiser*/)
                 .min(radar.max_distance)
-                .min(simulation::MAX_WORLD_SIZE);
+                .min(world_size * 2.0);
             let min_distance = radar.min_distance.min(max_distance);
             let reliable_distance = compute_reliable_detection_range(radar, 10.0 /*fighter*/)
                 .min(radar.max_distance)
-                .min(simulation::MAX_WORLD_SIZE);
+                .min(world_size * 2.0);
 
             let start_bearing = h - 0.5 * w;
             let end_bearing = h + 0.5 * w;
@@ -331,13 +381,44 @@ pub fn tick(sim: &mut Simulation) {
                 emitter.square_distance_range.end = ComplexField::powi(planet_distance, 2);
             }
 
-            find_candidates(&emitter, &reflectors, &mut candidates);
+            find_candidates(
+                &emitter,
+                &reflectors,
+                |key| key.team != emitter.team,
+                &mut candidates,
+            );
+            occluders.clear();
+            find_candidates(&emitter, &reflectors, |key| key.occluder, &mut occluders);
 
             let v = Rotation2::new(emitter.width / 2.0).transform_point(&point![1e6, 0.0]);
             let emitter_shape = parry::shape::Triangle::new(point![0.0, 0.0], v, point![v.x, -v.y]);
             let emitter_isometry = Isometry::new(emitter.center.coords, emitter.bearing);
 
             for reflector in candidates.iter() {
+                if let Some(world_size) = wrap_size {
+                    let d = reflector.position - emitter.center;
+                    if d.amax() > world_size / 2.0 {
+                        continue;
+                    }
+                }
+
+                if obstacle::is_occluded(sim, emitter.center, reflector.position)
+                    || is_shadowed(&emitter, reflector, &occluders)
+                {
+                    continue;
+                }
+
+                // The RSSI is filled in with the power density and converted
+                // once the receiver is known.
+                let dp = emitter.center - reflector.position;
+                warnings.push((
+                    reflector.handle,
+                    RadarWarning {
+                        bearing: dp.y.atan2(dp.x).rem_euclid(TAU),
+                        rssi: compute_power_density(&emitter, reflector),
+                    },
+                ));
+
                 if let Some(jammer) = reflector.jammer.as_ref() {
                     match jammer.ecm_mode {
                         EcmMode::None => {}
@@ -464,12 +545,97 @@ pub fn tick(sim: &mut Simulation) {
             }
         }
     }
+
+    deliver_warnings(sim, &handle_snapshot, warnings);
+}
+
+/// Pays for a tick of radar operation. If the ship can't afford it the radar
+/// is off and loses its contact.
+fn draw_energy(sim: &mut Simulation, handle: ShipHandle) -> bool {
+    let tick_length = sim.physics().tick_length;
+    let mut ship = sim.ship_mut(handle);
+    let data = ship.data_mut();
+    let (Some(radar), Some(energy)) = (data.radar.as_mut(), data.energy.as_mut()) else {
+        return true;
+    };
+    if energy.draw(energy.radar_cost * tick_length) {
+        true
+    } else {
+        radar.result = None;
+        false
+    }
+}
+
+/// Passes warnings to the receivers, which are the radars of the illuminated
+/// ships. Ships without a radar can't sense them.
+fn deliver_warnings(
+    sim: &mut Simulation,
+    handles: &[ShipHandle],
+    warnings: Vec<(ShipHandle, RadarWarning)>,
+) {
+    for &handle in handles {
+        if let Some(radar) = sim.ship_mut(handle).data_mut().radar.as_mut() {
+            radar.warnings.clear();
+        }
+    }
+
+    for (handle, mut warning) in warnings {
+        if let Some(radar) = sim.ship_mut(handle).data_mut().radar.as_mut() {
+            let rssi = warning.rssi * radar.rx_cross_section;
+            if rssi >= radar.min_rssi {
+                warning.rssi = into_dbm(rssi);
+                radar.warnings.push(warning);
+            }
+        }
+    }
+
+    for &handle in handles {
+        if let Some(radar) = sim.ship_mut(handle).data_mut().radar.as_mut() {
+            radar.warnings.sort_by(|a, b| b.rssi.total_cmp(&a.rssi));
+        }
+    }
+}
+
+fn is_occluder(class: ShipClass) -> bool {
+    matches!(
+        class,
+        ShipClass::Asteroid { .. }
+            | ShipClass::BigAsteroid { .. }
+            | ShipClass::Frigate
+            | ShipClass::Cruiser
+    )
+}
+
+/// Returns true if an occluder is between the emitter and the reflector.
+///
+/// Occluders are approximated as circles of their radar radius.
+fn is_shadowed(
+    emitter: &RadarEmitter,
+    reflector: &RadarReflector,
+    occluders: &[&RadarReflector],
+) -> bool {
+    let d = reflector.position - emitter.center;
+    let distance_sq = d.norm_squared();
+    occluders.iter().any(|occluder| {
+        if occluder.handle == reflector.handle || occluder.handle == emitter.handle {
+            return false;
+        }
+        let v = occluder.position - emitter.center;
+        let t = v.dot(&d) / distance_sq;
+        if t <= 0.0 || v.norm_squared() >= distance_sq {
+            return false;
+        }
+        (v - d * t).norm_squared() < occluder.radius * occluder.radius
+    })
 }
 
+/// Finds reflectors inside the emitter's beam from the groups accepted by
+/// `filter`.
 #[inline(never)]
 fn find_candidates<'a>(
     emitter: &RadarEmitter,
     reflectors: &'a Reflectors,
+    filter: impl Fn(&ReflectorGroupKey) -> bool,
     candidates: &mut Vec<&'a RadarReflector>,
 ) {
     let rays = [emitter.rays[0].cast::<f32>(), emitter.rays[1].cast::<f32>()];
@@ -481,7 +647,7 @@ fn find_candidates<'a>(
     let wry1 = f32x4::splat(rays[1].y);
 
     for (group_key, group) in reflectors.groups.iter() {
-        if emitter.team == group_key.team || group.reflectors.is_empty() {
+        if !filter(group_key) || group.reflectors.is_empty() {
             continue;
         }
 
@@ -623,6 +789,12 @@ fn compute_rssi(emitter: &RadarEmitter, reflector: &RadarReflector) -> f64 {
         / (TAU * emitter.width * r_sq * r_sq)
 }
 
+/// Power per square meter of the radar beam arriving at the reflector.
+fn compute_power_density(emitter: &RadarEmitter, reflector: &RadarReflector) -> f64 {
+    let r_sq = nalgebra::distance_squared(&emitter.center, &reflector.position);
+    emitter.power / (TAU * emitter.width * r_sq)
+}
+
 fn compute_max_detection_range(radar: &Radar, target_cross_section: f64) -> f64 {
     ComplexField::powf(
         radar.power * target_cross_section * radar.rx_cross_section
@@ -926,6 +1098,96 @@ mod test {
         assert!(sim.ship(ship0).radar().unwrap().result.is_some());
     }
 
+    #[test]
+    fn test_occlusion() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let asteroid = ship::create(
+            &mut sim,
+            vector![2000.0, 30.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::asteroid(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![4000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::target(1),
+        );
+
+        // Ignore the asteroid itself.
+        sim.ship_mut(ship0).radar_mut().unwrap().width = TAU / 360.0;
+        sim.ship_mut(ship0).radar_mut().unwrap().min_distance = 3000.0;
+        sim.step();
+        assert!(sim.ship(ship0).radar().unwrap().result.is_none());
+
+        // Move the asteroid out of the line of sight.
+        sim.ship_mut(asteroid)
+            .body()
+            .set_translation(vector![2000.0, 200.0], true);
+        sim.step();
+        assert!(sim.ship(ship0).radar().unwrap().result.is_some());
+    }
+
+    #[test]
+    fn test_radar_warning() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let ship1 = ship::create(
+            &mut sim,
+            vector![0.0, 50e3],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+        let ship2 = ship::create(
+            &mut sim,
+            vector![-10e3, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let warnings =
+            |sim: &Simulation, handle| sim.ship(handle).radar().unwrap().warnings.clone();
+
+        // Enemy radar pointed at ship0 and ship2.
+        sim.ship_mut(ship1).radar_mut().unwrap().heading = -TAU / 4.0;
+        sim.ship_mut(ship1).radar_mut().unwrap().width = TAU / 4.0 - EPSILON;
+        sim.ship_mut(ship0).radar_mut().unwrap().heading = 0.0;
+        sim.ship_mut(ship2).radar_mut().unwrap().heading = 0.0;
+        sim.step();
+        assert_eq!(warnings(&sim, ship0).len(), 1);
+        assert!((warnings(&sim, ship0)[0].bearing - TAU / 4.0).abs() < EPSILON);
+        assert_eq!(warnings(&sim, ship2).len(), 1);
+        assert!(warnings(&sim, ship0)[0].rssi > warnings(&sim, ship2)[0].rssi);
+
+        // Friendly radar pointed at ship0, which sees ship1 but isn't seen.
+        sim.ship_mut(ship2).radar_mut().unwrap().heading = 0.0;
+        sim.ship_mut(ship0).radar_mut().unwrap().heading = TAU / 4.0;
+        sim.ship_mut(ship1).radar_mut().unwrap().heading = TAU / 4.0;
+        sim.step();
+        assert!(warnings(&sim, ship0).is_empty());
+        assert!(warnings(&sim, ship2).is_empty());
+        assert_eq!(warnings(&sim, ship1).len(), 1);
+        assert!((warnings(&sim, ship1)[0].bearing - 3.0 * TAU / 4.0).abs() < EPSILON);
+    }
+
     #[test]
     fn test_detection_range() {
         let class_to_ship_data = |class, team| match class {
@@ -1,3 +1,5 @@
+use crate::obstacle;
+use crate::physics::{self, Walls};
 use crate::ship::ShipHandle;
 use crate::simulation::Simulation;
 use nalgebra::Point2;
@@ -33,6 +35,10 @@ impl Radio {
     pub fn get_received(&self) -> Option<Message> {
         self.received
     }
+
+    pub fn get_sent(&self) -> Option<Message> {
+        self.sent
+    }
 }
 
 struct RadioSender {
@@ -53,6 +59,7 @@ struct RadioReceiver {
 pub fn tick(sim: &mut Simulation) {
     let handle_snapshot: Vec<ShipHandle> = sim.ships.iter().cloned().collect();
 
+    let wrap_size = (sim.physics().walls == Walls::Wrap).then(|| sim.world_size());
     let mut receivers: BTreeMap<usize, Vec<RadioReceiver>> = BTreeMap::new();
     let mut senders: BTreeMap<usize, Vec<RadioSender>> = BTreeMap::new();
 
@@ -86,7 +93,10 @@ pub fn tick(sim: &mut Simulation) {
             let mut best_msg = None;
             let mut best_rssi = rx.min_rssi;
             for tx in senders.get(&channel).unwrap_or(&Vec::new()) {
-                let rssi = compute_rssi(tx, rx);
+                if obstacle::is_occluded(sim, tx.position, rx.position) {
+                    continue;
+                }
+                let rssi = compute_rssi(tx, rx, wrap_size);
                 if rssi > best_rssi {
                     best_rssi = rssi;
                     best_msg = Some(tx.msg);
@@ -107,8 +117,12 @@ pub fn tick(sim: &mut Simulation) {
     }
 }
 
-fn compute_rssi(sender: &RadioSender, receiver: &RadioReceiver) -> f64 {
-    let r_sq = nalgebra::distance_squared(&sender.position, &receiver.position);
+fn compute_rssi(sender: &RadioSender, receiver: &RadioReceiver, wrap_size: Option<f64>) -> f64 {
+    let mut d = receiver.position - sender.position;
+    if let Some(world_size) = wrap_size {
+        d = physics::wrap_delta(d, world_size);
+    }
+    let r_sq = d.norm_squared();
     sender.power * receiver.rx_cross_section / (TAU * r_sq)
 }
 
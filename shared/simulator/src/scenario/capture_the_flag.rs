@@ -0,0 +1,88 @@
+use super::prelude::*;
+use nalgebra::UnitComplex;
+
+/// Each team scores by holding the zone at the other team's starting position.
+pub struct CaptureTheFlag {
+    zone_control: ZoneControl,
+}
+
+impl CaptureTheFlag {
+    const TARGET_SCORE: f64 = 30.0;
+
+    pub fn new() -> Self {
+        Self {
+            zone_control: ZoneControl::new(&[0, 1], Self::TARGET_SCORE, TOURNAMENT_MAX_TICKS),
+        }
+    }
+}
+
+impl Scenario for CaptureTheFlag {
+    fn name(&self) -> String {
+        "capture_the_flag".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Capture the Flag".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let mut rng = new_rng(seed);
+        let placements = place_teams(&mut rng, self.world_size());
+        let offsets = [
+            vector![0.0, 0.0],
+            vector![-100.0, 100.0],
+            vector![-100.0, -100.0],
+        ];
+
+        for (team, placement) in placements.into_iter().enumerate() {
+            let Placement { position, heading } = placement;
+            let team = team as i32;
+            for offset in &offsets {
+                ship::create(
+                    sim,
+                    position + UnitComplex::new(heading).transform_vector(offset),
+                    vector![0.0, 0.0],
+                    heading,
+                    fighter(team),
+                );
+            }
+            // The flag sits in front of the team's starting position. Team 0's
+            // flag broadcasts on channel 9 and team 1's on channel 8.
+            let flag = position + UnitComplex::new(heading).transform_vector(&vector![2000.0, 0.0]);
+            self.zone_control
+                .add_zone(sim, flag.into(), 1000.0, Some(1 - team), 9 - team as usize);
+        }
+    }
+
+    fn tick(&mut self, sim: &mut Simulation) {
+        self.zone_control.tick(sim);
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        self.zone_control.status(sim)
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        self.zone_control.lines()
+    }
+
+    fn objectives(&self) -> Vec<ObjectiveStatus> {
+        self.zone_control.snapshot()
+    }
+
+    fn scores(&self) -> BTreeMap<i32, f64> {
+        self.zone_control.scores()
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), reference_ai()]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+
+    fn is_tournament(&self) -> bool {
+        true
+    }
+}
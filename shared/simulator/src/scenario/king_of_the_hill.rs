@@ -0,0 +1,84 @@
+use super::prelude::*;
+use nalgebra::UnitComplex;
+
+pub struct KingOfTheHill {
+    zone_control: ZoneControl,
+}
+
+impl KingOfTheHill {
+    const TARGET_SCORE: f64 = 60.0;
+
+    pub fn new() -> Self {
+        Self {
+            zone_control: ZoneControl::new(&[0, 1], Self::TARGET_SCORE, TOURNAMENT_MAX_TICKS),
+        }
+    }
+}
+
+impl Scenario for KingOfTheHill {
+    fn name(&self) -> String {
+        "king_of_the_hill".into()
+    }
+
+    fn human_name(&self) -> String {
+        "King of the Hill".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let mut rng = new_rng(seed);
+        let placements = place_teams(&mut rng, self.world_size());
+        let offsets = [
+            vector![0.0, 0.0],
+            vector![-100.0, 100.0],
+            vector![-100.0, -100.0],
+        ];
+
+        for (team, placement) in placements.into_iter().enumerate() {
+            let Placement { position, heading } = placement;
+            for offset in &offsets {
+                ship::create(
+                    sim,
+                    position + UnitComplex::new(heading).transform_vector(offset),
+                    vector![0.0, 0.0],
+                    heading,
+                    fighter(team as i32),
+                );
+            }
+        }
+
+        self.zone_control
+            .add_zone(sim, point![0.0, 0.0], 1000.0, None, 9);
+    }
+
+    fn tick(&mut self, sim: &mut Simulation) {
+        self.zone_control.tick(sim);
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        self.zone_control.status(sim)
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        self.zone_control.lines()
+    }
+
+    fn objectives(&self) -> Vec<ObjectiveStatus> {
+        self.zone_control.snapshot()
+    }
+
+    fn scores(&self) -> BTreeMap<i32, f64> {
+        self.zone_control.scores()
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), reference_ai()]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+
+    fn is_tournament(&self) -> bool {
+        true
+    }
+}
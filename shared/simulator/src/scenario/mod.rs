@@ -1,12 +1,15 @@
 mod asteroid_duel;
 mod belt;
+mod capture_the_flag;
 mod cruiser_defense;
 mod cruiser_duel;
 mod fighter_duel;
 mod fleet;
 mod frigate_duel;
 mod gunnery;
+mod king_of_the_hill;
 mod mini_fleet;
+pub mod objectives;
 mod orbit;
 mod planetary_defense;
 mod primitive_duel;
@@ -31,14 +34,19 @@ mod tutorial_search;
 mod tutorial_squadron;
 mod welcome;
 
-use crate::ship::{asteroid, fighter, ShipAccessor, ShipClass, ShipData};
+use crate::physics::PhysicsConfig;
+use crate::ship::{asteroid, fighter, ShipAbility, ShipAccessor, ShipClass, ShipData};
 use crate::simulation::{Code, Line, Simulation};
 use nalgebra::{vector, Vector2};
+use objectives::ObjectiveStatus;
 use rand::{seq::SliceRandom, Rng, RngCore};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 pub mod prelude {
+    pub use super::objectives::{
+        CaptureZone, Destroy, Escort, ObjectiveStatus, Objectives, Survive, ZoneControl,
+    };
     pub use super::Scenario;
     pub use super::Status;
     pub use super::{builtin, empty_ai, reference_ai};
@@ -48,14 +56,17 @@ pub mod prelude {
     pub use super::{fighter_without_missiles, fighter_without_missiles_or_radar, target_asteroid};
     pub use super::{place_teams, Placement};
     pub use super::{DEFAULT_TUTORIAL_MAX_TICKS, TOURNAMENT_MAX_TICKS};
+    pub use crate::obstacle;
+    pub use crate::physics::{PhysicsConfig, Walls};
     pub use crate::rng::{new_rng, SeededRng};
     pub use crate::ship::{
-        self, asteroid, beacon, big_asteroid, cruiser, fighter, frigate, missile, target, torpedo,
-        ShipHandle,
+        self, asteroid, beacon, big_asteroid, cloak, cruiser, fighter, frigate, missile,
+        point_defense, target, torpedo, tractor_beam, Energy, ShipAbility, ShipHandle,
     };
     pub use crate::simulation::{Code, Line, Simulation};
     pub use nalgebra::{point, vector, Point2, Rotation2, Vector2};
     pub use rand::Rng;
+    pub use std::collections::BTreeMap;
     pub use std::f64::consts::{PI, TAU};
 }
 
@@ -71,7 +82,7 @@ pub enum Status {
     Draw,
 }
 
-pub trait Scenario {
+pub trait Scenario: Send {
     fn name(&self) -> String;
 
     fn human_name(&self) -> String {
@@ -109,6 +120,15 @@ pub trait Scenario {
         vec![]
     }
 
+    fn objectives(&self) -> Vec<ObjectiveStatus> {
+        vec![]
+    }
+
+    /// Points for each team in scenarios scored by objectives.
+    fn scores(&self) -> BTreeMap<i32, f64> {
+        BTreeMap::new()
+    }
+
     fn is_tournament(&self) -> bool {
         false
     }
@@ -124,6 +144,18 @@ pub trait Scenario {
     fn world_size(&self) -> f64 {
         40000.0
     }
+
+    /// Physical parameters, e.g. to study control algorithms under different
+    /// tick lengths.
+    fn physics(&self) -> PhysicsConfig {
+        PhysicsConfig::default()
+    }
+
+    /// Abilities given to every ship of a class, in addition to the ones the
+    /// class normally has.
+    fn abilities(&self) -> BTreeMap<ShipClass, Vec<ShipAbility>> {
+        BTreeMap::new()
+    }
 }
 
 pub fn load_safe(name: &str) -> Option<Box<dyn Scenario>> {
@@ -158,6 +190,8 @@ pub fn load_safe(name: &str) -> Option<Box<dyn Scenario>> {
         "fleet" => Some(Box::new(fleet::Fleet::new())),
         "belt" => Some(Box::new(belt::Belt::new())),
         "orbit" => Some(Box::new(orbit::Orbit::new())),
+        "king_of_the_hill" => Some(Box::new(king_of_the_hill::KingOfTheHill::new())),
+        "capture_the_flag" => Some(Box::new(capture_the_flag::CaptureTheFlag::new())),
         // Challenge
         "gunnery" => Some(Box::new(gunnery::GunneryScenario {})),
         "cruiser_defense" => Some(Box::new(cruiser_defense::CruiserDefense::new())),
@@ -165,6 +199,9 @@ pub fn load_safe(name: &str) -> Option<Box<dyn Scenario>> {
         "race" => Some(Box::new(race::Race::new())),
         // Testing
         "test" => Some(Box::new(test::TestScenario {})),
+        "physics_test" => Some(Box::new(test::PhysicsTest {})),
+        "wrap_test" => Some(Box::new(test::WrapTest {})),
+        "ability_test" => Some(Box::new(test::AbilityTest {})),
         "basic" => Some(Box::new(test::BasicScenario {})),
         "missile_test" => Some(Box::new(test::MissileTest::new())),
         "frigate_vs_cruiser" => Some(Box::new(test::FrigateVsCruiser::new())),
@@ -230,6 +267,8 @@ pub fn list() -> Vec<(String, Vec<String>)> {
                 "fleet",
                 "belt",
                 "orbit",
+                "king_of_the_hill",
+                "capture_the_flag",
             ],
         ),
     ]
@@ -0,0 +1,606 @@
+//! Reusable victory conditions for scenarios.
+//!
+//! A scenario builds an [Objectives] in `init` and forwards `tick`, `status`,
+//! `lines` and `objectives` to it:
+//!
+//! ```ignore
+//! self.objectives = Objectives::new()
+//!     .with(CaptureZone::new(0, point![0.0, 0.0], 500.0, 30.0))
+//!     .with(Survive::new(1, 60.0));
+//! ```
+use super::{check_victory_with_filter, Status};
+use crate::ship::{self, ShipClass, ShipHandle};
+use crate::simulation::{Line, Simulation};
+use nalgebra::{vector, Point2, Vector4};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::f64::consts::TAU;
+
+/// Ships that can capture zones and count as survivors.
+const COMBATANT_CLASSES: [ShipClass; 3] =
+    [ShipClass::Fighter, ShipClass::Frigate, ShipClass::Cruiser];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObjectiveState {
+    InProgress,
+    Complete,
+    Failed,
+}
+
+/// Progress of an objective, included in the snapshot.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ObjectiveStatus {
+    pub team: i32,
+    pub description: String,
+    /// Between 0 and 1.
+    pub progress: f64,
+    pub state: ObjectiveState,
+}
+
+pub trait Objective: Send {
+    /// The team that must complete the objective.
+    fn team(&self) -> i32;
+
+    fn description(&self) -> String;
+
+    /// Called at the end of each tick. The objective must not change state
+    /// once it is complete or failed.
+    fn tick(&mut self, sim: &Simulation);
+
+    fn progress(&self) -> f64;
+
+    fn state(&self) -> ObjectiveState;
+
+    fn lines(&self) -> Vec<Line> {
+        vec![]
+    }
+}
+
+/// A set of objectives. A team wins when all of its objectives are complete.
+#[derive(Default)]
+pub struct Objectives {
+    objectives: Vec<Box<dyn Objective>>,
+}
+
+impl Objectives {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with(mut self, objective: impl Objective + 'static) -> Self {
+        self.objectives.push(Box::new(objective));
+        self
+    }
+
+    pub fn tick(&mut self, sim: &Simulation) {
+        for objective in self.objectives.iter_mut() {
+            if objective.state() == ObjectiveState::InProgress {
+                objective.tick(sim);
+            }
+        }
+    }
+
+    /// Returns victory for the first team to complete all of its objectives,
+    /// or failure once every team has failed an objective.
+    pub fn status(&self) -> Status {
+        let mut teams: Vec<i32> = self.objectives.iter().map(|x| x.team()).collect();
+        teams.sort();
+        teams.dedup();
+        if teams.is_empty() {
+            return Status::Running;
+        }
+        let team_objectives = |team| self.objectives.iter().filter(move |x| x.team() == team);
+        for &team in teams.iter() {
+            if team_objectives(team).all(|x| x.state() == ObjectiveState::Complete) {
+                return Status::Victory { team };
+            }
+        }
+        if teams
+            .iter()
+            .all(|&team| team_objectives(team).any(|x| x.state() == ObjectiveState::Failed))
+        {
+            return Status::Failed;
+        }
+        Status::Running
+    }
+
+    pub fn lines(&self) -> Vec<Line> {
+        self.objectives.iter().flat_map(|x| x.lines()).collect()
+    }
+
+    pub fn snapshot(&self) -> Vec<ObjectiveStatus> {
+        self.objectives
+            .iter()
+            .map(|x| ObjectiveStatus {
+                team: x.team(),
+                description: x.description(),
+                progress: x.progress(),
+                state: x.state(),
+            })
+            .collect()
+    }
+}
+
+/// Hold a zone for a total time. The zone is held when only the team's ships
+/// are inside it, and time held by another team counts against it.
+pub struct CaptureZone {
+    team: i32,
+    center: Point2<f64>,
+    radius: f64,
+    hold_time: f64,
+    held_time: f64,
+    state: ObjectiveState,
+}
+
+impl CaptureZone {
+    pub fn new(team: i32, center: Point2<f64>, radius: f64, hold_time: f64) -> Self {
+        Self {
+            team,
+            center,
+            radius,
+            hold_time,
+            held_time: 0.0,
+            state: ObjectiveState::InProgress,
+        }
+    }
+}
+
+impl Objective for CaptureZone {
+    fn team(&self) -> i32 {
+        self.team
+    }
+
+    fn description(&self) -> String {
+        format!("Hold the zone for {:.0}s", self.hold_time)
+    }
+
+    fn tick(&mut self, sim: &Simulation) {
+        if let Some(team) = zone_owner(sim, self.center, self.radius) {
+            if team == self.team {
+                self.held_time += sim.physics().tick_length;
+            } else {
+                self.held_time = (self.held_time - sim.physics().tick_length).max(0.0);
+            }
+        }
+        if self.held_time >= self.hold_time {
+            self.state = ObjectiveState::Complete;
+        }
+    }
+
+    fn progress(&self) -> f64 {
+        (self.held_time / self.hold_time).min(1.0)
+    }
+
+    fn state(&self) -> ObjectiveState {
+        self.state
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        progress_circle(self.center, self.radius, self.progress(), self.state)
+    }
+}
+
+/// Returns the team with the only combatant ships in the zone.
+pub fn zone_owner(sim: &Simulation, center: Point2<f64>, radius: f64) -> Option<i32> {
+    let mut owner = None;
+    for &handle in sim.ships.iter() {
+        let ship = sim.ship(handle);
+        if !COMBATANT_CLASSES.contains(&ship.data().class)
+            || (ship.position().vector - center.coords).magnitude() > radius
+        {
+            continue;
+        }
+        match owner {
+            None => owner = Some(ship.data().team),
+            Some(team) if team != ship.data().team => return None,
+            _ => {}
+        }
+    }
+    owner
+}
+
+/// Bring a ship within a radius of a destination. Fails if the ship is
+/// destroyed.
+pub struct Escort {
+    team: i32,
+    vip: ShipHandle,
+    destination: Point2<f64>,
+    radius: f64,
+    initial_distance: Option<f64>,
+    vip_position: Option<Point2<f64>>,
+    state: ObjectiveState,
+}
+
+impl Escort {
+    pub fn new(team: i32, vip: ShipHandle, destination: Point2<f64>, radius: f64) -> Self {
+        Self {
+            team,
+            vip,
+            destination,
+            radius,
+            initial_distance: None,
+            vip_position: None,
+            state: ObjectiveState::InProgress,
+        }
+    }
+}
+
+impl Objective for Escort {
+    fn team(&self) -> i32 {
+        self.team
+    }
+
+    fn description(&self) -> String {
+        "Escort the VIP to the destination".to_string()
+    }
+
+    fn tick(&mut self, sim: &Simulation) {
+        if !sim.ships.contains(self.vip) {
+            self.state = ObjectiveState::Failed;
+            return;
+        }
+        let position: Point2<f64> = sim.ship(self.vip).position().vector.into();
+        let distance = (position - self.destination).magnitude();
+        self.initial_distance = Some(self.initial_distance.unwrap_or(distance));
+        self.vip_position = Some(position);
+        if distance <= self.radius {
+            self.state = ObjectiveState::Complete;
+        }
+    }
+
+    fn progress(&self) -> f64 {
+        match (self.initial_distance, self.vip_position, self.state) {
+            (_, _, ObjectiveState::Complete) => 1.0,
+            (Some(initial), Some(position), _) if initial > self.radius => {
+                let distance = (position - self.destination).magnitude();
+                ((initial - distance) / (initial - self.radius)).clamp(0.0, 1.0)
+            }
+            _ => 0.0,
+        }
+    }
+
+    fn state(&self) -> ObjectiveState {
+        self.state
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        let mut lines = progress_circle(self.destination, self.radius, self.progress(), self.state);
+        if let (Some(position), ObjectiveState::InProgress) = (self.vip_position, self.state) {
+            lines.push(Line {
+                a: position,
+                b: self.destination,
+                color: vector![1.0, 1.0, 1.0, 0.2],
+            });
+        }
+        lines
+    }
+}
+
+/// Keep at least one combatant ship alive until the given time.
+pub struct Survive {
+    team: i32,
+    duration: f64,
+    time: f64,
+    state: ObjectiveState,
+}
+
+impl Survive {
+    pub fn new(team: i32, duration: f64) -> Self {
+        Self {
+            team,
+            duration,
+            time: 0.0,
+            state: ObjectiveState::InProgress,
+        }
+    }
+}
+
+impl Objective for Survive {
+    fn team(&self) -> i32 {
+        self.team
+    }
+
+    fn description(&self) -> String {
+        format!("Survive for {:.0}s", self.duration)
+    }
+
+    fn tick(&mut self, sim: &Simulation) {
+        self.time = sim.time();
+        let alive = sim.ships.iter().any(|&handle| {
+            let data = sim.ship(handle).data();
+            data.team == self.team && COMBATANT_CLASSES.contains(&data.class)
+        });
+        if !alive {
+            self.state = ObjectiveState::Failed;
+        } else if self.time >= self.duration {
+            self.state = ObjectiveState::Complete;
+        }
+    }
+
+    fn progress(&self) -> f64 {
+        (self.time / self.duration).min(1.0)
+    }
+
+    fn state(&self) -> ObjectiveState {
+        self.state
+    }
+}
+
+/// Destroy a specific ship.
+pub struct Destroy {
+    team: i32,
+    target: ShipHandle,
+    initial_health: Option<f64>,
+    health: f64,
+    target_position: Option<Point2<f64>>,
+    state: ObjectiveState,
+}
+
+impl Destroy {
+    pub fn new(team: i32, target: ShipHandle) -> Self {
+        Self {
+            team,
+            target,
+            initial_health: None,
+            health: 0.0,
+            target_position: None,
+            state: ObjectiveState::InProgress,
+        }
+    }
+}
+
+impl Objective for Destroy {
+    fn team(&self) -> i32 {
+        self.team
+    }
+
+    fn description(&self) -> String {
+        "Destroy the target".to_string()
+    }
+
+    fn tick(&mut self, sim: &Simulation) {
+        if !sim.ships.contains(self.target) {
+            self.state = ObjectiveState::Complete;
+            return;
+        }
+        let ship = sim.ship(self.target);
+        self.health = ship.data().health;
+        self.initial_health = Some(self.initial_health.unwrap_or(self.health));
+        self.target_position = Some(ship.position().vector.into());
+    }
+
+    fn progress(&self) -> f64 {
+        match (self.initial_health, self.state) {
+            (_, ObjectiveState::Complete) => 1.0,
+            (Some(initial), _) if initial > 0.0 => (1.0 - self.health / initial).clamp(0.0, 1.0),
+            _ => 0.0,
+        }
+    }
+
+    fn state(&self) -> ObjectiveState {
+        self.state
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        let Some(center) = self.target_position else {
+            return vec![];
+        };
+        if self.state != ObjectiveState::InProgress {
+            return vec![];
+        }
+        let color = vector![1.0, 0.0, 0.0, 1.0];
+        let r = 100.0;
+        vec![
+            Line {
+                a: center + vector![-r, -r],
+                b: center + vector![r, r],
+                color,
+            },
+            Line {
+                a: center + vector![-r, r],
+                b: center + vector![r, -r],
+                color,
+            },
+        ]
+    }
+}
+
+/// A circle with the completed fraction drawn brighter.
+fn progress_circle(
+    center: Point2<f64>,
+    radius: f64,
+    progress: f64,
+    state: ObjectiveState,
+) -> Vec<Line> {
+    let color: Vector4<f32> = match state {
+        ObjectiveState::InProgress => vector![1.0, 1.0, 1.0, 1.0],
+        ObjectiveState::Complete => vector![0.0, 1.0, 0.0, 1.0],
+        ObjectiveState::Failed => vector![1.0, 0.0, 0.0, 1.0],
+    };
+    let dim = vector![color.x, color.y, color.z, 0.3];
+    let n = 40;
+    (0..n)
+        .map(|i| {
+            let frac = i as f64 / n as f64;
+            let angle_a = TAU * frac;
+            let angle_b = TAU * (frac + 1.0 / n as f64);
+            Line {
+                a: center + vector![radius * angle_a.cos(), radius * angle_a.sin()],
+                b: center + vector![radius * angle_b.cos(), radius * angle_b.sin()],
+                color: if frac < progress { color } else { dim },
+            }
+        })
+        .collect()
+}
+
+/// Team of the beacons marking scoring zones.
+const NEUTRAL_TEAM: i32 = 9;
+
+struct ScoringZone {
+    center: Point2<f64>,
+    radius: f64,
+    /// If set, only this team scores by holding the zone.
+    team: Option<i32>,
+    channel: usize,
+    beacon: ShipHandle,
+    owner: Option<i32>,
+}
+
+/// Teams score a point per second for each zone they hold. A team wins by
+/// reaching the target score or destroying the other teams, and otherwise the
+/// highest score wins at the time limit.
+///
+/// Each zone has a beacon at its center that broadcasts `[x, y, owner, 0]` on
+/// the zone's radio channel, where owner is -1 if no team holds the zone.
+pub struct ZoneControl {
+    teams: Vec<i32>,
+    zones: Vec<ScoringZone>,
+    scores: BTreeMap<i32, f64>,
+    target_score: f64,
+    max_ticks: u32,
+}
+
+impl ZoneControl {
+    pub fn new(teams: &[i32], target_score: f64, max_ticks: u32) -> Self {
+        Self {
+            teams: teams.to_vec(),
+            zones: vec![],
+            scores: teams.iter().map(|&team| (team, 0.0)).collect(),
+            target_score,
+            max_ticks,
+        }
+    }
+
+    /// Adds a zone and creates its beacon. Call from `Scenario::init`.
+    pub fn add_zone(
+        &mut self,
+        sim: &mut Simulation,
+        center: Point2<f64>,
+        radius: f64,
+        team: Option<i32>,
+        channel: usize,
+    ) {
+        let beacon = ship::create(
+            sim,
+            center.coords,
+            vector![0.0, 0.0],
+            0.0,
+            ship::beacon(NEUTRAL_TEAM),
+        );
+        self.zones.push(ScoringZone {
+            center,
+            radius,
+            team,
+            channel,
+            beacon,
+            owner: None,
+        });
+    }
+
+    pub fn tick(&mut self, sim: &mut Simulation) {
+        for zone in self.zones.iter_mut() {
+            zone.owner = zone_owner(sim, zone.center, zone.radius);
+            if let Some(owner) = zone.owner {
+                if zone.team.is_none() || zone.team == Some(owner) {
+                    if let Some(score) = self.scores.get_mut(&owner) {
+                        *score += sim.physics().tick_length;
+                    }
+                }
+            }
+
+            if sim.ships.contains(zone.beacon) {
+                let msg = [
+                    zone.center.x,
+                    zone.center.y,
+                    zone.owner.unwrap_or(-1) as f64,
+                    0.0,
+                ];
+                if let Some(radio) = sim.ship_mut(zone.beacon).radio_mut(0) {
+                    radio.set_channel(zone.channel);
+                    radio.set_sent(Some(msg));
+                }
+            }
+        }
+    }
+
+    pub fn status(&self, sim: &Simulation) -> Status {
+        let leader = self
+            .scores
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(&team, &score)| (team, score));
+        if let Some((team, score)) = leader {
+            if score >= self.target_score {
+                // Teams can reach the target on the same tick.
+                let reached = self.scores.values().filter(|&&x| x >= self.target_score);
+                if reached.count() > 1 {
+                    return Status::Draw;
+                }
+                return Status::Victory { team };
+            }
+        }
+
+        let teams = &self.teams;
+        match check_victory_with_filter(sim, u32::MAX, |ship| {
+            COMBATANT_CLASSES.contains(&ship.data().class)
+        }) {
+            Status::Running => {}
+            status @ Status::Victory { team } if teams.contains(&team) => return status,
+            _ => return Status::Draw,
+        }
+
+        if sim.tick() >= self.max_ticks - 1 {
+            let (team, score) = leader.unwrap_or((0, 0.0));
+            if self.scores.values().filter(|&&x| x == score).count() > 1 {
+                return Status::Draw;
+            }
+            return Status::Victory { team };
+        }
+        Status::Running
+    }
+
+    pub fn scores(&self) -> BTreeMap<i32, f64> {
+        self.scores.clone()
+    }
+
+    pub fn lines(&self) -> Vec<Line> {
+        self.zones
+            .iter()
+            .flat_map(|zone| {
+                let color = match zone.owner {
+                    Some(team) => team_color(team),
+                    None => vector![1.0, 1.0, 1.0, 0.3],
+                };
+                progress_circle(zone.center, zone.radius, 1.0, ObjectiveState::InProgress)
+                    .into_iter()
+                    .map(move |line| Line { color, ..line })
+            })
+            .collect()
+    }
+
+    pub fn snapshot(&self) -> Vec<ObjectiveStatus> {
+        self.scores
+            .iter()
+            .map(|(&team, &score)| ObjectiveStatus {
+                team,
+                description: format!("Score {:.0} points", self.target_score),
+                progress: (score / self.target_score).min(1.0),
+                state: if score >= self.target_score {
+                    ObjectiveState::Complete
+                } else {
+                    ObjectiveState::InProgress
+                },
+            })
+            .collect()
+    }
+}
+
+/// Matches the renderer's team colors.
+fn team_color(team: i32) -> Vector4<f32> {
+    match team {
+        0 => vector![0.99, 0.98, 0.00, 1.00],
+        1 => vector![0.99, 0.00, 0.98, 1.00],
+        2 => vector![0.13, 0.50, 0.73, 1.00],
+        _ => vector![1.0, 1.0, 1.0, 1.0],
+    }
+}
@@ -4,7 +4,6 @@ use rapier2d_f64::prelude::RigidBody;
 
 use super::prelude::*;
 use crate::ship::{ShipClass, ShipData};
-use crate::simulation::PHYSICS_TICK_LENGTH;
 
 const PLANET_MASS: f64 = 1.5e19;
 const G: f64 = 6.674e-11;
@@ -63,11 +62,12 @@ impl Scenario for Orbit {
     }
 
     fn tick(&mut self, sim: &mut Simulation) {
+        let tick_length = sim.physics().tick_length;
         let apply_gravity = |body: &mut RigidBody| {
             let r = body.translation().norm();
             let g = G * PLANET_MASS / (r * r);
             let acc = body.translation().normalize() * -g;
-            let impulse = acc * body.mass() * PHYSICS_TICK_LENGTH;
+            let impulse = acc * body.mass() * tick_length;
             body.apply_impulse(impulse, true);
         };
 
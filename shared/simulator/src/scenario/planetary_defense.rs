@@ -1,6 +1,5 @@
 use super::prelude::*;
 use crate::ship::{ShipClass, ShipData};
-use crate::simulation::PHYSICS_TICK_LENGTH;
 use oort_api::{Class, ClassStats};
 
 pub struct PlanetaryDefense {
@@ -88,7 +87,7 @@ impl Scenario for PlanetaryDefense {
             let bound = (sim.world_size() / 2.0) * 0.9;
             if self
                 .rng
-                .gen_bool(PHYSICS_TICK_LENGTH * (sim.time() / Self::SPAWN_DURATION) * 2.5)
+                .gen_bool(sim.physics().tick_length * (sim.time() / Self::SPAWN_DURATION) * 2.5)
             {
                 let mut ship_data = if self.rng.gen_bool(0.1) {
                     torpedo(1)
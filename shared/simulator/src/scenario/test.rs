@@ -1,5 +1,6 @@
 use super::prelude::*;
-use crate::{bullet, simulation};
+use crate::bullet;
+use crate::ship::ShipClass;
 
 pub struct TestScenario {}
 
@@ -11,7 +12,65 @@ impl Scenario for TestScenario {
     fn init(&mut self, _sim: &mut Simulation, _seed: u32) {}
 
     fn world_size(&self) -> f64 {
-        simulation::MAX_WORLD_SIZE
+        200000.0
+    }
+}
+
+/// An empty world with nonstandard physics.
+pub struct PhysicsTest {}
+
+impl Scenario for PhysicsTest {
+    fn name(&self) -> String {
+        "physics_test".into()
+    }
+
+    fn init(&mut self, _sim: &mut Simulation, _seed: u32) {}
+
+    fn physics(&self) -> PhysicsConfig {
+        PhysicsConfig {
+            tick_length: 1.0 / 30.0,
+            damage_factor: 2.0 * crate::physics::DEFAULT_DAMAGE_FACTOR,
+            restitution: BTreeMap::from([(ShipClass::Fighter, 1.0)]),
+            walls: Walls::Open,
+            ..Default::default()
+        }
+    }
+}
+
+/// An empty world where ships wrap around at the edges.
+pub struct WrapTest {}
+
+impl Scenario for WrapTest {
+    fn name(&self) -> String {
+        "wrap_test".into()
+    }
+
+    fn init(&mut self, _sim: &mut Simulation, _seed: u32) {}
+
+    fn world_size(&self) -> f64 {
+        100e3
+    }
+
+    fn physics(&self) -> PhysicsConfig {
+        PhysicsConfig {
+            walls: Walls::Wrap,
+            ..Default::default()
+        }
+    }
+}
+
+/// An empty world where frigates can use point defense.
+pub struct AbilityTest {}
+
+impl Scenario for AbilityTest {
+    fn name(&self) -> String {
+        "ability_test".into()
+    }
+
+    fn init(&mut self, _sim: &mut Simulation, _seed: u32) {}
+
+    fn abilities(&self) -> BTreeMap<ShipClass, Vec<ShipAbility>> {
+        BTreeMap::from([(ShipClass::Frigate, vec![point_defense()])])
     }
 }
 
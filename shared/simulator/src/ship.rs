@@ -5,7 +5,6 @@ use crate::model;
 use crate::radar::Radar;
 use crate::radio::Radio;
 use crate::rng;
-use crate::simulation::{self, PHYSICS_TICK_LENGTH};
 use crate::simulation::{Particle, Simulation};
 use crate::{bullet, collision};
 use bullet::BulletData;
@@ -120,6 +119,45 @@ pub struct ShipAbility {
     pub reload_time: f64,
     pub active_time_remaining: f64,
     pub reload_time_remaining: f64,
+    /// Energy drawn per second while active.
+    pub energy_cost: f64,
+}
+
+/// An energy budget shared by a ship's weapons, radar and abilities.
+///
+/// Ships without one can use these systems freely.
+#[derive(Debug, Clone)]
+pub struct Energy {
+    pub capacity: f64,
+    pub remaining: f64,
+    /// Energy regained per second.
+    pub regeneration: f64,
+    /// Energy drawn each time a gun or missile launcher fires.
+    pub weapon_cost: f64,
+    /// Energy drawn per second while the radar is scanning or jamming.
+    pub radar_cost: f64,
+}
+
+impl Energy {
+    /// Returns a full energy budget with free weapons and radar.
+    pub fn new(capacity: f64, regeneration: f64) -> Self {
+        Self {
+            capacity,
+            remaining: capacity,
+            regeneration,
+            weapon_cost: 0.0,
+            radar_cost: 0.0,
+        }
+    }
+
+    /// Removes the given amount of energy if it is available.
+    pub fn draw(&mut self, amount: f64) -> bool {
+        if self.remaining < amount {
+            return false;
+        }
+        self.remaining -= amount;
+        true
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -149,6 +187,7 @@ pub struct ShipData {
     pub crash_message: Option<String>,
     pub ttl: Option<u64>,
     pub fuel: Option<f64>,
+    pub energy: Option<Energy>,
     pub guns: Vec<Gun>,
     pub missile_launchers: Vec<MissileLauncher>,
     pub radar: Option<Radar>,
@@ -199,6 +238,7 @@ impl Default for ShipData {
             crash_message: None,
             ttl: None,
             fuel: None,
+            energy: None,
             guns: vec![],
             missile_launchers: vec![],
             radar: None,
@@ -242,6 +282,7 @@ impl Default for ShipAbility {
             reload_time: 0.0,
             active_time_remaining: 0.0,
             reload_time_remaining: 0.0,
+            energy_cost: 0.0,
         }
     }
 }
@@ -253,7 +294,7 @@ impl Default for Warhead {
             mass: 0.2,
             width: TAU,
             speed: 1e3,
-            ttl: (PHYSICS_TICK_LENGTH * 5.0) as f32,
+            ttl: 5.0 / 60.0,
         }
     }
 }
@@ -548,6 +589,33 @@ pub fn beacon(team: i32) -> ShipData {
     }
 }
 
+pub fn cloak() -> ShipAbility {
+    ShipAbility {
+        ability: Ability::Cloak,
+        active_time: 5.0,
+        reload_time: 20.0,
+        ..Default::default()
+    }
+}
+
+pub fn point_defense() -> ShipAbility {
+    ShipAbility {
+        ability: Ability::PointDefense,
+        active_time: 1.0,
+        reload_time: 4.0,
+        ..Default::default()
+    }
+}
+
+pub fn tractor_beam() -> ShipAbility {
+    ShipAbility {
+        ability: Ability::TractorBeam,
+        active_time: 5.0,
+        reload_time: 10.0,
+        ..Default::default()
+    }
+}
+
 pub fn create(
     sim: &mut Simulation,
     position: Vector2<f64>,
@@ -569,10 +637,7 @@ pub fn create(
     let handle = ShipHandle(body_handle.0);
     let team = data.team;
     let model = model::load(data.class);
-    let restitution = match data.class {
-        ShipClass::Missile => 0.0,
-        _ => 0.1,
-    };
+    let restitution = sim.physics().restitution(data.class);
     let vertices = model
         .iter()
         .map(|&v| point![v.x as f64, v.y as f64])
@@ -597,6 +662,14 @@ pub fn create(
         gun.magazine_remaining = gun.magazine_size;
     }
 
+    if let Some(abilities) = sim.granted_abilities.get(&data.class) {
+        for ability in abilities {
+            if !data.abilities.iter().any(|x| x.ability == ability.ability) {
+                data.abilities.push(ability.clone());
+            }
+        }
+    }
+
     sim.ships.insert(handle);
     sim.new_ships.push((data.team, handle));
     sim.ship_data.insert(handle.index(), data);
@@ -682,6 +755,13 @@ impl<'a> ShipAccessor<'a> {
     }
 }
 
+fn draw_weapon_energy(energy: &mut Option<Energy>) -> bool {
+    match energy.as_mut() {
+        Some(energy) => energy.draw(energy.weapon_cost),
+        None => true,
+    }
+}
+
 pub struct ShipAccessorMut<'a> {
     pub(crate) simulation: &'a mut Simulation,
     pub(crate) handle: ShipHandle,
@@ -695,6 +775,10 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         }
     }
 
+    fn tick_length(&self) -> f64 {
+        self.simulation.physics().tick_length
+    }
+
     pub fn body(&'b mut self) -> &'b mut RigidBody {
         self.simulation
             .bodies
@@ -758,10 +842,12 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         }
         let team = ship_data.team;
         let gun = {
-            let gun = &mut ship_data.guns[index as usize];
-            if gun.reload_ticks_remaining > 0 {
+            if ship_data.guns[index as usize].reload_ticks_remaining > 0
+                || !draw_weapon_energy(&mut ship_data.energy)
+            {
                 return;
             }
+            let gun = &mut ship_data.guns[index as usize];
             gun.reload_ticks_remaining = gun.reload_ticks;
             gun.magazine_remaining -= gun.burst_size;
             if gun.magazine_remaining <= 0 {
@@ -776,7 +862,7 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         let alpha = (gun.bullet_mass as f32).clamp(0.7, 1.0);
         let color = color::to_u32(vector![1.0, 1.0, 1.0, alpha]);
         let mut t = 0.0;
-        let dt = simulation::PHYSICS_TICK_LENGTH / gun.burst_size as f64;
+        let dt = self.simulation.physics().tick_length / gun.burst_size as f64;
 
         let relative_heading = (gun.heading - self.readonly().heading())
             .rem_euclid(TAU)
@@ -820,7 +906,9 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             if let Some(missile_launcher) =
                 ship_data.missile_launchers.get_mut(index as usize).as_mut()
             {
-                if missile_launcher.reload_ticks_remaining > 0 {
+                if missile_launcher.reload_ticks_remaining > 0
+                    || !draw_weapon_energy(&mut ship_data.energy)
+                {
                     return;
                 }
                 missile_launcher.reload_ticks_remaining = missile_launcher.reload_ticks;
@@ -869,7 +957,7 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         let warhead = self.data().warhead.clone();
         let team = self.data().team;
         let p =
-            self.body().position().translation.vector - self.body().linvel() * PHYSICS_TICK_LENGTH;
+            self.body().position().translation.vector - self.body().linvel() * self.tick_length();
         let mut rng = new_rng(0);
         for _ in 0..warhead.count {
             let color = vector![rng.gen_range(0.7..1.0), 0.5, 0.5, rng.gen_range(0.5..1.0)];
@@ -877,7 +965,7 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
                 * Rotation2::new(rng.gen_range((-warhead.width / 2.0)..(warhead.width / 2.0)));
             let speed = warhead.speed * 2.0 * rng.gen_range(0.0..1.0);
             let v = self.body().linvel() + rot.transform_vector(&vector![speed, 0.0]);
-            let offset = v * rng.gen_range(0.0..PHYSICS_TICK_LENGTH);
+            let offset = v * rng.gen_range(0.0..self.tick_length());
             bullet::create(
                 self.simulation,
                 p + offset,
@@ -899,16 +987,18 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
     }
 
     pub fn activate_ability(&mut self, ability: oort_api::Ability) {
-        if let Some(ship_ability) = self
-            .data_mut()
-            .abilities
-            .iter_mut()
-            .find(|x| x.ability == ability)
-        {
+        let tick_length = self.tick_length();
+        let data = self.data_mut();
+        if let Some(ship_ability) = data.abilities.iter_mut().find(|x| x.ability == ability) {
             if ship_ability.reload_time_remaining > 0.0 {
                 return;
             }
-            ship_ability.active_time_remaining = ship_ability.active_time - PHYSICS_TICK_LENGTH;
+            if let Some(energy) = data.energy.as_ref() {
+                if energy.remaining < ship_ability.energy_cost * tick_length {
+                    return;
+                }
+            }
+            ship_ability.active_time_remaining = ship_ability.active_time - tick_length;
             ship_ability.reload_time_remaining = ship_ability.reload_time;
         }
     }
@@ -951,7 +1041,7 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             if self.readonly().is_ability_active(Ability::Boost) {
                 acceleration += vector![100.0, 0.0];
             }
-            let fuel_consumption = (acceleration * PHYSICS_TICK_LENGTH).norm();
+            let fuel_consumption = (acceleration * self.tick_length()).norm();
             if let Some(fuel) = self.data_mut().fuel {
                 if fuel < fuel_consumption {
                     acceleration *= fuel / fuel_consumption;
@@ -995,18 +1085,36 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
 
         // Special abilities.
         {
-            for ship_ability in self.data_mut().abilities.iter_mut() {
+            let tick_length = self.tick_length();
+            let data = self.data_mut();
+            for ship_ability in data.abilities.iter_mut() {
+                if let Some(energy) = data.energy.as_mut() {
+                    if ship_ability.active_time_remaining > 0.0
+                        && !energy.draw(ship_ability.energy_cost * tick_length)
+                    {
+                        ship_ability.active_time_remaining = 0.0;
+                    }
+                }
                 ship_ability.active_time_remaining =
-                    (ship_ability.active_time_remaining - PHYSICS_TICK_LENGTH).max(0.0);
+                    (ship_ability.active_time_remaining - tick_length).max(0.0);
                 ship_ability.reload_time_remaining =
-                    (ship_ability.reload_time_remaining - PHYSICS_TICK_LENGTH).max(0.0);
+                    (ship_ability.reload_time_remaining - tick_length).max(0.0);
+            }
+        }
+
+        // Energy.
+        {
+            let tick_length = self.tick_length();
+            if let Some(energy) = self.data_mut().energy.as_mut() {
+                energy.remaining =
+                    (energy.remaining + energy.regeneration * tick_length).min(energy.capacity);
             }
         }
 
         // Destruction.
         if self.data().destroyed {
             if let Some(team_ctrl) = self.simulation.get_team_controller(self.data().team) {
-                team_ctrl.borrow_mut().remove_ship(self.handle);
+                team_ctrl.remove_ship(self.handle);
             }
             self.simulation.ships.remove(self.handle);
             self.simulation.bodies.remove(
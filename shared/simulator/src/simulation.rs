@@ -1,13 +1,18 @@
+use crate::ability;
 use crate::bullet::{self, BulletData, BulletHandle};
 use crate::collision;
 use crate::debug;
 pub use crate::debug::Line;
 use crate::index_set::{HasIndex, IndexSet};
+use crate::obstacle::{self, Obstacle};
+use crate::physics::{self, PhysicsConfig, Walls};
 use crate::radar;
 use crate::radio;
 use crate::scenario;
 use crate::scenario::Scenario;
-use crate::ship::{ShipAccessor, ShipAccessorMut, ShipData, ShipHandle, Target};
+use crate::ship::{
+    ShipAbility, ShipAccessor, ShipAccessorMut, ShipClass, ShipData, ShipHandle, Target,
+};
 use crate::snapshot::*;
 use crate::vm;
 use crate::vm::TeamController;
@@ -19,11 +24,8 @@ use rand_chacha::ChaCha8Rng;
 use rapier2d_f64::data::Coarena;
 use rapier2d_f64::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::cell::RefCell;
-use std::collections::{BTreeMap, HashMap};
-use std::rc::Rc;
+use std::collections::BTreeMap;
 
-pub const MAX_WORLD_SIZE: f64 = 200000.0;
 pub const PHYSICS_TICK_LENGTH: f64 = 1.0 / 60.0;
 
 #[derive(Clone, Serialize, Deserialize, Debug, Eq, Hash, PartialEq)]
@@ -40,7 +42,7 @@ pub struct Simulation {
     scenario: Option<Box<dyn Scenario>>,
     pub ships: IndexSet<ShipHandle>,
     pub(crate) ship_data: Coarena<ShipData>,
-    team_controllers: HashMap<i32, Rc<RefCell<Box<TeamController>>>>,
+    team_controllers: BTreeMap<i32, Box<dyn TeamController>>,
     pub new_ships: Vec<(/*team*/ i32, ShipHandle)>,
     pub bullets: IndexSet<BulletHandle>,
     pub(crate) bullet_data: Coarena<BulletData>,
@@ -63,11 +65,32 @@ pub struct Simulation {
     timing: Timing,
     pub(crate) rng: ChaCha8Rng,
     world_size: f64,
+    physics: PhysicsConfig,
+    /// Abilities given to every ship of a class, in addition to its own.
+    pub(crate) granted_abilities: BTreeMap<ShipClass, Vec<ShipAbility>>,
+    pub(crate) obstacles: Vec<Obstacle>,
 }
 
 impl Simulation {
     pub fn new(scenario_name: &str, seed: u32, codes: &[Code]) -> Box<Simulation> {
+        Self::with_team_controllers(scenario_name, seed, |sim| {
+            for (team, code) in codes.iter().enumerate() {
+                if !matches!(code, Code::None) {
+                    sim.upload_code(team as i32, code);
+                }
+            }
+        })
+    }
+
+    /// Creates a simulation, calling `init_teams` to set up team controllers
+    /// before the scenario creates any ships.
+    pub fn with_team_controllers(
+        scenario_name: &str,
+        seed: u32,
+        init_teams: impl FnOnce(&mut Simulation),
+    ) -> Box<Simulation> {
         let mut scenario = scenario::load(scenario_name);
+        let physics = scenario.physics();
 
         log::debug!("seed {seed}");
         let (contact_send, contact_recv) = crossbeam::channel::unbounded();
@@ -75,7 +98,7 @@ impl Simulation {
             scenario: None,
             ships: IndexSet::new(),
             ship_data: Coarena::new(),
-            team_controllers: HashMap::new(),
+            team_controllers: BTreeMap::new(),
             new_ships: Vec::new(),
             bullets: IndexSet::new(),
             bullet_data: Coarena::new(),
@@ -84,8 +107,8 @@ impl Simulation {
             multibody_joints: MultibodyJointSet::new(),
             colliders: ColliderSet::new(),
             integration_parameters: IntegrationParameters {
-                dt: PHYSICS_TICK_LENGTH,
-                max_ccd_substeps: 2,
+                dt: physics.tick_length,
+                max_ccd_substeps: physics.max_ccd_substeps,
                 ..Default::default()
             },
             physics_pipeline: PhysicsPipeline::new(),
@@ -102,13 +125,12 @@ impl Simulation {
             timing: Default::default(),
             rng: crate::rng::new_rng(seed),
             world_size: scenario.world_size(),
+            physics,
+            granted_abilities: scenario.abilities(),
+            obstacles: Vec::new(),
         });
 
-        for (team, code) in codes.iter().enumerate() {
-            if !matches!(code, Code::None) {
-                sim.upload_code(team as i32, code);
-            }
-        }
+        init_teams(&mut sim);
 
         collision::add_walls(&mut sim);
 
@@ -127,7 +149,7 @@ impl Simulation {
     }
 
     pub fn time(&self) -> f64 {
-        self.tick as f64 * PHYSICS_TICK_LENGTH
+        self.tick as f64 * self.physics.tick_length
     }
 
     pub fn score_time(&self) -> f64 {
@@ -142,6 +164,14 @@ impl Simulation {
         self.world_size
     }
 
+    pub fn physics(&self) -> &PhysicsConfig {
+        &self.physics
+    }
+
+    pub fn obstacles(&self) -> &[Obstacle] {
+        &self.obstacles
+    }
+
     pub fn status(&self) -> scenario::Status {
         self.scenario.as_ref().unwrap().status(self)
     }
@@ -167,11 +197,11 @@ impl Simulation {
 
         let new_ships = std::mem::take(&mut self.new_ships);
         for (team, handle) in new_ships.iter() {
-            if let Some(team_ctrl) = self.get_team_controller(*team) {
-                if let Err(e) = team_ctrl.borrow_mut().add_ship(*handle, self) {
-                    log::warn!("Ship creation error: {:?}", e);
-                    self.events.errors.push(e);
-                }
+            let result =
+                self.with_team_controller(*team, |team_ctrl, sim| team_ctrl.add_ship(*handle, sim));
+            if let Some(Err(e)) = result {
+                log::warn!("Ship creation error: {:?}", e);
+                self.events.errors.push(e);
             }
         }
 
@@ -193,6 +223,9 @@ impl Simulation {
             &physics_hooks,
             &self.event_collector,
         );
+        if self.physics.walls == Walls::Wrap {
+            physics::wrap_ships(self);
+        }
         self.timing.physics = physics_timer.elapsed();
 
         let collision_timer = Timer::new();
@@ -209,19 +242,15 @@ impl Simulation {
         self.timing.radio += radio_timer.elapsed();
 
         let vm_timer = Timer::new();
-        let mut teams: Vec<_> = self
-            .team_controllers
-            .iter()
-            .map(|(k, v)| (*k, Rc::clone(v)))
-            .collect();
-        teams.sort_by_key(|(k, _)| *k);
-
-        for (_, team_controller) in teams.iter() {
-            team_controller.borrow_mut().tick(self);
+        let mut team_controllers = std::mem::take(&mut self.team_controllers);
+        for team_controller in team_controllers.values_mut() {
+            team_controller.tick(self);
         }
+        self.team_controllers = team_controllers;
         self.timing.vm += vm_timer.elapsed();
 
         let ship_timer = Timer::new();
+        ability::tick(self);
         let handle_snapshot: Vec<ShipHandle> = self.ships.iter().cloned().collect();
         for handle in handle_snapshot {
             self.ship_mut(handle).tick();
@@ -245,10 +274,17 @@ impl Simulation {
     }
 
     pub fn upload_code(&mut self, team: i32, code: &Code) {
-        match vm::new_team_controller(code) {
+        self.upload_team_controller(team, vm::new_team_controller(code));
+    }
+
+    pub(crate) fn upload_team_controller(
+        &mut self,
+        team: i32,
+        team_ctrl: Result<Box<dyn TeamController>, vm::Error>,
+    ) {
+        match team_ctrl {
             Ok(team_ctrl) => {
-                self.team_controllers
-                    .insert(team, Rc::new(RefCell::new(team_ctrl)));
+                self.set_team_controller(team, team_ctrl);
             }
             Err(e) => {
                 log::warn!("Creating team controller failed: {:?}", e);
@@ -265,6 +301,12 @@ impl Simulation {
         &self.timing
     }
 
+    pub fn cpu_usage(&self, team: i32) -> Option<f64> {
+        self.team_controllers
+            .get(&team)
+            .and_then(|team_ctrl| team_ctrl.cpu_usage())
+    }
+
     pub fn emit_debug_lines(&mut self, ship: ShipHandle, lines: Vec<Line>) {
         self.events.debug_lines.push((ship.into(), lines));
     }
@@ -329,7 +371,13 @@ impl Simulation {
             status: self.status(),
             ships: vec![],
             bullets: vec![],
-            scenario_lines: self.scenario.as_ref().unwrap().lines(),
+            scenario_lines: [
+                obstacle::lines(self),
+                self.scenario.as_ref().unwrap().lines(),
+            ]
+            .concat(),
+            objectives: self.scenario.as_ref().unwrap().objectives(),
+            scores: self.scenario.as_ref().unwrap().scores(),
             debug_lines: self.events.debug_lines.clone(),
             debug_text: self.events.debug_text.clone(),
             drawn_text: self.events.drawn_text.clone(),
@@ -338,6 +386,12 @@ impl Simulation {
             cheats: self.cheats,
             timing: self.timing.clone(),
             world_size: self.world_size,
+            tick_length: self.physics.tick_length,
+            cpu_usage: self
+                .team_controllers
+                .iter()
+                .filter_map(|(team, team_ctrl)| team_ctrl.cpu_usage().map(|cpu| (*team, cpu)))
+                .collect(),
         };
 
         for &handle in self.ships.iter() {
@@ -378,8 +432,27 @@ impl Simulation {
         snapshot
     }
 
-    pub fn get_team_controller(&mut self, team: i32) -> Option<Rc<RefCell<Box<TeamController>>>> {
-        self.team_controllers.get_mut(&team).map(|x| x.clone())
+    pub fn get_team_controller(&mut self, team: i32) -> Option<&mut Box<dyn TeamController>> {
+        self.team_controllers.get_mut(&team)
+    }
+
+    /// Calls `f` with the team's controller temporarily removed from the
+    /// simulation, so that it can borrow both.
+    pub(crate) fn with_team_controller<R>(
+        &mut self,
+        team: i32,
+        f: impl FnOnce(&mut dyn TeamController, &mut Simulation) -> R,
+    ) -> Option<R> {
+        let mut team_ctrl = self.team_controllers.remove(&team)?;
+        let ret = f(team_ctrl.as_mut(), self);
+        self.team_controllers.entry(team).or_insert(team_ctrl);
+        Some(ret)
+    }
+
+    /// Replaces the code controlling a team, e.g. with `vm::native` code.
+    /// Ships created before the next step are added to the new controller.
+    pub fn set_team_controller(&mut self, team: i32, team_ctrl: Box<dyn TeamController>) {
+        self.team_controllers.insert(team, team_ctrl);
     }
 
     pub fn update_environment(&mut self, team: i32, mut environment: BTreeMap<String, String>) {
@@ -388,11 +461,9 @@ impl Simulation {
             self.scenario.as_ref().unwrap().name(),
         );
         environment.insert("WORLD_SIZE".to_string(), format!("{}", self.world_size));
+        environment.extend(self.physics.environment());
         if let Some(team_ctrl) = self.get_team_controller(team) {
-            team_ctrl
-                .borrow_mut()
-                .update_environment(&environment)
-                .unwrap();
+            team_ctrl.update_environment(&environment).unwrap();
         }
     }
 }
@@ -446,6 +517,8 @@ pub struct SimEvents {
     pub debug_lines: Vec<(u64, Vec<Line>)>,
     pub debug_text: BTreeMap<u64, String>,
     pub drawn_text: BTreeMap<Option<u64>, Vec<Text>>,
+    /// Bullet hits as (ship, damage).
+    pub hits: Vec<(u64, f64)>,
 }
 
 impl SimEvents {
@@ -456,6 +529,7 @@ impl SimEvents {
             debug_lines: Vec::new(),
             debug_text: BTreeMap::new(),
             drawn_text: BTreeMap::new(),
+            hits: Vec::new(),
         }
     }
 
@@ -465,6 +539,7 @@ impl SimEvents {
         self.debug_lines.clear();
         self.debug_text.clear();
         self.drawn_text.clear();
+        self.hits.clear();
     }
 }
 
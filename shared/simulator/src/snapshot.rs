@@ -1,3 +1,4 @@
+use crate::scenario::objectives::ObjectiveStatus;
 use crate::scenario::Status;
 use crate::ship::ShipClass;
 use crate::simulation::{Line, Particle};
@@ -16,6 +17,8 @@ pub struct Snapshot {
     pub ships: Vec<ShipSnapshot>,
     pub bullets: Vec<BulletSnapshot>,
     pub scenario_lines: Vec<Line>,
+    pub objectives: Vec<ObjectiveStatus>,
+    pub scores: BTreeMap<i32, f64>,
     pub particles: Vec<Particle>,
     pub errors: Vec<vm::Error>,
     pub cheats: bool,
@@ -24,6 +27,8 @@ pub struct Snapshot {
     pub drawn_text: BTreeMap<Option<u64>, Vec<Text>>,
     pub timing: Timing,
     pub world_size: f64,
+    pub tick_length: f64,
+    pub cpu_usage: BTreeMap<i32, f64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
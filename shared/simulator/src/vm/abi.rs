@@ -0,0 +1,233 @@
+// Checks that a user-provided WASM module matches the interface the VM expects.
+use super::Error;
+use walrus::{ExportItem, ImportKind, ValType};
+
+/// Imports stubbed out by the VM, with their (params, results). The VM's
+/// import object is built from this table too, so anything that passes
+/// validation can be instantiated.
+const ALLOWED_IMPORTS: &[(&str, &str, &[ValType], &[ValType])] = &[
+    (
+        "wasi_snapshot_preview1",
+        "fd_write",
+        &[ValType::I32, ValType::I32, ValType::I32, ValType::I32],
+        &[ValType::I32],
+    ),
+    (
+        "wasi_snapshot_preview1",
+        "fd_seek",
+        &[ValType::I32, ValType::I64, ValType::I32, ValType::I32],
+        &[ValType::I32],
+    ),
+    (
+        "wasi_snapshot_preview1",
+        "fd_close",
+        &[ValType::I32],
+        &[ValType::I32],
+    ),
+    ("wasi_snapshot_preview1", "proc_exit", &[ValType::I32], &[]),
+];
+
+/// Builds the VM's import object. Every stub returns -1, the WASI error value.
+pub fn imports(store: &mut wasmer::Store) -> wasmer::Imports {
+    let mut imports = wasmer::Imports::new();
+    for (module, name, params, results) in ALLOWED_IMPORTS {
+        let ty = wasmer::FunctionType::new(
+            params.iter().map(|x| wasmer_type(*x)).collect::<Vec<_>>(),
+            results.iter().map(|x| wasmer_type(*x)).collect::<Vec<_>>(),
+        );
+        let n = results.len();
+        let function =
+            wasmer::Function::new(store, ty, move |_| Ok(vec![wasmer::Value::I32(-1); n]));
+        imports.define(module, name, function);
+    }
+    imports
+}
+
+fn wasmer_type(ty: ValType) -> wasmer::Type {
+    match ty {
+        ValType::I32 => wasmer::Type::I32,
+        ValType::I64 => wasmer::Type::I64,
+        ValType::F32 => wasmer::Type::F32,
+        ValType::F64 => wasmer::Type::F64,
+        ValType::V128 => wasmer::Type::V128,
+        ValType::Externref => wasmer::Type::ExternRef,
+        ValType::Funcref => wasmer::Type::FuncRef,
+    }
+}
+
+/// Globals holding the address of a buffer shared with the simulator.
+const REQUIRED_GLOBALS: &[&str] = &["SYSTEM_STATE", "ENVIRONMENT", "PANIC_BUFFER"];
+
+/// Returns an error listing every way the module doesn't match the ABI.
+pub fn validate(wasm: &[u8]) -> Result<(), Error> {
+    let module = walrus::Module::from_buffer(wasm).map_err(|e| Error {
+        msg: format!("Invalid WASM module: {e}"),
+    })?;
+    let mut problems = vec![];
+
+    for import in module.imports.iter() {
+        let allowed = ALLOWED_IMPORTS
+            .iter()
+            .find(|(m, name, _, _)| *m == import.module && *name == import.name);
+        match (&import.kind, allowed) {
+            (ImportKind::Function(id), Some((_, _, params, results))) => {
+                let ty = module.types.get(module.funcs.get(*id).ty());
+                if ty.params() != *params || ty.results() != *results {
+                    problems.push(format!(
+                        "import {}.{} has type {:?} -> {:?}, expected {:?} -> {:?}",
+                        import.module,
+                        import.name,
+                        ty.params(),
+                        ty.results(),
+                        params,
+                        results
+                    ));
+                }
+            }
+            _ => problems.push(format!(
+                "import {}.{} is not allowed",
+                import.module, import.name
+            )),
+        }
+    }
+
+    let find_export = |name: &str| module.exports.iter().find(|x| x.name == name);
+
+    match find_export("tick").map(|x| &x.item) {
+        Some(ExportItem::Function(id)) => {
+            let ty = module.types.get(module.funcs.get(*id).ty());
+            if !ty.params().is_empty() || !ty.results().is_empty() {
+                problems.push(format!(
+                    "export tick has type {:?} -> {:?}, expected [] -> []",
+                    ty.params(),
+                    ty.results()
+                ));
+            }
+        }
+        Some(_) => problems.push("export tick is not a function".to_string()),
+        None => problems.push("missing export tick".to_string()),
+    }
+
+    match find_export("memory").map(|x| &x.item) {
+        Some(ExportItem::Memory(_)) => {}
+        Some(_) => problems.push("export memory is not a memory".to_string()),
+        None => problems.push("missing export memory".to_string()),
+    }
+
+    for name in REQUIRED_GLOBALS {
+        match find_export(name).map(|x| &x.item) {
+            Some(ExportItem::Global(id)) => {
+                let global = module.globals.get(*id);
+                if global.ty != ValType::I32 {
+                    problems.push(format!(
+                        "export {name} has type {:?}, expected I32",
+                        global.ty
+                    ));
+                }
+            }
+            Some(_) => problems.push(format!("export {name} is not a global")),
+            None => problems.push(format!("missing export {name}")),
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(Error {
+            msg: format!("Invalid WASM module:\n  {}", problems.join("\n  ")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wat2wasm(wat: &str) -> Vec<u8> {
+        wabt::Wat2Wasm::new()
+            .convert(wat)
+            .unwrap()
+            .as_ref()
+            .to_vec()
+    }
+
+    const VALID: &str = r#"
+(module
+    (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32)))
+    (memory (export "memory") 1)
+    (global (export "SYSTEM_STATE") i32 (i32.const 0))
+    (global (export "ENVIRONMENT") i32 (i32.const 1024))
+    (global (export "PANIC_BUFFER") i32 (i32.const 2048))
+    (func (export "tick")))
+"#;
+
+    #[test]
+    fn test_valid() {
+        validate(&wat2wasm(VALID)).unwrap();
+    }
+
+    #[test]
+    fn test_instantiate() {
+        // Every allowed import, in place of the one in VALID.
+        let imports: String = ALLOWED_IMPORTS
+            .iter()
+            .map(|(module, name, params, results)| {
+                let clause = |kind: &str, xs: &[ValType]| {
+                    xs.iter()
+                        .map(|x| format!(" ({kind} {x:?})").to_lowercase())
+                        .collect::<String>()
+                };
+                format!(
+                    "(import \"{module}\" \"{name}\" (func{}{}))\n    ",
+                    clause("param", params),
+                    clause("result", results)
+                )
+            })
+            .collect();
+        let wat = VALID.replace(
+            "(import \"wasi_snapshot_preview1\" \"fd_write\" (func (param i32 i32 i32 i32) (result i32)))\n    ",
+            &imports,
+        );
+        assert_ne!(wat, VALID);
+        let wasm = wat2wasm(&wat);
+        validate(&wasm).unwrap();
+        crate::vm::WasmVm::create(&crate::simulation::Code::Wasm(wasm)).unwrap();
+    }
+
+    #[test]
+    fn test_not_wasm() {
+        let err = validate(b"not wasm").unwrap_err();
+        assert!(err.msg.starts_with("Invalid WASM module: "), "{}", err.msg);
+    }
+
+    #[test]
+    fn test_missing_exports() {
+        let err = validate(&wat2wasm("(module (func (export \"tick\") (param i32)))")).unwrap_err();
+        assert_eq!(
+            err.msg,
+            "Invalid WASM module:
+  export tick has type [I32] -> [], expected [] -> []
+  missing export memory
+  missing export SYSTEM_STATE
+  missing export ENVIRONMENT
+  missing export PANIC_BUFFER"
+        );
+    }
+
+    #[test]
+    fn test_forbidden_imports() {
+        let wat = VALID.replace(
+            "(memory",
+            r#"(import "env" "emscripten_notify_memory_growth" (func (param i32)))
+    (import "wasi_snapshot_preview1" "proc_exit" (func (param i64)))
+    (memory"#,
+        );
+        let err = validate(&wat2wasm(&wat)).unwrap_err();
+        assert_eq!(
+            err.msg,
+            "Invalid WASM module:
+  import env.emscripten_notify_memory_growth is not allowed
+  import wasi_snapshot_preview1.proc_exit has type [I64] -> [], expected [I32] -> []"
+        );
+    }
+}
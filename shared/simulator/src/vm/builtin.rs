@@ -8,7 +8,15 @@ fn builtin_ai_data() -> &'static [u8] {
 }
 
 pub fn load_source(name: &str) -> Result<Code, String> {
-    let name = format!("{name}.rs");
+    load_source_with_extension(name, "rs")
+}
+
+/// Loads the source of a builtin AI written in the language with the given
+/// file extension ("rs", "c", "cpp", or "zig").
+///
+/// Only Rust AIs are precompiled, the others are initial code for the editor.
+pub fn load_source_with_extension(name: &str, extension: &str) -> Result<Code, String> {
+    let name = format!("{name}.{extension}");
     let mut a = Archive::new(Decoder::new(builtin_ai_data()).unwrap());
     for file in a.entries().unwrap() {
         let mut file = file.unwrap();
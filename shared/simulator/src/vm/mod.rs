@@ -1,25 +1,24 @@
 // TODO clean up error translation
 // TODO add methods to WasmVm for each exported function
 // TODO shift pointers according to headroom + base
+pub mod abi;
 pub mod builtin;
 mod limiter;
+pub mod native;
 
 use crate::color;
 use crate::debug;
 use crate::rng::new_rng;
 use crate::ship::{ShipClass, ShipHandle};
-use crate::simulation::{Code, Particle, Simulation, PHYSICS_TICK_LENGTH};
+use crate::simulation::{Code, Particle, Simulation};
 use nalgebra::{point, vector, Rotation2};
 use oort_api::{ActiveAbilities, Class, EcmMode, Line, SystemState, Text};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::cell::{Ref, RefCell, RefMut};
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::f64::consts::TAU;
-use std::ops::{Deref, DerefMut};
-use std::rc::Rc;
-use wasmer::{imports, Function, Instance, MemoryView, Module, Store, WasmPtr};
+use wasmer::{Instance, MemoryView, Module, Store, WasmPtr};
 
 pub type Vec2 = nalgebra::Vector2<f64>;
 pub type Environment = BTreeMap<String, String>;
@@ -28,6 +27,10 @@ const SUBMEMORY_SIZE: u32 = 2 << 20;
 const GAS_PER_TICK: i32 = 1_000_000;
 const MAX_DEBUG_LINES: u32 = 1024;
 const MAX_DRAWN_TEXT: u32 = 128;
+const SHIPS_PER_SHARD: usize = 8;
+/// Fixed rather than derived from the host's cores so that ships are split
+/// between shards the same way everywhere.
+const MAX_SHARDS: usize = 8;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Error {
@@ -67,16 +70,73 @@ impl From<anyhow::Error> for Error {
     }
 }
 
-pub fn new_team_controller(code: &Code) -> Result<Box<TeamController>, Error> {
-    match code {
-        Code::Wasm(_) => TeamController::create(code),
-        #[cfg(feature = "precompile")]
-        Code::Precompiled(_) => TeamController::create(code),
-        Code::Builtin(name) => match builtin::load_compiled(name) {
-            Ok(code) => new_team_controller(&code),
-            Err(e) => Err(Error { msg: e }),
-        },
-        _ => unreachable!(),
+/// Runs the code controlling a team's ships.
+pub trait TeamController: Send {
+    fn add_ship(&mut self, handle: ShipHandle, sim: &Simulation) -> Result<(), Error>;
+
+    fn remove_ship(&mut self, handle: ShipHandle);
+
+    /// Runs one tick of each ship's code, in order of ship handle.
+    fn tick(&mut self, sim: &mut Simulation);
+
+    /// Average gas used per ship per tick, or None if no ship has completed a tick.
+    fn cpu_usage(&self) -> Option<f64>;
+
+    fn update_environment(&mut self, environment: &Environment) -> Result<(), Error>;
+}
+
+pub fn new_team_controller(code: &Code) -> Result<Box<dyn TeamController>, Error> {
+    CompiledCode::new(code)?.team_controller()
+}
+
+/// Ship code compiled once and shared between simulations.
+#[derive(Clone)]
+pub struct CompiledCode {
+    engine: wasmer::Engine,
+    module: Module,
+}
+
+impl CompiledCode {
+    pub fn new(code: &Code) -> Result<CompiledCode, Error> {
+        #[cfg(feature = "js")]
+        let store = Store::default();
+        #[cfg(feature = "sys")]
+        let store = Store::new(wasmer_compiler_cranelift::Cranelift::new());
+        let module = match code {
+            Code::Wasm(wasm) => {
+                abi::validate(wasm)?;
+                let wasm = wasm_submemory::rewrite(wasm, SUBMEMORY_SIZE)?;
+                let wasm = limiter::rewrite(&wasm)?;
+                translate_error(Module::new(&store, wasm))?
+            }
+            #[cfg(feature = "precompile")]
+            Code::Precompiled(bytes) => {
+                translate_error(unsafe { Module::deserialize(&store, bytes.clone()) })?
+            }
+            Code::Builtin(name) => {
+                return match builtin::load_compiled(name) {
+                    Ok(code) => CompiledCode::new(&code),
+                    Err(e) => Err(Error { msg: e }),
+                }
+            }
+            _ => unreachable!(),
+        };
+        Ok(CompiledCode {
+            engine: store.engine().clone(),
+            module,
+        })
+    }
+
+    pub fn team_controller(&self) -> Result<Box<dyn TeamController>, Error> {
+        Ok(WasmTeamController::from_compiled(self)?)
+    }
+
+    fn instantiate(&self) -> Result<WasmVm, Error> {
+        #[cfg(feature = "js")]
+        let store = Store::default();
+        #[cfg(feature = "sys")]
+        let store = Store::new(self.engine.clone());
+        WasmVm::instantiate_module(store, self.clone())
     }
 }
 
@@ -89,28 +149,281 @@ pub struct ShipController {
     panic_buffer_ptr: WasmPtr<u8>,
 }
 
-pub struct TeamController {
+/// A wasm instance running some of a team's ships. Ships in different shards
+/// run in parallel.
+struct Shard {
     vm: WasmVm,
-    pub ship_controllers: HashMap<ShipHandle, ShipController>,
-    next_id: u32,
+    ship_controllers: BTreeMap<ShipHandle, ShipController>,
     free_submemories: Vec<(u32, u32)>, // (index, base_address)
+}
+
+/// The result of running a ship's code, applied to the simulation after all
+/// ships have run.
+struct ShipOutput {
+    gas: Option<i32>,
+    debug_text: Option<String>,
+    debug_lines: Option<Vec<Line>>,
+    drawn_text: Option<Vec<Text>>,
+}
+
+pub struct WasmTeamController {
+    shards: Vec<Shard>,
+    ship_shards: HashMap<ShipHandle, usize>,
+    max_shards: usize,
+    next_id: u32,
     environment: Environment,
+    gas_used: u64,
+    ship_ticks: u64,
 }
 
-impl TeamController {
-    pub fn create(code: &Code) -> Result<Box<TeamController>, Error> {
-        Ok(Box::new(TeamController {
-            vm: WasmVm::create(code)?,
-            ship_controllers: HashMap::new(),
+impl WasmTeamController {
+    pub fn create(code: &Code) -> Result<Box<WasmTeamController>, Error> {
+        Self::from_compiled(&CompiledCode::new(code)?)
+    }
+
+    pub fn from_compiled(compiled: &CompiledCode) -> Result<Box<WasmTeamController>, Error> {
+        Ok(Box::new(WasmTeamController {
+            shards: vec![Shard::new(compiled.instantiate()?)],
+            ship_shards: HashMap::new(),
+            max_shards: MAX_SHARDS,
             next_id: 1,
-            free_submemories: Vec::new(),
             environment: Environment::new(),
+            gas_used: 0,
+            ship_ticks: 0,
         }))
     }
 
-    pub fn add_ship(&mut self, handle: ShipHandle, sim: &Simulation) -> Result<(), Error> {
-        let mut state = LocalSystemState::new();
+    /// Limits the number of shards created for new ships. Mostly useful for
+    /// checking that results don't depend on how ships are sharded.
+    pub fn set_max_shards(&mut self, max_shards: usize) {
+        self.max_shards = max_shards.max(1);
+    }
+
+    /// Picks the shard with the fewest ships, adding a shard if they are all
+    /// full.
+    fn choose_shard(&mut self) -> Result<usize, Error> {
+        let (index, num_ships) = self
+            .shards
+            .iter()
+            .map(|shard| shard.ship_controllers.len())
+            .enumerate()
+            .min_by_key(|&(_, num_ships)| num_ships)
+            .unwrap();
+        if num_ships >= SHIPS_PER_SHARD && self.shards.len() < self.max_shards {
+            let vm = self.shards[0].vm.instantiate()?;
+            self.shards.push(Shard::new(vm));
+            return Ok(self.shards.len() - 1);
+        }
+        Ok(index)
+    }
+
+    fn apply_output(&mut self, sim: &mut Simulation, handle: ShipHandle, output: ShipOutput) {
+        // Display gas usage as debug text
+        if let Some(gas) = output.gas {
+            self.gas_used += (GAS_PER_TICK - gas).max(0) as u64;
+            self.ship_ticks += 1;
+            sim.emit_debug_text(
+                handle,
+                format!("CPU: {}%\n", (GAS_PER_TICK - gas) * 100 / GAS_PER_TICK),
+            );
+        }
+
+        let shard = &mut self.shards[self.ship_shards[&handle]];
+        let state = &mut shard.ship_controllers.get_mut(&handle).unwrap().state;
+        apply_system_state(sim, handle, state);
+
+        if let Some(s) = output.debug_text {
+            sim.emit_debug_text(handle, s);
+        }
+        if let Some(lines) = output.debug_lines {
+            emit_lines(sim, handle, &lines);
+        }
+        if let Some(texts) = output.drawn_text {
+            emit_texts(sim, handle, &texts);
+        }
+    }
+}
+
+impl TeamController for WasmTeamController {
+    fn add_ship(&mut self, handle: ShipHandle, sim: &Simulation) -> Result<(), Error> {
+        let state = initial_system_state(sim, handle, self.next_id);
+        self.next_id += 1;
+
+        let shard_index = self.choose_shard()?;
+        let ship_controller = self.shards[shard_index].add_ship(state, &self.environment)?;
+        self.shards[shard_index]
+            .ship_controllers
+            .insert(handle, ship_controller);
+        self.ship_shards.insert(handle, shard_index);
+
+        Ok(())
+    }
+
+    fn remove_ship(&mut self, handle: ShipHandle) {
+        let shard_index = self.ship_shards.remove(&handle).unwrap();
+        self.shards[shard_index].remove_ship(handle);
+    }
+
+    fn tick(&mut self, sim: &mut Simulation) {
+        // A ship's inputs don't depend on other ships' outputs from the same
+        // tick (outputs only change the ship itself or spawn bullets), so all
+        // inputs are generated before running any code.
+        let mut shard_handles = vec![vec![]; self.shards.len()];
+        for (shard, handles) in self.shards.iter_mut().zip(shard_handles.iter_mut()) {
+            for (&handle, ship_controller) in shard.ship_controllers.iter_mut() {
+                if sim.ship(handle).data().crash_message.is_none() {
+                    generate_system_state(sim, handle, &mut ship_controller.state);
+                    handles.push(handle);
+                }
+            }
+        }
+
+        let mut outputs = run_shards(&mut self.shards, shard_handles);
+
+        // Side effects are applied in the same order as if the ships had run
+        // sequentially.
+        let mut handles: Vec<_> = self.ship_shards.keys().cloned().collect();
+        handles.sort_by_key(|x| x.0);
+
+        for handle in handles {
+            if tick_crashed(sim, handle) {
+                continue;
+            }
+            match outputs.remove(&handle) {
+                Some(Ok(output)) => self.apply_output(sim, handle, output),
+                Some(Err(e)) => crash(sim, handle, e),
+                None => {}
+            }
+        }
+    }
+
+    fn cpu_usage(&self) -> Option<f64> {
+        if self.ship_ticks == 0 {
+            None
+        } else {
+            Some(self.gas_used as f64 / self.ship_ticks as f64)
+        }
+    }
+
+    fn update_environment(&mut self, environment: &Environment) -> Result<(), Error> {
+        self.environment = environment.clone();
+        for shard in self.shards.iter_mut() {
+            for (_, ship_controller) in shard.ship_controllers.iter() {
+                shard
+                    .vm
+                    .update_environment(ship_controller.environment_ptr, environment)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+type ShipOutputs = BTreeMap<ShipHandle, Result<ShipOutput, Error>>;
+
+/// Runs each shard's ships. Shards are spread over rayon's thread pool, which
+/// has one thread per core.
+fn run_shards(shards: &mut [Shard], shard_handles: Vec<Vec<ShipHandle>>) -> ShipOutputs {
+    #[cfg(not(target_arch = "wasm32"))]
+    if shards.len() > 1 {
+        use rayon::prelude::*;
+        return shards
+            .par_iter_mut()
+            .zip(shard_handles)
+            .flat_map_iter(|(shard, handles)| shard.tick(handles))
+            .collect();
+    }
+    shards
+        .iter_mut()
+        .zip(shard_handles)
+        .flat_map(|(shard, handles)| shard.tick(handles))
+        .collect()
+}
+
+/// System state for a new ship, before its first tick.
+fn initial_system_state(sim: &Simulation, handle: ShipHandle, id: u32) -> LocalSystemState {
+    let mut state = LocalSystemState::new();
+    state.set(
+        SystemState::Seed,
+        (make_seed(sim.seed(), handle) & 0xffffff) as f64,
+    );
+    state.set(SystemState::Id, id as f64);
+    if let Some(radar) = sim.ship(handle).data().radar.as_ref() {
+        state.set(SystemState::RadarHeading, radar.heading);
+        state.set(SystemState::RadarWidth, radar.width);
+        state.set(SystemState::RadarMinDistance, radar.min_distance);
+        state.set(SystemState::RadarMaxDistance, radar.max_distance);
+    }
+    state
+}
+
+fn emit_lines(sim: &mut Simulation, handle: ShipHandle, lines: &[Line]) {
+    if validate_lines(lines) {
+        sim.emit_debug_lines(
+            handle,
+            lines
+                .iter()
+                .map(|v| crate::debug::Line {
+                    a: point![v.x0, v.y0],
+                    b: point![v.x1, v.y1],
+                    color: color::from_u24(v.color),
+                })
+                .collect::<Vec<debug::Line>>(),
+        );
+    }
+}
+
+fn emit_texts(sim: &mut Simulation, handle: ShipHandle, texts: &[Text]) {
+    if validate_texts(texts) {
+        sim.emit_drawn_text(Some(handle), texts);
+    }
+}
 
+/// Records a crash in the ship's code, which stops it from running again.
+fn crash(sim: &mut Simulation, handle: ShipHandle, e: Error) {
+    log::warn!("{}", e.msg);
+    sim.emit_debug_text(handle, format!("Crashed: {}", e.msg.clone()));
+    sim.ship_mut(handle).data_mut().crash_message = Some(e.msg);
+}
+
+/// Shows the crash message and sparks for a crashed ship. Returns true if the
+/// ship has crashed.
+fn tick_crashed(sim: &mut Simulation, handle: ShipHandle) -> bool {
+    let Some(msg) = sim.ship(handle).data().crash_message.as_ref() else {
+        return false;
+    };
+    sim.emit_debug_text(handle, format!("Crashed: {}", msg.clone()));
+    let mut rng = new_rng(sim.tick());
+    if rng.gen_range(0.0..1.0) < 0.2 {
+        let color = vector![0.5, 0.5, 0.9, rng.gen_range(0.5..1.0)];
+        let rot = Rotation2::new(rng.gen_range(0.0..TAU));
+        let speed = 300.0 * rng.gen_range(0.0..1.0);
+        let p = sim.ship(handle).position().vector;
+        let v = sim.ship(handle).body().linvel() + rot.transform_vector(&vector![speed, 0.0]);
+        let offset = v * rng.gen_range(0.0..sim.physics().tick_length);
+        sim.events.particles.push(Particle {
+            position: p + offset,
+            velocity: v,
+            color,
+            lifetime: 1.0,
+        });
+    }
+    true
+}
+
+impl Shard {
+    fn new(vm: WasmVm) -> Self {
+        Self {
+            vm,
+            ship_controllers: BTreeMap::new(),
+            free_submemories: Vec::new(),
+        }
+    }
+
+    fn add_ship(
+        &mut self,
+        state: LocalSystemState,
+        environment: &Environment,
+    ) -> Result<ShipController, Error> {
         let (index, base_address) = {
             if let Some((index, base_address)) = self.free_submemories.pop() {
                 (index, base_address)
@@ -119,19 +432,6 @@ impl TeamController {
             }
         };
 
-        state.set(
-            SystemState::Seed,
-            (make_seed(sim.seed(), handle) & 0xffffff) as f64,
-        );
-        state.set(SystemState::Id, self.next_id as f64);
-        self.next_id += 1;
-        if let Some(radar) = sim.ship(handle).data().radar.as_ref() {
-            state.set(SystemState::RadarHeading, radar.heading);
-            state.set(SystemState::RadarWidth, radar.width);
-            state.set(SystemState::RadarMinDistance, radar.min_distance);
-            state.set(SystemState::RadarMaxDistance, radar.max_distance);
-        }
-
         self.vm.select_submemory(index)?;
 
         let system_state_ptr: WasmPtr<u64> =
@@ -140,87 +440,48 @@ impl TeamController {
         let panic_buffer_ptr: WasmPtr<u8> =
             WasmPtr::new(base_address + self.vm.panic_buffer_offset);
 
-        self.vm
-            .update_environment(environment_ptr, &self.environment)?;
-
-        self.ship_controllers.insert(
-            handle,
-            ShipController {
-                index,
-                state,
-                base_address,
-                system_state_ptr,
-                environment_ptr,
-                panic_buffer_ptr,
-            },
-        );
+        self.vm.update_environment(environment_ptr, environment)?;
 
-        Ok(())
+        Ok(ShipController {
+            index,
+            state,
+            base_address,
+            system_state_ptr,
+            environment_ptr,
+            panic_buffer_ptr,
+        })
     }
 
-    pub fn remove_ship(&mut self, handle: ShipHandle) {
+    fn remove_ship(&mut self, handle: ShipHandle) {
         let ship_controller = self.ship_controllers.remove(&handle).unwrap();
         self.vm
             .reset_gas
-            .call(&mut self.vm.store_mut(), &[GAS_PER_TICK.into()])
+            .call(&mut self.vm.store, &[GAS_PER_TICK.into()])
             .unwrap();
         self.vm.reset_submemory(ship_controller.index).unwrap();
         self.free_submemories
             .push((ship_controller.index, ship_controller.base_address));
     }
 
-    pub fn tick(&mut self, sim: &mut Simulation) {
-        let mut handles: Vec<_> = self.ship_controllers.keys().cloned().collect();
-        handles.sort_by_key(|x| x.0);
-
-        for handle in handles {
-            if let Err(e) = self.tick_ship(sim, handle) {
-                log::warn!("{}", e.msg);
-                sim.emit_debug_text(handle, format!("Crashed: {}", e.msg.clone()));
-                sim.ship_mut(handle).data_mut().crash_message = Some(e.msg);
-            }
-        }
+    fn tick(&mut self, handles: Vec<ShipHandle>) -> Vec<(ShipHandle, Result<ShipOutput, Error>)> {
+        handles
+            .into_iter()
+            .map(|handle| (handle, self.tick_ship(handle)))
+            .collect()
     }
 
-    fn tick_ship(&mut self, sim: &mut Simulation, handle: ShipHandle) -> Result<(), Error> {
-        if let Some(msg) = sim.ship(handle).data().crash_message.as_ref() {
-            sim.emit_debug_text(handle, format!("Crashed: {}", msg.clone()));
-            let mut rng = new_rng(sim.tick());
-            if rng.gen_range(0.0..1.0) < 0.2 {
-                let color = vector![0.5, 0.5, 0.9, rng.gen_range(0.5..1.0)];
-                let rot = Rotation2::new(rng.gen_range(0.0..TAU));
-                let speed = 300.0 * rng.gen_range(0.0..1.0);
-                let p = sim.ship(handle).position().vector;
-                let v =
-                    sim.ship(handle).body().linvel() + rot.transform_vector(&vector![speed, 0.0]);
-                let offset = v * rng.gen_range(0.0..PHYSICS_TICK_LENGTH);
-                sim.events.particles.push(Particle {
-                    position: p + offset,
-                    velocity: v,
-                    color,
-                    lifetime: 1.0,
-                });
-            }
-            return Ok(());
-        }
-
+    fn tick_ship(&mut self, handle: ShipHandle) -> Result<ShipOutput, Error> {
         let vm = &mut self.vm;
-        let ship_controller = &mut self.ship_controllers.get_mut(&handle).unwrap();
+        let ship_controller = self.ship_controllers.get_mut(&handle).unwrap();
         let state = &mut ship_controller.state;
 
-        // Take the state of the sim and the ship, update the ship's memory
+        // Write the system state generated from the sim to the ship's memory
         {
-            translate_runtime_error(
-                vm.reset_gas
-                    .call(vm.store_mut().deref_mut(), &[GAS_PER_TICK.into()]),
-            )?;
+            translate_runtime_error(vm.reset_gas.call(&mut vm.store, &[GAS_PER_TICK.into()]))?;
 
             vm.select_submemory(ship_controller.index)?;
 
-            generate_system_state(sim, handle, state);
-
-            let store = vm.store();
-            let memory_view = vm.memory.view(store.deref());
+            let memory_view = vm.memory.view(&vm.store);
             let ptr = ship_controller.system_state_ptr;
             let slice = ptr
                 .slice(&memory_view, SystemState::Size as u32)
@@ -229,10 +490,10 @@ impl TeamController {
         }
 
         // Run user's ship tick
-        let result = vm.tick_ship.call(vm.store_mut().deref_mut(), &[]);
+        let result = vm.tick_ship.call(&mut vm.store, &[]);
         if let Err(e) = result {
             // If gas has run out, throw an error
-            if let Ok(gas) = vm.get_gas.call(vm.store_mut().deref_mut()) {
+            if let Ok(gas) = vm.get_gas.call(&mut vm.store) {
                 if gas <= 0 {
                     return Err(Error {
                         msg: "Ship exceeded maximum number of instructions".to_string(),
@@ -242,8 +503,7 @@ impl TeamController {
 
             // Otherwise pull the panic message from memory and throw it
             {
-                let store = vm.store();
-                let memory_view = vm.memory.view(store.deref());
+                let memory_view = vm.memory.view(&vm.store);
                 if let Some(vec) = WasmVm::read_vec(
                     &memory_view,
                     ship_controller.panic_buffer_ptr.offset(),
@@ -264,91 +524,57 @@ impl TeamController {
             return translate_runtime_error(Err(e));
         }
 
-        // Display gas usage as debug text
-        if let Ok(gas) = vm.get_gas.call(vm.store_mut().deref_mut()) {
-            sim.emit_debug_text(
-                handle,
-                format!("CPU: {}%\n", (GAS_PER_TICK - gas) * 100 / GAS_PER_TICK),
-            );
-        }
+        let mut output = ShipOutput {
+            gas: vm.get_gas.call(&mut vm.store).ok(),
+            debug_text: None,
+            debug_lines: None,
+            drawn_text: None,
+        };
 
-        {
-            let store = vm.store();
-            let memory_view = vm.memory.view(store.deref());
-            let ptr = ship_controller.system_state_ptr;
-            let slice = ptr
-                .slice(&memory_view, SystemState::Size as u32)
-                .expect("system state read");
-            slice
-                .read_slice(&mut state.state)
-                .expect("system state read");
-            apply_system_state(sim, handle, state);
-
-            // Emit debug text
-            if state.get(SystemState::DebugTextLength) > 0.0 {
-                let offset =
-                    state.get(SystemState::DebugTextPointer) as u32 + ship_controller.base_address;
-                let length = state.get(SystemState::DebugTextLength) as u32;
-                if let Some(s) = WasmVm::read_string(&memory_view, offset, length) {
-                    sim.emit_debug_text(handle, s);
-                }
-            }
+        let memory_view = vm.memory.view(&vm.store);
+        let ptr = ship_controller.system_state_ptr;
+        let slice = ptr
+            .slice(&memory_view, SystemState::Size as u32)
+            .expect("system state read");
+        slice
+            .read_slice(&mut state.state)
+            .expect("system state read");
+
+        // Read debug text
+        if state.get(SystemState::DebugTextLength) > 0.0 {
+            let offset =
+                state.get(SystemState::DebugTextPointer) as u32 + ship_controller.base_address;
+            let length = state.get(SystemState::DebugTextLength) as u32;
+            output.debug_text = WasmVm::read_string(&memory_view, offset, length);
+        }
 
-            // Emit debug lines
-            if state.get(SystemState::DebugLinesLength) > 0.0 {
-                let offset =
-                    state.get(SystemState::DebugLinesPointer) as u32 + ship_controller.base_address;
-                let length = state.get(SystemState::DebugLinesLength) as u32;
-                if length <= MAX_DEBUG_LINES {
-                    if let Some(lines) = WasmVm::read_vec::<Line>(&memory_view, offset, length) {
-                        if validate_lines(&lines) {
-                            sim.emit_debug_lines(
-                                handle,
-                                lines
-                                    .iter()
-                                    .map(|v| crate::debug::Line {
-                                        a: point![v.x0, v.y0],
-                                        b: point![v.x1, v.y1],
-                                        color: color::from_u24(v.color),
-                                    })
-                                    .collect::<Vec<debug::Line>>(),
-                            );
-                        }
-                    }
-                }
+        // Read debug lines
+        if state.get(SystemState::DebugLinesLength) > 0.0 {
+            let offset =
+                state.get(SystemState::DebugLinesPointer) as u32 + ship_controller.base_address;
+            let length = state.get(SystemState::DebugLinesLength) as u32;
+            if length <= MAX_DEBUG_LINES {
+                output.debug_lines = WasmVm::read_vec::<Line>(&memory_view, offset, length);
             }
+        }
 
-            // Emit drawn text
-            if state.get(SystemState::DrawnTextLength) > 0.0 {
-                let offset =
-                    state.get(SystemState::DrawnTextPointer) as u32 + ship_controller.base_address;
-                let length = state.get(SystemState::DrawnTextLength) as u32;
-                if length <= MAX_DRAWN_TEXT {
-                    if let Some(texts) = WasmVm::read_vec::<Text>(&memory_view, offset, length) {
-                        if validate_texts(&texts) {
-                            sim.emit_drawn_text(Some(handle), &texts);
-                        }
-                    }
-                }
+        // Read drawn text
+        if state.get(SystemState::DrawnTextLength) > 0.0 {
+            let offset =
+                state.get(SystemState::DrawnTextPointer) as u32 + ship_controller.base_address;
+            let length = state.get(SystemState::DrawnTextLength) as u32;
+            if length <= MAX_DRAWN_TEXT {
+                output.drawn_text = WasmVm::read_vec::<Text>(&memory_view, offset, length);
             }
         }
 
-        Ok(())
-    }
-
-    pub fn update_environment(&mut self, environment: &Environment) -> Result<(), Error> {
-        self.environment = environment.clone();
-        for (_, ship_controller) in self.ship_controllers.iter_mut() {
-            self.vm
-                .update_environment(ship_controller.environment_ptr, environment)?;
-        }
-        Ok(())
+        Ok(output)
     }
 }
 
-#[derive(Clone)]
 pub struct WasmVm {
-    store: Rc<RefCell<wasmer::Store>>,
+    store: wasmer::Store,
+    compiled: CompiledCode,
     memory: wasmer::Memory,
     system_state_offset: u32,
     environment_offset: u32,
@@ -363,31 +589,17 @@ pub struct WasmVm {
 
 impl WasmVm {
     pub fn create(code: &Code) -> Result<WasmVm, Error> {
-        #[cfg(feature = "js")]
-        let mut store = Store::default();
-        #[cfg(feature = "sys")]
-        let mut store = Store::new(wasmer_compiler_cranelift::Cranelift::new());
-        let module = match code {
-            Code::Wasm(wasm) => {
-                let wasm = wasm_submemory::rewrite(wasm, SUBMEMORY_SIZE)?;
-                let wasm = limiter::rewrite(&wasm)?;
-                translate_error(Module::new(&store, wasm))?
-            }
-            #[cfg(feature = "precompile")]
-            Code::Precompiled(bytes) => {
-                translate_error(unsafe { Module::deserialize(&store, bytes.clone()) })?
-            }
-            _ => unreachable!(),
-        };
-        let import_object = imports! {
-            "wasi_snapshot_preview1" => {
-                "fd_write" => Function::new_typed(&mut store, |_a: i32, _b: i32, _c: i32, _d: i32, _e: i32| -> i32 { -1 }),
-                "fd_seek" => Function::new_typed(&mut store, |_a: i32, _b: i64, _c: i32, _d: i32| -> i32 { -1 }),
-                "fd_close" => Function::new_typed(&mut store, |_a: i32| -> i32 { -1 }),
-                "proc_exit" => Function::new_typed(&mut store, |_a: i32| {}),
-            }
-        };
-        let instance = Instance::new(&mut store, &module, &import_object)?;
+        CompiledCode::new(code)?.instantiate()
+    }
+
+    /// Creates another instance of the same module, reusing the compiled code.
+    fn instantiate(&self) -> Result<WasmVm, Error> {
+        self.compiled.instantiate()
+    }
+
+    fn instantiate_module(mut store: Store, compiled: CompiledCode) -> Result<WasmVm, Error> {
+        let import_object = abi::imports(&mut store);
+        let instance = Instance::new(&mut store, &compiled.module, &import_object)?;
 
         let memory = translate_error(instance.exports.get_memory("memory"))?.clone();
         let system_state_offset: u32 = translate_error(instance.exports.get_global("SYSTEM_STATE"))?
@@ -416,7 +628,8 @@ impl WasmVm {
         translate_runtime_error(reset_gas.call(&mut store, &[GAS_PER_TICK.into()]))?;
 
         Ok(WasmVm {
-            store: Rc::new(RefCell::new(store)),
+            store,
+            compiled,
             memory,
             system_state_offset,
             environment_offset,
@@ -430,14 +643,6 @@ impl WasmVm {
         })
     }
 
-    fn store(&self) -> Ref<'_, Store> {
-        self.store.borrow()
-    }
-
-    fn store_mut(&self) -> RefMut<'_, Store> {
-        self.store.borrow_mut()
-    }
-
     fn read_string(memory_view: &MemoryView, offset: u32, length: u32) -> Option<String> {
         let ptr: WasmPtr<u8> = WasmPtr::new(offset);
         let mut bytes: Vec<u8> = vec![0; length as usize];
@@ -471,8 +676,7 @@ impl WasmVm {
                 msg: "environment too large".to_string(),
             });
         }
-        let store = self.store_mut();
-        let view = self.memory.view(&store);
+        let view = self.memory.view(&self.store);
         let slice = ptr
             .slice(&view, environment_string.bytes().len() as u32)
             .ok()
@@ -485,8 +689,7 @@ impl WasmVm {
     }
 
     fn add_submemory(&mut self) -> Result<(u32, u32), Error> {
-        let mut store = self.store_mut();
-        let ret = translate_error(self.add_submemory.call(store.deref_mut(), &[]))?;
+        let ret = translate_error(self.add_submemory.call(&mut self.store, &[]))?;
         match *ret {
             [wasmer::Value::I32(index), wasmer::Value::I32(base_address)] => {
                 Ok((index as u32, base_address as u32))
@@ -498,19 +701,17 @@ impl WasmVm {
     }
 
     fn select_submemory(&mut self, index: u32) -> Result<(), Error> {
-        let mut store = self.store_mut();
         translate_error(
             self.select_submemory
-                .call(store.deref_mut(), &[wasmer::Value::I32(index as i32)]),
+                .call(&mut self.store, &[wasmer::Value::I32(index as i32)]),
         )?;
         Ok(())
     }
 
     fn reset_submemory(&mut self, index: u32) -> Result<(), Error> {
-        let mut store = self.store_mut();
         translate_error(
             self.reset_submemory
-                .call(store.deref_mut(), &[wasmer::Value::I32(index as i32)]),
+                .call(&mut self.store, &[wasmer::Value::I32(index as i32)]),
         )?;
         Ok(())
     }
@@ -590,6 +791,15 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
         } else {
             state.set(SystemState::RadarContactFound, 0.0);
         }
+
+        let warnings = radar.warnings();
+        let warnings = &warnings[..warnings.len().min(oort_api::prelude::MAX_RADAR_WARNINGS)];
+        state.set(SystemState::RadarWarningCount, warnings.len() as f64);
+        for (i, warning) in warnings.iter().enumerate() {
+            let (bearing, rssi) = oort_api::prelude::radar_internal::warning_indices(i);
+            state.set(bearing, warning.bearing);
+            state.set(rssi, warning.rssi);
+        }
     } else if let Some(target) = sim.ship(handle).data().target.as_ref() {
         state.set(SystemState::RadarContactFound, 1.0);
         state.set(SystemState::RadarContactPositionX, target.position.x);
@@ -623,6 +833,12 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
         );
         state.set(SystemState::Health, data.health);
         state.set(SystemState::Fuel, data.fuel.unwrap_or(f64::INFINITY));
+        state.set(
+            SystemState::Energy,
+            data.energy
+                .as_ref()
+                .map_or(f64::INFINITY, |energy| energy.remaining),
+        );
     }
 
     for (i, radio) in sim.ship(handle).data().radios.iter().enumerate() {
@@ -682,11 +898,12 @@ fn apply_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut Loca
         }
     }
 
+    let world_size = sim.world_size();
     if let Some(radar) = sim.ship_mut(handle).data_mut().radar.as_mut() {
         radar.set_heading(state.get(SystemState::RadarHeading));
         radar.set_width(state.get(SystemState::RadarWidth));
-        radar.set_min_distance(state.get(SystemState::RadarMinDistance));
-        radar.set_max_distance(state.get(SystemState::RadarMaxDistance));
+        radar.set_min_distance(state.get(SystemState::RadarMinDistance), world_size);
+        radar.set_max_distance(state.get(SystemState::RadarMaxDistance), world_size);
         radar.set_ecm_mode(translate_ecm_mode(state.get(SystemState::RadarEcmMode)));
     }
 
@@ -793,6 +1010,7 @@ fn validate_texts(texts: &[Text]) -> bool {
 
 #[cfg(feature = "precompile")]
 pub fn precompile(wasm: &[u8]) -> Result<Code, Error> {
+    abi::validate(wasm)?;
     let wasm = wasm_submemory::rewrite(wasm, SUBMEMORY_SIZE)?;
     let wasm = limiter::rewrite(&wasm)?;
     let store = Store::default();
@@ -0,0 +1,169 @@
+//! Runs ship code compiled into the host binary instead of WASM.
+//!
+//! This is meant for testing AIs with `cargo test`. The code uses the same
+//! oort_api globals as it would in WASM, so only one ship can run at a time
+//! across all simulations in the process.
+use super::{
+    apply_system_state, crash, emit_lines, emit_texts, generate_system_state, initial_system_state,
+    tick_crashed, Environment, Error, LocalSystemState, TeamController,
+};
+use crate::ship::ShipHandle;
+use crate::simulation::Simulation;
+use oort_api::rng_state::{self, RngState};
+use oort_api::SystemState;
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::sync::Mutex;
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+type ShipTick = Box<dyn FnMut() + Send>;
+type ShipFactory = Box<dyn Fn() -> ShipTick + Send>;
+
+struct NativeShipController {
+    /// Created on the first tick, so that the constructor can use the API.
+    tick: Option<ShipTick>,
+    state: LocalSystemState,
+    rng: Option<RngState>,
+}
+
+pub struct NativeTeamController {
+    new_ship: ShipFactory,
+    ship_controllers: HashMap<ShipHandle, NativeShipController>,
+    next_id: u32,
+    environment: Environment,
+}
+
+impl NativeTeamController {
+    /// Creates a controller from a ship type's constructor and tick method,
+    /// e.g. `NativeTeamController::create(Ship::new, Ship::tick)`.
+    pub fn create<S: Send + 'static>(
+        new: fn() -> S,
+        tick: fn(&mut S),
+    ) -> Box<NativeTeamController> {
+        Box::new(NativeTeamController {
+            new_ship: Box::new(move || {
+                let mut ship = new();
+                Box::new(move || tick(&mut ship))
+            }),
+            ship_controllers: HashMap::new(),
+            next_id: 1,
+            environment: Environment::new(),
+        })
+    }
+
+    fn tick_ship(&mut self, sim: &mut Simulation, handle: ShipHandle) -> Result<(), Error> {
+        let ship_controller = self.ship_controllers.get_mut(&handle).unwrap();
+        let state = &mut ship_controller.state;
+        generate_system_state(sim, handle, state);
+
+        let (result, debug_text, lines, texts) = {
+            let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            unsafe {
+                let system_state = &mut *ptr::addr_of_mut!(oort_api::sys::SYSTEM_STATE);
+                system_state[..SystemState::Size as usize].copy_from_slice(&state.state);
+                write_environment(&self.environment)?;
+                rng_state::set(ship_controller.rng.take().unwrap_or_else(RngState::new));
+            }
+            oort_api::dbg::reset();
+
+            let new_ship = &self.new_ship;
+            let tick = &mut ship_controller.tick;
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                tick.get_or_insert_with(|| new_ship())();
+            }));
+
+            unsafe {
+                let system_state = &*ptr::addr_of!(oort_api::sys::SYSTEM_STATE);
+                state
+                    .state
+                    .copy_from_slice(&system_state[..SystemState::Size as usize]);
+                ship_controller.rng = Some(rng_state::get().clone());
+            }
+            let (debug_text, lines, texts) = oort_api::dbg::take();
+            (result, debug_text, lines, texts)
+        };
+
+        if let Err(payload) = result {
+            let msg = payload
+                .downcast_ref::<&str>()
+                .map(|x| x.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            return Err(Error {
+                msg: format!("ship panicked at '{}'", msg),
+            });
+        }
+
+        apply_system_state(sim, handle, state);
+        if !debug_text.is_empty() {
+            sim.emit_debug_text(handle, debug_text);
+        }
+        emit_lines(sim, handle, &lines);
+        emit_texts(sim, handle, &texts);
+        Ok(())
+    }
+}
+
+impl TeamController for NativeTeamController {
+    fn add_ship(&mut self, handle: ShipHandle, sim: &Simulation) -> Result<(), Error> {
+        let state = initial_system_state(sim, handle, self.next_id);
+        self.next_id += 1;
+        self.ship_controllers.insert(
+            handle,
+            NativeShipController {
+                tick: None,
+                state,
+                rng: None,
+            },
+        );
+        Ok(())
+    }
+
+    fn remove_ship(&mut self, handle: ShipHandle) {
+        self.ship_controllers.remove(&handle);
+    }
+
+    fn tick(&mut self, sim: &mut Simulation) {
+        let mut handles: Vec<_> = self.ship_controllers.keys().cloned().collect();
+        handles.sort_by_key(|x| x.0);
+
+        for handle in handles {
+            if tick_crashed(sim, handle) {
+                continue;
+            }
+            if let Err(e) = self.tick_ship(sim, handle) {
+                crash(sim, handle, e);
+            }
+        }
+    }
+
+    fn cpu_usage(&self) -> Option<f64> {
+        None
+    }
+
+    fn update_environment(&mut self, environment: &Environment) -> Result<(), Error> {
+        self.environment = environment.clone();
+        Ok(())
+    }
+}
+
+/// Must be called with LOCK held.
+unsafe fn write_environment(environment: &Environment) -> Result<(), Error> {
+    let environment_string = environment
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<String>>()
+        .join("\n");
+    // Leave room for the null terminator.
+    if environment_string.len() >= oort_api::MAX_ENVIRONMENT_SIZE {
+        return Err(Error {
+            msg: "environment too large".to_string(),
+        });
+    }
+    let buffer = &mut *ptr::addr_of_mut!(oort_api::sys::ENVIRONMENT);
+    buffer[..environment_string.len()].copy_from_slice(environment_string.as_bytes());
+    buffer[environment_string.len()] = 0;
+    Ok(())
+}
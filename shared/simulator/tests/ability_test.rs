@@ -1,8 +1,9 @@
 use nalgebra::vector;
 use oort_api::Ability;
 use oort_simulator::ship;
-use oort_simulator::ship::{cruiser, fighter, frigate, torpedo, ShipClass};
+use oort_simulator::ship::{cruiser, fighter, frigate, missile, torpedo, ShipClass, ShipData};
 use oort_simulator::simulation::{self, Code, PHYSICS_TICK_LENGTH};
+use std::f64::consts::TAU;
 use test_log::test;
 
 #[test]
@@ -127,3 +128,117 @@ fn test_shield() {
     assert_ne!(sim.ship(ship0).data().health, frigate(0).health);
     assert_eq!(sim.ship(ship1).data().health, cruiser(1).health);
 }
+
+#[test]
+fn test_cloak() {
+    let count_detections = |cloak: bool| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            fighter(0),
+        );
+        let ship1 = ship::create(
+            &mut sim,
+            vector![60e3, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ShipData {
+                abilities: vec![ship::cloak()],
+                ..fighter(1)
+            },
+        );
+        sim.ship_mut(ship0).radar_mut().unwrap().width = TAU / 360.0;
+        if cloak {
+            sim.ship_mut(ship1).activate_ability(Ability::Cloak);
+        }
+        (0..100)
+            .filter(|_| {
+                sim.step();
+                sim.ship(ship0).radar().unwrap().scan().is_some()
+            })
+            .count()
+    };
+
+    assert!(count_detections(false) > 50);
+    assert!(count_detections(true) < 10);
+}
+
+#[test]
+fn test_point_defense() {
+    let mut sim = simulation::Simulation::new("ability_test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        frigate(0),
+    );
+    let near_missile = ship::create(
+        &mut sim,
+        vector![500.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        missile(1),
+    );
+    let far_missile = ship::create(
+        &mut sim,
+        vector![0.0, 1500.0],
+        vector![0.0, 0.0],
+        0.0,
+        missile(1),
+    );
+    let friendly_missile = ship::create(
+        &mut sim,
+        vector![-500.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        missile(0),
+    );
+
+    // Granted to frigates by the scenario.
+    sim.ship_mut(ship0).activate_ability(Ability::PointDefense);
+    assert!(sim.ship(ship0).is_ability_active(Ability::PointDefense));
+
+    for _ in 0..10 {
+        sim.step();
+    }
+
+    assert!(!sim.ships.contains(near_missile));
+    assert!(sim.ships.contains(far_missile));
+    assert!(sim.ships.contains(friendly_missile));
+}
+
+#[test]
+fn test_tractor_beam() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        ShipData {
+            abilities: vec![ship::tractor_beam()],
+            ..frigate(0)
+        },
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    sim.ship_mut(ship0).radar_mut().unwrap().heading = 0.0;
+
+    sim.ship_mut(ship0).activate_ability(Ability::TractorBeam);
+    for _ in 0..10 {
+        sim.step();
+    }
+
+    assert!(sim.ship(ship1).velocity().x < 0.0);
+    assert!(sim.ship(ship0).velocity().x > 0.0);
+    assert!(sim.ship(ship1).velocity().norm() > sim.ship(ship0).velocity().norm());
+}
@@ -0,0 +1,135 @@
+use nalgebra::vector;
+use oort_api::Ability;
+use oort_simulator::ship::{self, cruiser, fighter, Energy, ShipData};
+use oort_simulator::simulation::{Code, Simulation, PHYSICS_TICK_LENGTH};
+use test_log::test;
+
+fn energy(sim: &Simulation, handle: ship::ShipHandle) -> f64 {
+    sim.ship(handle).data().energy.as_ref().unwrap().remaining
+}
+
+#[test]
+fn test_no_energy_budget() {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    assert!(sim.ship(ship0).data().energy.is_none());
+    sim.ship_mut(ship0).fire(0);
+    assert!(!sim.bullets.is_empty());
+}
+
+#[test]
+fn test_weapons() {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        ShipData {
+            energy: Some(Energy {
+                weapon_cost: 10.0,
+                ..Energy::new(15.0, 0.0)
+            }),
+            ..fighter(0)
+        },
+    );
+
+    sim.ship_mut(ship0).fire(0);
+    let num_bullets = sim.bullets.len();
+    assert!(num_bullets > 0);
+    assert_eq!(energy(&sim, ship0), 5.0);
+
+    for _ in 0..60 {
+        sim.step();
+    }
+    sim.ship_mut(ship0).fire(0);
+    assert_eq!(sim.ship(ship0).data().guns[0].reload_ticks_remaining, 0);
+    assert_eq!(energy(&sim, ship0), 5.0);
+}
+
+#[test]
+fn test_regeneration() {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        ShipData {
+            energy: Some(Energy {
+                remaining: 0.0,
+                ..Energy::new(100.0, 60.0)
+            }),
+            ..fighter(0)
+        },
+    );
+
+    sim.step();
+    approx::assert_abs_diff_eq!(
+        energy(&sim, ship0),
+        60.0 * PHYSICS_TICK_LENGTH,
+        epsilon = 1e-9
+    );
+
+    for _ in 0..200 {
+        sim.step();
+    }
+    assert_eq!(energy(&sim, ship0), 100.0);
+}
+
+#[test]
+fn test_radar() {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        ShipData {
+            energy: Some(Energy {
+                radar_cost: 60.0,
+                ..Energy::new(3.5, 0.0)
+            }),
+            ..fighter(0)
+        },
+    );
+    ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        ship::target(1),
+    );
+
+    for _ in 0..3 {
+        sim.step();
+        assert!(sim.ship(ship0).radar().unwrap().scan().is_some());
+    }
+    sim.step();
+    assert!(sim.ship(ship0).radar().unwrap().scan().is_none());
+    approx::assert_abs_diff_eq!(energy(&sim, ship0), 0.5, epsilon = 1e-9);
+}
+
+#[test]
+fn test_shield() {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    let mut data = cruiser(0);
+    data.energy = Some(Energy::new(35.0, 0.0));
+    data.abilities[0].energy_cost = 60.0 * 10.0;
+    let ship0 = ship::create(&mut sim, vector![0.0, 0.0], vector![0.0, 0.0], 0.0, data);
+
+    sim.ship_mut(ship0).activate_ability(Ability::Shield);
+    assert!(sim.ship(ship0).is_ability_active(Ability::Shield));
+    for _ in 0..3 {
+        sim.step();
+        assert!(sim.ship(ship0).is_ability_active(Ability::Shield));
+    }
+    sim.step();
+    assert!(!sim.ship(ship0).is_ability_active(Ability::Shield));
+}
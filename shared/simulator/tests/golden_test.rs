@@ -1,5 +1,6 @@
 use oort_simulator::scenario;
 use oort_simulator::simulation;
+use oort_simulator::vm;
 use test_log::test;
 
 fn run(scenario_name: &str) -> u64 {
@@ -19,3 +20,29 @@ fn run(scenario_name: &str) -> u64 {
 fn test_frigate_vs_cruiser() {
     assert_eq!(run("frigate_vs_cruiser"), 2277288571665634197);
 }
+
+#[test]
+fn test_fleet_sharding() {
+    // Enough ships per team that their code is split between shards, which
+    // must not change the result.
+    let hash = |max_shards: usize| {
+        let scenario_name = "fleet";
+        let codes = scenario::load(scenario_name).solution_codes();
+        let mut sim = simulation::Simulation::with_team_controllers(scenario_name, 0, |sim| {
+            for (team, code) in codes.iter().enumerate() {
+                if matches!(code, simulation::Code::None) {
+                    continue;
+                }
+                let compiled = vm::CompiledCode::new(code).unwrap();
+                let mut team_ctrl = vm::WasmTeamController::from_compiled(&compiled).unwrap();
+                team_ctrl.set_max_shards(max_shards);
+                sim.set_team_controller(team as i32, team_ctrl);
+            }
+        });
+        for _ in 0..300 {
+            sim.step();
+        }
+        sim.hash()
+    };
+    assert_eq!(hash(1), hash(8));
+}
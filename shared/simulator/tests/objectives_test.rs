@@ -0,0 +1,218 @@
+use nalgebra::{point, vector};
+use oort_simulator::scenario::objectives::{
+    CaptureZone, Destroy, Escort, ObjectiveState, Objectives, Survive, ZoneControl,
+};
+use oort_simulator::scenario::{self, Status};
+use oort_simulator::ship::{self, fighter, target};
+use oort_simulator::simulation::{self, Code, Simulation};
+use test_log::test;
+
+fn new_sim() -> Box<Simulation> {
+    simulation::Simulation::new("test", 0, &[Code::None, Code::None])
+}
+
+fn run(sim: &mut Simulation, objectives: &mut Objectives, ticks: u32) {
+    for _ in 0..ticks {
+        sim.step();
+        objectives.tick(sim);
+    }
+}
+
+#[test]
+fn test_capture_zone() {
+    let mut sim = new_sim();
+    ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let mut objectives = Objectives::new().with(CaptureZone::new(0, point![0.0, 0.0], 500.0, 1.0));
+
+    run(&mut sim, &mut objectives, 30);
+    let progress = objectives.snapshot()[0].progress;
+    assert!(progress > 0.4 && progress < 0.6, "progress: {}", progress);
+    assert_eq!(objectives.status(), Status::Running);
+
+    run(&mut sim, &mut objectives, 31);
+    assert_eq!(objectives.snapshot()[0].state, ObjectiveState::Complete);
+    assert_eq!(objectives.status(), Status::Victory { team: 0 });
+}
+
+#[test]
+fn test_capture_zone_contested() {
+    let mut sim = new_sim();
+    ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    ship::create(
+        &mut sim,
+        vector![100.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    let mut objectives = Objectives::new().with(CaptureZone::new(0, point![0.0, 0.0], 500.0, 1.0));
+
+    run(&mut sim, &mut objectives, 120);
+    assert_eq!(objectives.snapshot()[0].progress, 0.0);
+    assert_eq!(objectives.status(), Status::Running);
+}
+
+#[test]
+fn test_escort() {
+    let mut sim = new_sim();
+    let vip = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![100.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let mut objectives = Objectives::new().with(Escort::new(0, vip, point![1000.0, 0.0], 100.0));
+
+    run(&mut sim, &mut objectives, 60);
+    let progress = objectives.snapshot()[0].progress;
+    assert!(progress > 0.0 && progress < 1.0, "progress: {}", progress);
+
+    run(&mut sim, &mut objectives, 600);
+    assert_eq!(objectives.status(), Status::Victory { team: 0 });
+}
+
+#[test]
+fn test_escort_vip_destroyed() {
+    let mut sim = new_sim();
+    let vip = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let mut objectives = Objectives::new().with(Escort::new(0, vip, point![1000.0, 0.0], 100.0));
+
+    run(&mut sim, &mut objectives, 10);
+    sim.ship_mut(vip).explode();
+    run(&mut sim, &mut objectives, 1);
+    assert_eq!(objectives.snapshot()[0].state, ObjectiveState::Failed);
+    assert_eq!(objectives.status(), Status::Failed);
+}
+
+#[test]
+fn test_survive_and_destroy() {
+    let mut sim = new_sim();
+    ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let enemy = ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        target(1),
+    );
+    let mut objectives = Objectives::new()
+        .with(Survive::new(0, 2.0))
+        .with(Destroy::new(0, enemy));
+
+    run(&mut sim, &mut objectives, 60);
+    let snapshot = objectives.snapshot();
+    assert!(snapshot[0].progress > 0.4 && snapshot[0].progress < 0.6);
+    assert_eq!(snapshot[1].progress, 0.0);
+
+    sim.ship_mut(enemy).explode();
+    run(&mut sim, &mut objectives, 1);
+    assert_eq!(objectives.snapshot()[1].state, ObjectiveState::Complete);
+    assert_eq!(objectives.status(), Status::Running);
+
+    run(&mut sim, &mut objectives, 60);
+    assert_eq!(objectives.snapshot()[0].state, ObjectiveState::Complete);
+    assert_eq!(objectives.status(), Status::Victory { team: 0 });
+}
+
+#[test]
+fn test_zone_control() {
+    let mut sim = new_sim();
+    ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let listener = ship::create(
+        &mut sim,
+        vector![5000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    sim.ship_mut(listener).radio_mut(0).unwrap().set_channel(9);
+    let mut zone_control = ZoneControl::new(&[0, 1], 1.0, 10000);
+    zone_control.add_zone(&mut sim, point![0.0, 0.0], 500.0, None, 9);
+
+    for _ in 0..30 {
+        sim.step();
+        zone_control.tick(&mut sim);
+    }
+    assert_eq!(zone_control.status(&sim), Status::Running);
+    assert_eq!(
+        sim.ship(listener).radio(0).unwrap().get_received(),
+        Some([0.0, 0.0, 0.0, 0.0])
+    );
+
+    for _ in 0..31 {
+        sim.step();
+        zone_control.tick(&mut sim);
+    }
+    assert_eq!(zone_control.status(&sim), Status::Victory { team: 0 });
+    assert_eq!(zone_control.scores()[&1], 0.0);
+}
+
+#[test]
+fn test_zone_control_simultaneous_target() {
+    let mut sim = new_sim();
+    for (team, x) in [(0, 0.0), (1, 5000.0)] {
+        ship::create(
+            &mut sim,
+            vector![x, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            fighter(team),
+        );
+    }
+    let mut zone_control = ZoneControl::new(&[0, 1], 1.0, 10000);
+    zone_control.add_zone(&mut sim, point![0.0, 0.0], 500.0, None, 9);
+    zone_control.add_zone(&mut sim, point![5000.0, 0.0], 500.0, None, 10);
+
+    for _ in 0..61 {
+        sim.step();
+        zone_control.tick(&mut sim);
+    }
+    assert_eq!(zone_control.scores()[&0], zone_control.scores()[&1]);
+    assert_eq!(zone_control.status(&sim), Status::Draw);
+}
+
+#[test]
+fn test_zone_control_scenarios() {
+    for scenario_name in ["king_of_the_hill", "capture_the_flag"] {
+        let mut sim = simulation::Simulation::new(scenario_name, 0, &[Code::None, Code::None]);
+        for _ in 0..10 {
+            sim.step();
+        }
+        assert_eq!(sim.status(), scenario::Status::Running);
+        let snapshot = sim.snapshot(0);
+        assert_eq!(snapshot.scores.len(), 2);
+        assert_eq!(snapshot.objectives.len(), 2);
+        assert!(!snapshot.scenario_lines.is_empty());
+    }
+}
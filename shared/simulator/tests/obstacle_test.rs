@@ -0,0 +1,127 @@
+use nalgebra::{point, vector};
+use oort_simulator::obstacle;
+use oort_simulator::ship::{self, fighter};
+use oort_simulator::simulation::{Code, Simulation};
+use test_log::test;
+
+fn new_sim() -> Box<Simulation> {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    obstacle::create(
+        &mut sim,
+        vec![
+            point![-500.0, -500.0],
+            point![500.0, -500.0],
+            point![500.0, 500.0],
+            point![-500.0, 500.0],
+        ],
+    );
+    sim
+}
+
+#[test]
+fn test_occlusion() {
+    let sim = new_sim();
+    assert!(obstacle::is_occluded(
+        &sim,
+        point![-1000.0, 0.0],
+        point![1000.0, 0.0]
+    ));
+    assert!(!obstacle::is_occluded(
+        &sim,
+        point![-1000.0, 1000.0],
+        point![1000.0, 1000.0]
+    ));
+    assert_eq!(sim.snapshot(0).scenario_lines.len(), 4);
+}
+
+#[test]
+fn test_ship_collision() {
+    let mut sim = new_sim();
+    let handle = ship::create(
+        &mut sim,
+        vector![-1000.0, 0.0],
+        vector![500.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    for _ in 0..120 {
+        sim.step();
+    }
+    assert!(!sim.ships.contains(handle));
+}
+
+#[test]
+fn test_radio() {
+    let mut sim = new_sim();
+    let sender = ship::create(
+        &mut sim,
+        vector![-2000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let hidden = ship::create(
+        &mut sim,
+        vector![2000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let visible = ship::create(
+        &mut sim,
+        vector![-2000.0, 2000.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let msg = [1.0, 2.0, 3.0, 4.0];
+    sim.ship_mut(sender)
+        .radio_mut(0)
+        .unwrap()
+        .set_sent(Some(msg));
+    sim.step();
+    assert_eq!(sim.ship(hidden).radio(0).unwrap().get_received(), None);
+    assert_eq!(
+        sim.ship(visible).radio(0).unwrap().get_received(),
+        Some(msg)
+    );
+}
+
+#[test]
+fn test_radar() {
+    let mut sim = new_sim();
+    let emitter = ship::create(
+        &mut sim,
+        vector![-2000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    ship::create(
+        &mut sim,
+        vector![2000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    let scan = |sim: &Simulation| sim.ship(emitter).data().radar.as_ref().unwrap().scan();
+    sim.step();
+    assert!(scan(&sim).is_none());
+
+    sim.ship_mut(emitter)
+        .data_mut()
+        .radar
+        .as_mut()
+        .unwrap()
+        .set_heading(std::f64::consts::PI / 2.0);
+    let above = ship::create(
+        &mut sim,
+        vector![-2000.0, 2000.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    sim.step();
+    assert!(scan(&sim).is_some());
+    assert!(sim.ships.contains(above));
+}
@@ -0,0 +1,215 @@
+use approx::assert_abs_diff_eq;
+use nalgebra::{point, vector};
+use oort_simulator::bullet::{self, BulletData};
+use oort_simulator::obstacle;
+use oort_simulator::physics::PhysicsConfig;
+use oort_simulator::ship::{self, fighter, ShipClass};
+use oort_simulator::simulation::{Code, Simulation};
+use test_log::test;
+
+#[test]
+fn test_tick_length() {
+    let mut sim = Simulation::new("physics_test", 0, &[Code::None, Code::None]);
+    let handle = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![100.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    for _ in 0..30 {
+        sim.step();
+    }
+    assert_abs_diff_eq!(sim.time(), 1.0, epsilon = 1e-9);
+    assert_abs_diff_eq!(sim.ship(handle).position().x, 100.0, epsilon = 1e-3);
+}
+
+#[test]
+fn test_open_walls() {
+    let mut sim = Simulation::new("physics_test", 0, &[Code::None, Code::None]);
+    let edge = sim.world_size() / 2.0;
+    let handle = ship::create(
+        &mut sim,
+        vector![edge - 10.0, 0.0],
+        vector![1000.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    for _ in 0..30 {
+        sim.step();
+    }
+    assert!(sim.ship(handle).position().x > edge);
+}
+
+#[test]
+fn test_config() {
+    let sim = Simulation::new("physics_test", 0, &[Code::None, Code::None]);
+    let physics = sim.physics();
+    assert_eq!(physics.restitution(ShipClass::Fighter), 1.0);
+    assert_eq!(physics.restitution(ShipClass::Missile), 0.0);
+    assert_eq!(physics.environment()["WALLS"], "open");
+    assert_eq!(
+        physics.environment()["TICK_LENGTH"],
+        format!("{}", 1.0 / 30.0)
+    );
+
+    let default_sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    assert_eq!(default_sim.physics(), &PhysicsConfig::default());
+}
+
+#[test]
+fn test_wrap_ships() {
+    let mut sim = Simulation::new("wrap_test", 0, &[Code::None, Code::None]);
+    let edge = sim.world_size() / 2.0;
+    let handle = ship::create(
+        &mut sim,
+        vector![edge - 10.0, 0.0],
+        vector![1000.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    for _ in 0..30 {
+        sim.step();
+    }
+    let x = sim.ship(handle).position().x;
+    assert!(x < -edge + 1000.0, "x: {}", x);
+}
+
+#[test]
+fn test_wrap_radio() {
+    let mut sim = Simulation::new("wrap_test", 0, &[Code::None, Code::None]);
+    let edge = sim.world_size() / 2.0;
+    let sender = ship::create(
+        &mut sim,
+        vector![edge - 500.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let receiver = ship::create(
+        &mut sim,
+        vector![-edge + 500.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let msg = [1.0, 2.0, 3.0, 4.0];
+    sim.ship_mut(sender)
+        .radio_mut(0)
+        .unwrap()
+        .set_sent(Some(msg));
+    sim.step();
+    assert_eq!(
+        sim.ship(receiver).radio(0).unwrap().get_received(),
+        Some(msg)
+    );
+}
+
+#[test]
+fn test_wrap_radar() {
+    let mut sim = Simulation::new("wrap_test", 0, &[Code::None, Code::None]);
+    let edge = sim.world_size() / 2.0;
+    let emitter = ship::create(
+        &mut sim,
+        vector![edge - 1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    ship::create(
+        &mut sim,
+        vector![-edge + 1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    sim.step();
+    let contact = sim
+        .ship(emitter)
+        .data()
+        .radar
+        .as_ref()
+        .unwrap()
+        .scan()
+        .expect("no radar contact");
+    // The contact is reported beyond the edge, where the shortest path leads.
+    assert!(contact.position.x > edge, "contact: {:?}", contact);
+}
+
+#[test]
+fn test_wrap_bullet_hit() {
+    let mut sim = Simulation::new("wrap_test", 0, &[Code::None, Code::None]);
+    let edge = sim.world_size() / 2.0;
+    let target = ship::create(
+        &mut sim,
+        vector![-edge + 20.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    let initial_health = sim.ship(target).data().health;
+    // Fast enough to jump over the target when it wraps, so the hit has to be
+    // found before it crosses the edge.
+    bullet::create(
+        &mut sim,
+        vector![edge - 130.0, 0.0],
+        vector![6000.0, 0.0],
+        BulletData {
+            mass: 1.0,
+            team: 0,
+            ttl: 1.0,
+            color: 0xffffffff,
+        },
+    );
+    for _ in 0..10 {
+        sim.step();
+    }
+    assert!(sim.ship(target).data().health < initial_health);
+}
+
+#[test]
+fn test_wrap_radio_occlusion() {
+    let mut sim = Simulation::new("wrap_test", 0, &[Code::None, Code::None]);
+    let edge = sim.world_size() / 2.0;
+    // Only on the shortest path between the ships, which crosses the edge.
+    obstacle::create(
+        &mut sim,
+        vec![
+            point![edge - 300.0, -100.0],
+            point![edge - 200.0, -100.0],
+            point![edge - 200.0, 100.0],
+            point![edge - 300.0, 100.0],
+        ],
+    );
+    assert!(obstacle::is_occluded(
+        &sim,
+        point![edge - 500.0, 0.0],
+        point![-edge + 500.0, 0.0]
+    ));
+    assert!(obstacle::is_occluded(
+        &sim,
+        point![-edge + 500.0, 0.0],
+        point![edge - 500.0, 0.0]
+    ));
+
+    let sender = ship::create(
+        &mut sim,
+        vector![edge - 500.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let receiver = ship::create(
+        &mut sim,
+        vector![-edge + 500.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    sim.ship_mut(sender)
+        .radio_mut(0)
+        .unwrap()
+        .set_sent(Some([1.0, 2.0, 3.0, 4.0]));
+    sim.step();
+    assert_eq!(sim.ship(receiver).radio(0).unwrap().get_received(), None);
+}
@@ -0,0 +1,53 @@
+use oort_simulator::pool::SimulationPool;
+use oort_simulator::scenario;
+use oort_simulator::simulation::{self, Code};
+use test_log::test;
+
+#[test]
+fn test_matches_simulation() {
+    let scenario_name = "frigate_vs_cruiser";
+    let codes = scenario::load(scenario_name).solution_codes();
+    let mut pool = SimulationPool::new(scenario_name);
+    for code in &codes {
+        pool.add(code);
+    }
+
+    let mut sim = pool.simulation(0, &codes);
+    while sim.status() == scenario::Status::Running {
+        sim.step();
+    }
+    assert_eq!(sim.hash(), 2277288571665634197);
+}
+
+#[test]
+fn test_run_seeds() {
+    let scenario_name = "frigate_vs_cruiser";
+    let codes = scenario::load(scenario_name).solution_codes();
+    let mut pool = SimulationPool::new(scenario_name);
+    let seeds = [0, 1, 2];
+    let results = pool.run_seeds(&codes, &seeds);
+    assert_eq!(results.results.len(), seeds.len());
+
+    for (&seed, result) in seeds.iter().zip(results.results.iter()) {
+        assert_eq!(result.seed, seed);
+        let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
+        while sim.status() == scenario::Status::Running && sim.tick() < scenario::MAX_TICKS {
+            sim.step();
+        }
+        assert_eq!(result.status, sim.status());
+        assert_eq!(result.score_time, sim.score_time());
+    }
+    assert_eq!(
+        results.wins(0).len() + results.wins(1).len() + results.draws().len(),
+        seeds.len()
+    );
+}
+
+#[test]
+fn test_compile_error() {
+    let mut pool = SimulationPool::new("frigate_vs_cruiser");
+    let codes = [Code::Wasm(vec![1, 2, 3]), Code::None];
+    pool.add(&codes[0]);
+    let sim = pool.simulation(0, &codes);
+    assert_eq!(sim.events().errors.len(), 1);
+}
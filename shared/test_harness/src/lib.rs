@@ -0,0 +1,204 @@
+//! Runs Oort AI code natively in a simulation, so it can be tested with
+//! `cargo test` instead of in the browser.
+//!
+//! ```ignore
+//! use oort_api::prelude::*;
+//! use oort_test_harness::{ship, Harness};
+//!
+//! #[test]
+//! fn test_accelerate() {
+//!     let mut harness = Harness::empty(Ship::new, Ship::tick);
+//!     let handle = harness.spawn(ship::fighter(0), vec2(0.0, 0.0), vec2(0.0, 0.0), 0.0);
+//!     harness.run(60);
+//!     assert!(harness.position(handle).x > 0.0);
+//! }
+//! ```
+//!
+//! Ships on team 0 run the given code. Other teams run the scenario's code.
+//! Ships share the `oort_api` globals, so ticks are serialized across all
+//! harnesses in the process.
+use nalgebra::vector;
+use oort_api::Vec2;
+use oort_simulator::radar::{RadarWarning, ScanResult};
+use oort_simulator::scenario::{self, Status};
+use oort_simulator::ship::{ShipData, ShipHandle};
+use oort_simulator::simulation::{Code, Simulation};
+use oort_simulator::vm::native::NativeTeamController;
+use std::collections::BTreeMap;
+
+pub use oort_simulator::ship;
+
+/// The team whose ships run the code under test.
+pub const TEAM: i32 = 0;
+
+pub struct Harness {
+    sim: Box<Simulation>,
+    hits: Vec<(u64, f64)>,
+}
+
+impl Harness {
+    /// Runs the named scenario with the code under test controlling team 0.
+    pub fn scenario<S: Send + 'static>(
+        scenario_name: &str,
+        seed: u32,
+        new: fn() -> S,
+        tick: fn(&mut S),
+    ) -> Harness {
+        let mut codes = scenario::load(scenario_name).initial_code();
+        codes[TEAM as usize] = Code::None;
+        let mut sim = Simulation::new(scenario_name, seed, &codes);
+        sim.set_team_controller(TEAM, NativeTeamController::create(new, tick));
+        sim.update_environment(TEAM, BTreeMap::new());
+        Harness {
+            sim,
+            hits: Vec::new(),
+        }
+    }
+
+    /// Runs an empty scenario. Use [Harness::spawn] to add ships.
+    pub fn empty<S: Send + 'static>(new: fn() -> S, tick: fn(&mut S)) -> Harness {
+        Self::scenario("test", 0, new, tick)
+    }
+
+    /// Sets environment variables readable with `oort_api::sys::getenv`.
+    pub fn set_environment(&mut self, environment: BTreeMap<String, String>) {
+        self.sim.update_environment(TEAM, environment);
+    }
+
+    /// Creates a ship, e.g. `harness.spawn(ship::fighter(0), ...)`. Its code
+    /// starts running on the next tick.
+    pub fn spawn(
+        &mut self,
+        data: ShipData,
+        position: Vec2,
+        velocity: Vec2,
+        heading: f64,
+    ) -> ShipHandle {
+        ship::create(
+            &mut self.sim,
+            vector![position.x, position.y],
+            vector![velocity.x, velocity.y],
+            heading,
+            data,
+        )
+    }
+
+    pub fn step(&mut self) {
+        self.sim.step();
+        self.hits.extend(self.sim.events().hits.iter().cloned());
+    }
+
+    /// Runs the given number of ticks.
+    pub fn run(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.step();
+        }
+    }
+
+    /// Runs until the predicate returns true, for at most `max_ticks`. Returns
+    /// false if the predicate never returned true.
+    pub fn run_until(
+        &mut self,
+        max_ticks: u32,
+        mut predicate: impl FnMut(&Harness) -> bool,
+    ) -> bool {
+        for _ in 0..max_ticks {
+            if predicate(self) {
+                return true;
+            }
+            self.step();
+        }
+        predicate(self)
+    }
+
+    pub fn tick(&self) -> u32 {
+        self.sim.tick()
+    }
+
+    pub fn status(&self) -> Status {
+        self.sim.status()
+    }
+
+    /// Ships that exist, in creation order.
+    pub fn ships(&self) -> Vec<ShipHandle> {
+        self.sim.ships.iter().cloned().collect()
+    }
+
+    pub fn is_alive(&self, handle: ShipHandle) -> bool {
+        self.sim.ships.contains(handle)
+    }
+
+    pub fn position(&self, handle: ShipHandle) -> Vec2 {
+        let p = self.sim.ship(handle).position();
+        Vec2::new(p.x, p.y)
+    }
+
+    pub fn velocity(&self, handle: ShipHandle) -> Vec2 {
+        let v = self.sim.ship(handle).velocity();
+        Vec2::new(v.x, v.y)
+    }
+
+    pub fn heading(&self, handle: ShipHandle) -> f64 {
+        self.sim.ship(handle).heading()
+    }
+
+    pub fn health(&self, handle: ShipHandle) -> f64 {
+        self.sim.ship(handle).data().health
+    }
+
+    /// Damage from each bullet that hit the ship, since the harness was created.
+    pub fn hits(&self, handle: ShipHandle) -> Vec<f64> {
+        self.hits
+            .iter()
+            .filter(|(id, _)| *id == handle.into())
+            .map(|(_, damage)| *damage)
+            .collect()
+    }
+
+    /// The contact the ship's radar found on the last tick.
+    pub fn radar_contact(&self, handle: ShipHandle) -> Option<ScanResult> {
+        self.sim.ship(handle).radar().and_then(|radar| radar.result)
+    }
+
+    /// Enemy radars that illuminated the ship on the last tick.
+    pub fn radar_warnings(&self, handle: ShipHandle) -> Vec<RadarWarning> {
+        self.sim
+            .ship(handle)
+            .radar()
+            .map(|radar| radar.warnings().to_vec())
+            .unwrap_or_default()
+    }
+
+    /// The message the ship sent on the last tick, which is delivered on the
+    /// next tick.
+    pub fn radio_sent(&self, handle: ShipHandle, radio: usize) -> Option<oort_api::Message> {
+        self.sim.ship(handle).radio(radio)?.get_sent()
+    }
+
+    /// The message the ship received on the last tick.
+    pub fn radio_received(&self, handle: ShipHandle, radio: usize) -> Option<oort_api::Message> {
+        self.sim.ship(handle).radio(radio)?.get_received()
+    }
+
+    /// Debug text from the last tick, including crash messages.
+    pub fn debug_text(&self, handle: ShipHandle) -> String {
+        self.sim
+            .events()
+            .debug_text
+            .get(&handle.into())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn crash_message(&self, handle: ShipHandle) -> Option<String> {
+        self.sim.ship(handle).data().crash_message.clone()
+    }
+
+    pub fn sim(&self) -> &Simulation {
+        &self.sim
+    }
+
+    pub fn sim_mut(&mut self) -> &mut Simulation {
+        &mut self.sim
+    }
+}
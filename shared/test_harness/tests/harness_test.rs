@@ -0,0 +1,113 @@
+use oort_api::prelude::*;
+use oort_test_harness::{ship, Harness};
+
+struct Thruster {}
+
+impl Thruster {
+    fn new() -> Self {
+        Self {}
+    }
+
+    fn tick(&mut self) {
+        accelerate(vec2(100.0, 0.0));
+        debug!("id: {}", id());
+    }
+}
+
+#[test]
+fn test_accelerate() {
+    let mut harness = Harness::empty(Thruster::new, Thruster::tick);
+    let handle = harness.spawn(ship::fighter(0), vec2(0.0, 0.0), vec2(0.0, 0.0), 0.0);
+    harness.run(60);
+    assert!(harness.position(handle).x > 0.0);
+    assert!(harness.velocity(handle).x > 0.0);
+    assert_eq!(harness.debug_text(handle), "id: 1\n");
+}
+
+struct Gunner {}
+
+impl Gunner {
+    fn new() -> Self {
+        Self {}
+    }
+
+    fn tick(&mut self) {
+        set_radar_heading(0.0);
+        fire(0);
+    }
+}
+
+#[test]
+fn test_radar_and_hits() {
+    let mut harness = Harness::empty(Gunner::new, Gunner::tick);
+    let shooter = harness.spawn(ship::fighter(0), vec2(0.0, 0.0), vec2(0.0, 0.0), 0.0);
+    let target = harness.spawn(ship::target(1), vec2(500.0, 0.0), vec2(0.0, 0.0), 0.0);
+    assert!(harness.run_until(60, |h| h.radar_contact(shooter).is_some()));
+    assert!(harness.run_until(120, |h| !h.hits(target).is_empty()));
+    assert!(harness.hits(shooter).is_empty());
+}
+
+struct Radio {}
+
+impl Radio {
+    fn new() -> Self {
+        Self {}
+    }
+
+    fn tick(&mut self) {
+        set_radio_channel(2);
+        if id() == 1 {
+            send([1.0, 2.0, 3.0, 4.0]);
+        } else if let Some(msg) = receive() {
+            debug!("received: {:?}", msg);
+        }
+    }
+}
+
+#[test]
+fn test_radio() {
+    let mut harness = Harness::empty(Radio::new, Radio::tick);
+    let sender = harness.spawn(ship::fighter(0), vec2(0.0, 0.0), vec2(0.0, 0.0), 0.0);
+    let receiver = harness.spawn(ship::fighter(0), vec2(1000.0, 0.0), vec2(0.0, 0.0), 0.0);
+    harness.step();
+    assert_eq!(harness.radio_sent(sender, 0), Some([1.0, 2.0, 3.0, 4.0]));
+    harness.step();
+    assert_eq!(
+        harness.radio_received(receiver, 0),
+        Some([1.0, 2.0, 3.0, 4.0])
+    );
+    assert!(harness.debug_text(receiver).contains("received"));
+}
+
+struct Panicker {}
+
+impl Panicker {
+    fn new() -> Self {
+        Self {}
+    }
+
+    fn tick(&mut self) {
+        panic!("oops");
+    }
+}
+
+#[test]
+fn test_panic() {
+    let mut harness = Harness::empty(Panicker::new, Panicker::tick);
+    let handle = harness.spawn(ship::fighter(0), vec2(0.0, 0.0), vec2(0.0, 0.0), 0.0);
+    harness.step();
+    assert_eq!(
+        harness.crash_message(handle),
+        Some("ship panicked at 'oops'".to_string())
+    );
+}
+
+#[test]
+fn test_scenario() {
+    let mut harness = Harness::scenario("basic", 0, Thruster::new, Thruster::tick);
+    let ships = harness.ships();
+    assert_eq!(ships.len(), 2);
+    let start = harness.position(ships[0]);
+    harness.run(10);
+    assert!(harness.position(ships[0]).x > start.x);
+}
@@ -1,6 +1,7 @@
 use clap::Parser;
+use oort_simulator::pool::SimulationPool;
+use oort_simulator::scenario;
 use oort_simulator::simulation::Code;
-use oort_simulator::{scenario, simulation};
 use oort_tools::AI;
 use rayon::prelude::*;
 use std::default::Default;
@@ -117,28 +118,19 @@ struct Results {
 }
 
 fn run_simulations(scenario_name: &str, codes: Vec<Code>, rounds: u32) -> Results {
-    let seed_statuses: Vec<(u32, (scenario::Status, f64))> = (0..rounds)
-        .into_par_iter()
-        .map(|seed| (seed, run_simulation(scenario_name, seed, codes.clone())))
-        .collect();
+    let mut pool = SimulationPool::new(scenario_name);
+    let seeds: Vec<u32> = (0..rounds).collect();
+    let batch = pool.run_seeds(&codes, &seeds);
     let mut results: Results = Default::default();
-    for (seed, (status, time)) in seed_statuses {
-        match status {
-            scenario::Status::Victory { team: 0 } => results.team0_wins.push(seed),
-            scenario::Status::Victory { team: 1 } => results.team1_wins.push(seed),
-            scenario::Status::Draw => results.draws.push(seed),
-            scenario::Status::Failed => results.team1_wins.push(seed),
+    for result in batch.results {
+        match result.status {
+            scenario::Status::Victory { team: 0 } => results.team0_wins.push(result.seed),
+            scenario::Status::Victory { team: 1 } => results.team1_wins.push(result.seed),
+            scenario::Status::Draw => results.draws.push(result.seed),
+            scenario::Status::Failed => results.team1_wins.push(result.seed),
             _ => unreachable!(),
         }
-        results.times.push(time);
+        results.times.push(result.score_time);
     }
     results
 }
-
-fn run_simulation(scenario_name: &str, seed: u32, codes: Vec<Code>) -> (scenario::Status, f64) {
-    let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
-    while sim.status() == scenario::Status::Running && sim.tick() < scenario::MAX_TICKS {
-        sim.step();
-    }
-    (sim.status(), sim.score_time())
-}
@@ -32,6 +32,14 @@ fn main() -> Result<()> {
         .filter(|x| !["lib.rs", "mod.rs"].contains(&x.file_name().unwrap().to_str().unwrap()))
         .collect();
 
+    // Initial code in the other languages is only included as source.
+    let mut other_paths = vec![];
+    for extension in ["c", "cpp", "zig"] {
+        other_paths
+            .extend(glob(&format!("{}/**/*.{}", args.input, extension))?.map(|x| x.unwrap()));
+    }
+    other_paths.sort();
+
     let results: Vec<_> = paths
         .par_iter()
         .map(
@@ -69,6 +77,16 @@ fn main() -> Result<()> {
         ar.append_data(&mut header, path, data).unwrap();
     }
 
+    for path in other_paths.iter() {
+        let data = std::fs::read(path)?;
+        let path = path.strip_prefix(&args.input)?;
+        let mut header = Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o700);
+        header.set_cksum();
+        ar.append_data(&mut header, path, &data[..]).unwrap();
+    }
+
     for r in results.iter() {
         let (path, _, wasm) = r.as_ref().unwrap();
         let mut path = path.strip_prefix(&args.input)?.to_path_buf();
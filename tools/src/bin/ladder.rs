@@ -0,0 +1,549 @@
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use firestore::*;
+use oort_proto::{LadderEntrant, LadderHistoryEntry, TournamentSubmission};
+use oort_simulator::simulation::Code;
+use oort_simulator::{scenario, simulation};
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use skillratings::{
+    glicko2::{glicko2, Glicko2Config, Glicko2Rating},
+    Outcomes,
+};
+use std::collections::{BTreeMap, HashMap};
+
+const COLLECTION_NAME: &str = "ladder";
+
+#[derive(Parser, Debug)]
+#[clap()]
+struct Arguments {
+    #[clap(short, long, value_parser, default_value_t = String::from("oort-319301"))]
+    project_id: String,
+
+    #[clap(subcommand)]
+    cmd: SubCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum SubCommand {
+    /// Enter new and updated tournament submissions into the ladder.
+    Update {
+        scenario: String,
+
+        /// Number of nearest-rated opponents each challenger plays.
+        #[clap(short, long, default_value_t = 8)]
+        opponents: usize,
+
+        /// Seeds played against each opponent, from both sides.
+        #[clap(short, long, default_value_t = 10)]
+        rounds: u32,
+
+        #[clap(short, long)]
+        dry_run: bool,
+    },
+    Show {
+        scenario: String,
+    },
+    History {
+        scenario: String,
+        username: String,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("ladder=info"))
+        .init();
+
+    let args = Arguments::parse();
+    match args.cmd {
+        SubCommand::Update {
+            scenario,
+            opponents,
+            rounds,
+            dry_run,
+        } => cmd_update(&args.project_id, &scenario, opponents, rounds, dry_run).await,
+        SubCommand::Show { scenario } => cmd_show(&args.project_id, &scenario).await,
+        SubCommand::History { scenario, username } => {
+            cmd_history(&args.project_id, &scenario, &username).await
+        }
+    }
+}
+
+struct Match {
+    challenger: usize,
+    opponent: usize,
+    seed: u32,
+    // Whether the challenger plays as team 0.
+    challenger_first: bool,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Record {
+    wins: u32,
+    losses: u32,
+    draws: u32,
+}
+
+async fn cmd_update(
+    project_id: &str,
+    scenario_name: &str,
+    num_opponents: usize,
+    rounds: u32,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    let db = FirestoreDb::new(project_id).await?;
+    scenario::load_safe(scenario_name).expect("Unknown scenario");
+
+    let mut entrants = get_ladder(&db, scenario_name).await?;
+    let submissions = get_submissions(&db, scenario_name).await?;
+
+    // Add new entrants and reset the uncertainty of entrants with new code.
+    let mut challengers: Vec<usize> = vec![];
+    for submission in submissions.values() {
+        match entrants.iter().position(|x| x.userid == submission.userid) {
+            Some(index) => {
+                let entrant = &mut entrants[index];
+                if entrant.submission_timestamp < submission.timestamp {
+                    log::info!("Updated entrant {:?}", entrant.username);
+                    entrant.username = submission.username.clone();
+                    entrant.submission_timestamp = submission.timestamp;
+                    entrant.deviation = Glicko2Rating::default().deviation;
+                    challengers.push(index);
+                }
+            }
+            None => {
+                log::info!("New entrant {:?}", submission.username);
+                let rating = Glicko2Rating::default();
+                entrants.push(LadderEntrant {
+                    scenario_name: scenario_name.to_string(),
+                    userid: submission.userid.clone(),
+                    username: submission.username.clone(),
+                    submission_timestamp: submission.timestamp,
+                    rating: rating.rating,
+                    deviation: rating.deviation,
+                    volatility: rating.volatility,
+                    history: vec![],
+                });
+                challengers.push(entrants.len() - 1);
+            }
+        }
+    }
+
+    if challengers.is_empty() {
+        log::info!("No new submissions");
+        return Ok(());
+    }
+
+    let matches = schedule(&entrants, &challengers, num_opponents, rounds);
+
+    let mut compiler = oort_compiler::Compiler::new();
+    let mut codes: HashMap<usize, Code> = HashMap::new();
+    let mut skipped: Vec<usize> = vec![];
+    for m in matches.iter() {
+        for index in [m.challenger, m.opponent] {
+            if codes.contains_key(&index) || skipped.contains(&index) {
+                continue;
+            }
+            let entrant = &entrants[index];
+            let Some(submission) = submissions.get(&entrant.userid) else {
+                log::warn!(
+                    "Missing submission for {:?}, skipping its matches",
+                    entrant.username
+                );
+                skipped.push(index);
+                continue;
+            };
+            log::info!("Compiling {:?}", entrant.username);
+            let compiled_code = compiler.compile(&submission.code).and_then(|wasm| {
+                oort_simulator::vm::precompile(&wasm).map_err(|e| anyhow::anyhow!(e.msg))
+            });
+            match compiled_code {
+                Ok(compiled_code) => {
+                    codes.insert(index, compiled_code);
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Failed to compile submission for {:?}, skipping its matches: {}",
+                        entrant.username,
+                        e
+                    );
+                    skipped.push(index);
+                }
+            }
+        }
+    }
+    let matches: Vec<Match> = matches
+        .into_iter()
+        .filter(|m| codes.contains_key(&m.challenger) && codes.contains_key(&m.opponent))
+        .collect();
+    log::info!(
+        "Scheduled {} matches for {} challengers",
+        matches.len(),
+        challengers.len()
+    );
+
+    let progress = indicatif::ProgressBar::new(matches.len() as u64);
+    let outcomes: Vec<Outcomes> = matches
+        .par_iter()
+        .map(|m| {
+            let (code0, code1) = if m.challenger_first {
+                (&codes[&m.challenger], &codes[&m.opponent])
+            } else {
+                (&codes[&m.opponent], &codes[&m.challenger])
+            };
+            let outcome = run_simulation(scenario_name, m.seed, code0, code1);
+            progress.inc(1);
+            if m.challenger_first {
+                outcome
+            } else {
+                invert(outcome)
+            }
+        })
+        .collect();
+    progress.finish_and_clear();
+
+    let records = apply_outcomes(&mut entrants, &matches, &outcomes);
+
+    let now = Utc::now();
+    for (&index, record) in records.iter() {
+        let entrant = &mut entrants[index];
+        entrant.history.push(LadderHistoryEntry {
+            timestamp: now,
+            rating: entrant.rating,
+            deviation: entrant.deviation,
+            wins: record.wins,
+            losses: record.losses,
+            draws: record.draws,
+        });
+    }
+
+    display_ladder(scenario_name, &entrants);
+
+    if !dry_run {
+        let mut changed: Vec<usize> = records.keys().copied().collect();
+        changed.extend(challengers.iter().copied());
+        changed.sort();
+        changed.dedup();
+        log::info!("Uploading {} entrants", changed.len());
+        for index in changed {
+            let entrant = &entrants[index];
+            let docid = format!("{}.{}", scenario_name, entrant.userid);
+            db.update_obj(COLLECTION_NAME, &docid, entrant, None, None, None)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Pairs each challenger with the entrants closest to it in rating.
+fn schedule(
+    entrants: &[LadderEntrant],
+    challengers: &[usize],
+    num_opponents: usize,
+    rounds: u32,
+) -> Vec<Match> {
+    let mut matches = vec![];
+    for &challenger in challengers {
+        let mut opponents: Vec<usize> = (0..entrants.len()).filter(|&x| x != challenger).collect();
+        opponents.sort_by_key(|&x| {
+            (
+                ((entrants[x].rating - entrants[challenger].rating).abs() * 1e3) as i64,
+                entrants[x].username.clone(),
+            )
+        });
+        opponents.truncate(num_opponents);
+        for opponent in opponents {
+            for round in 0..rounds {
+                let seed = match_seed(&entrants[challenger], &entrants[opponent], round);
+                for challenger_first in [true, false] {
+                    matches.push(Match {
+                        challenger,
+                        opponent,
+                        seed,
+                        challenger_first,
+                    });
+                }
+            }
+        }
+    }
+    matches
+}
+
+/// Derives the seed from the pairing and round so a ladder update can be
+/// reproduced.
+fn match_seed(challenger: &LadderEntrant, opponent: &LadderEntrant, round: u32) -> u32 {
+    let key = format!(
+        "{}:{}:{}:{}",
+        challenger.scenario_name, challenger.userid, opponent.userid, round
+    );
+    rand_seeder::Seeder::from(key)
+        .make_rng::<ChaCha8Rng>()
+        .gen()
+}
+
+/// Updates ratings from the match outcomes (from the challenger's side) and
+/// returns each entrant's record. Results are applied in schedule order so the
+/// update is deterministic.
+fn apply_outcomes(
+    entrants: &mut [LadderEntrant],
+    matches: &[Match],
+    outcomes: &[Outcomes],
+) -> BTreeMap<usize, Record> {
+    let config = Glicko2Config::new();
+    let mut records: BTreeMap<usize, Record> = BTreeMap::new();
+    for (m, outcome) in matches.iter().zip(outcomes.iter()) {
+        let (r0, r1) = glicko2(
+            &rating(&entrants[m.challenger]),
+            &rating(&entrants[m.opponent]),
+            outcome,
+            &config,
+        );
+        set_rating(&mut entrants[m.challenger], &r0);
+        set_rating(&mut entrants[m.opponent], &r1);
+        record(records.entry(m.challenger).or_default(), outcome);
+        record(records.entry(m.opponent).or_default(), &invert(*outcome));
+    }
+    records
+}
+
+fn rating(entrant: &LadderEntrant) -> Glicko2Rating {
+    Glicko2Rating {
+        rating: entrant.rating,
+        deviation: entrant.deviation,
+        volatility: entrant.volatility,
+    }
+}
+
+fn set_rating(entrant: &mut LadderEntrant, rating: &Glicko2Rating) {
+    entrant.rating = rating.rating;
+    entrant.deviation = rating.deviation;
+    entrant.volatility = rating.volatility;
+}
+
+fn invert(outcome: Outcomes) -> Outcomes {
+    match outcome {
+        Outcomes::WIN => Outcomes::LOSS,
+        Outcomes::LOSS => Outcomes::WIN,
+        Outcomes::DRAW => Outcomes::DRAW,
+    }
+}
+
+fn record(record: &mut Record, outcome: &Outcomes) {
+    match outcome {
+        Outcomes::WIN => record.wins += 1,
+        Outcomes::LOSS => record.losses += 1,
+        Outcomes::DRAW => record.draws += 1,
+    }
+}
+
+fn run_simulation(scenario_name: &str, seed: u32, code0: &Code, code1: &Code) -> Outcomes {
+    let f = move || {
+        let codes = [code0.clone(), code1.clone()];
+        let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
+        while sim.status() == scenario::Status::Running && sim.tick() < scenario::MAX_TICKS {
+            sim.step();
+        }
+        match sim.status() {
+            scenario::Status::Victory { team: 0 } => Outcomes::WIN,
+            scenario::Status::Victory { team: 1 } => Outcomes::LOSS,
+            scenario::Status::Draw => Outcomes::DRAW,
+            status => {
+                log::warn!(
+                    "Simulation with seed {} did not finish ({:?}), counting it as a draw",
+                    seed,
+                    status
+                );
+                Outcomes::DRAW
+            }
+        }
+    };
+    match ::std::panic::catch_unwind(f) {
+        Ok(x) => x,
+        Err(e) => {
+            log::error!("Simulation panicked: {:?}", e);
+            Outcomes::DRAW
+        }
+    }
+}
+
+fn display_ladder(scenario_name: &str, entrants: &[LadderEntrant]) {
+    let mut entrants: Vec<&LadderEntrant> = entrants.iter().collect();
+    entrants.sort_by_key(|x| (-x.rating * 1e6) as i64);
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    table.set_header(vec!["Rank", "Name", "Rating", "Deviation", "Updated"]);
+    for (i, entrant) in entrants.iter().enumerate() {
+        table.add_row(vec![
+            format!("{}", i + 1),
+            entrant.username.clone(),
+            format!("{:.0}", entrant.rating),
+            format!("{:.0}", entrant.deviation),
+            format_timestamp(entrant.history.last().map(|x| x.timestamp)),
+        ]);
+    }
+    println!("Scenario: {}", scenario_name);
+    println!("{table}");
+}
+
+fn format_timestamp(timestamp: Option<DateTime<Utc>>) -> String {
+    timestamp
+        .map(|x| x.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+async fn cmd_show(project_id: &str, scenario_name: &str) -> anyhow::Result<()> {
+    let db = FirestoreDb::new(project_id).await?;
+    let entrants = get_ladder(&db, scenario_name).await?;
+    display_ladder(scenario_name, &entrants);
+    Ok(())
+}
+
+async fn cmd_history(project_id: &str, scenario_name: &str, username: &str) -> anyhow::Result<()> {
+    let db = FirestoreDb::new(project_id).await?;
+    let entrants = get_ladder(&db, scenario_name).await?;
+    let Some(entrant) = entrants.iter().find(|x| x.username == username) else {
+        anyhow::bail!("No ladder entrant named {:?}", username);
+    };
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    table.set_header(vec!["Time", "Rating", "Deviation", "W", "L", "D"]);
+    for entry in entrant.history.iter() {
+        table.add_row(vec![
+            format_timestamp(Some(entry.timestamp)),
+            format!("{:.0}", entry.rating),
+            format!("{:.0}", entry.deviation),
+            format!("{}", entry.wins),
+            format!("{}", entry.losses),
+            format!("{}", entry.draws),
+        ]);
+    }
+    println!("{table}");
+    Ok(())
+}
+
+async fn get_ladder(db: &FirestoreDb, scenario_name: &str) -> anyhow::Result<Vec<LadderEntrant>> {
+    let mut entrants: Vec<LadderEntrant> = db
+        .query_obj(
+            FirestoreQueryParams::new(COLLECTION_NAME.into()).with_filter(
+                FirestoreQueryFilter::Composite(FirestoreQueryFilterComposite::new(
+                    vec![FirestoreQueryFilter::Compare(Some(
+                        FirestoreQueryFilterCompare::Equal(
+                            "scenario_name".into(),
+                            scenario_name.into(),
+                        ),
+                    ))],
+                    FirestoreQueryFilterCompositeOperator::And,
+                )),
+            ),
+        )
+        .await?;
+    entrants.sort_by_key(|x| x.userid.clone());
+    Ok(entrants)
+}
+
+/// Returns the latest tournament submission for each userid.
+async fn get_submissions(
+    db: &FirestoreDb,
+    scenario_name: &str,
+) -> anyhow::Result<BTreeMap<String, TournamentSubmission>> {
+    let msgs: Vec<TournamentSubmission> = db
+        .query_obj(
+            FirestoreQueryParams::new("tournament".into())
+                .with_filter(FirestoreQueryFilter::Composite(
+                    FirestoreQueryFilterComposite::new(
+                        vec![FirestoreQueryFilter::Compare(Some(
+                            FirestoreQueryFilterCompare::Equal(
+                                "scenario_name".into(),
+                                scenario_name.into(),
+                            ),
+                        ))],
+                        FirestoreQueryFilterCompositeOperator::And,
+                    ),
+                ))
+                .with_order_by(vec![FirestoreQueryOrder::new(
+                    "timestamp".to_owned(),
+                    FirestoreQueryDirection::Ascending,
+                )]),
+        )
+        .await?;
+
+    let mut map: BTreeMap<String, TournamentSubmission> = BTreeMap::new();
+    for msg in msgs {
+        map.insert(msg.userid.clone(), msg);
+    }
+    Ok(map)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entrant(userid: &str, rating: f64) -> LadderEntrant {
+        let default = Glicko2Rating::default();
+        LadderEntrant {
+            scenario_name: "fighter_duel".to_string(),
+            userid: userid.to_string(),
+            username: userid.to_string(),
+            submission_timestamp: Utc::now(),
+            rating,
+            deviation: default.deviation,
+            volatility: default.volatility,
+            history: vec![],
+        }
+    }
+
+    #[test]
+    fn test_schedule_seeds_reproducible() {
+        let entrants = vec![entrant("a", 1500.0), entrant("b", 1500.0)];
+        let seeds = |matches: Vec<Match>| matches.iter().map(|m| m.seed).collect::<Vec<_>>();
+        let first = seeds(schedule(&entrants, &[0], 1, 3));
+        assert_eq!(first, seeds(schedule(&entrants, &[0], 1, 3)));
+        assert_eq!(first.len(), 6);
+        // Both sides of a round share a seed, different rounds don't.
+        assert_eq!(first[0], first[1]);
+        assert_ne!(first[0], first[2]);
+    }
+
+    #[test]
+    fn test_apply_outcomes() {
+        let mut entrants = vec![entrant("a", 1500.0), entrant("b", 1500.0)];
+        let matches = schedule(&entrants, &[0], 1, 2);
+        let outcomes = vec![Outcomes::WIN, Outcomes::WIN, Outcomes::WIN, Outcomes::DRAW];
+        let records = apply_outcomes(&mut entrants, &matches, &outcomes);
+
+        assert!(entrants[0].rating > 1500.0);
+        assert!(entrants[1].rating < 1500.0);
+        assert!(entrants[0].deviation < Glicko2Rating::default().deviation);
+        assert_eq!(
+            (records[&0].wins, records[&0].losses, records[&0].draws),
+            (3, 0, 1)
+        );
+        assert_eq!(
+            (records[&1].wins, records[&1].losses, records[&1].draws),
+            (0, 3, 1)
+        );
+    }
+
+    #[test]
+    fn test_apply_outcomes_deterministic() {
+        let initial = vec![
+            entrant("a", 1500.0),
+            entrant("b", 1600.0),
+            entrant("c", 1400.0),
+        ];
+        let matches = schedule(&initial, &[0, 2], 2, 1);
+        let outcomes: Vec<Outcomes> = (0..matches.len())
+            .map(|i| [Outcomes::WIN, Outcomes::LOSS, Outcomes::DRAW][i % 3])
+            .collect();
+        let mut x = initial.clone();
+        let mut y = initial.clone();
+        apply_outcomes(&mut x, &matches, &outcomes);
+        apply_outcomes(&mut y, &matches, &outcomes);
+        assert_eq!(x, y);
+    }
+}
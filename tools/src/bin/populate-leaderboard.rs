@@ -110,6 +110,8 @@ async fn run(
                 code: code.clone(),
                 code_size: *code_size,
                 time: time.unwrap(),
+                cpu_gas: None,
+                verification: None,
             });
         }
     }
@@ -6,7 +6,7 @@ use oort_api::EcmMode;
 use oort_simulator::ship;
 use oort_simulator::ship::ShipClass::*;
 use oort_simulator::ship::{ShipClass, ShipData};
-use oort_simulator::simulation::{Code, Simulation, MAX_WORLD_SIZE};
+use oort_simulator::simulation::{Code, Simulation};
 use std::f64::consts::{PI, TAU};
 
 #[derive(Parser, Debug)]
@@ -38,7 +38,7 @@ fn run_simulation(
     f64, /* distance error RMS */
 ) {
     let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
-    let offset = -MAX_WORLD_SIZE / 2.0 + 100.0;
+    let offset = -sim.world_size() / 2.0 + 100.0;
     let ship0 = ship::create(
         &mut sim,
         vector![offset, 0.0],
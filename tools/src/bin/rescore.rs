@@ -5,6 +5,7 @@ use firestore::*;
 use gcloud_sdk::google::firestore::v1::Document;
 use indicatif::{MultiProgress, ProgressBar};
 use oort_proto::LeaderboardSubmission;
+use oort_simulator::pool::SimulationPool;
 use oort_simulator::{scenario, simulation};
 use oort_tools::ParallelCompiler;
 use rayon::prelude::*;
@@ -200,12 +201,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 }
 
 fn run_simulations(scenario_name: &str, wasm: Vec<u8>, progress: &ProgressBar) -> Option<f64> {
+    let mut codes = scenario::load(scenario_name).initial_code();
+    codes[0] = simulation::Code::Wasm(wasm);
+    let mut pool = SimulationPool::new(scenario_name);
+    for code in &codes {
+        pool.add(code);
+    }
     let results: Vec<Option<f64>> = (0..10u32)
         .into_par_iter()
         .map(|seed| {
-            let ret = run_simulation(scenario_name, seed, wasm.clone());
+            let result = pool.run(seed, &codes);
             progress.inc(1);
-            ret
+            match result.status {
+                scenario::Status::Victory { team: 0 } => Some(result.score_time),
+                _ => None,
+            }
         })
         .collect();
     log::info!("Results: {:?}", results);
@@ -215,20 +225,6 @@ fn run_simulations(scenario_name: &str, wasm: Vec<u8>, progress: &ProgressBar) -
     Some(results.iter().map(|x| x.unwrap()).sum::<f64>() / results.len() as f64)
 }
 
-fn run_simulation(scenario_name: &str, seed: u32, wasm: Vec<u8>) -> Option<f64> {
-    let scenario = scenario::load(scenario_name);
-    let mut codes = scenario.initial_code();
-    codes[0] = simulation::Code::Wasm(wasm);
-    let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
-    while sim.status() == scenario::Status::Running && sim.tick() < scenario::MAX_TICKS {
-        sim.step();
-    }
-    match sim.status() {
-        scenario::Status::Victory { team: 0 } => Some(sim.score_time()),
-        _ => None,
-    }
-}
-
 fn extract_docid(docname: &str) -> String {
     let (_, docid) = docname.rsplit_once('/').unwrap();
     docid.to_string()
@@ -4,8 +4,12 @@ use comfy_table::presets::UTF8_FULL;
 use comfy_table::Table;
 use firestore::*;
 use itertools::Itertools;
-use oort_proto::{ShortcodeUpload, TournamentCompetitor, TournamentResults, TournamentSubmission};
-use oort_simulator::{scenario, simulation};
+use oort_proto::{
+    ShortcodeUpload, TournamentCompetitor, TournamentFormat, TournamentMatch, TournamentResults,
+    TournamentRound, TournamentSubmission,
+};
+use oort_simulator::pool::SimulationPool;
+use oort_simulator::scenario;
 use oort_tools::AI;
 use rand::Rng;
 use rayon::prelude::*;
@@ -14,7 +18,10 @@ use skillratings::{
     Outcomes,
 };
 use std::default::Default;
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
 #[derive(Parser, Debug)]
 #[clap()]
@@ -37,6 +44,13 @@ enum SubCommand {
 
         #[clap(short, long)]
         dry_run: bool,
+
+        #[clap(short, long, value_enum, default_value_t = Format::RoundRobin)]
+        format: Format,
+
+        /// Number of Swiss rounds, defaults to log2 of the number of entrants.
+        #[clap(long)]
+        swiss_rounds: Option<usize>,
     },
     RunUnofficial {
         scenario: String,
@@ -50,6 +64,13 @@ enum SubCommand {
 
         #[clap(long, default_value = "/tmp/oort-wasm-cache")]
         wasm_cache: Option<PathBuf>,
+
+        #[clap(short, long, value_enum, default_value_t = Format::RoundRobin)]
+        format: Format,
+
+        /// Number of Swiss rounds, defaults to log2 of the number of entrants.
+        #[clap(long)]
+        swiss_rounds: Option<usize>,
     },
     Fetch {
         scenario: String,
@@ -62,6 +83,14 @@ enum SubCommand {
     },
 }
 
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+enum Format {
+    RoundRobin,
+    Swiss,
+    SingleElimination,
+    DoubleElimination,
+}
+
 #[derive(Debug, Clone)]
 struct Entrant {
     username: String,
@@ -80,14 +109,40 @@ async fn main() -> anyhow::Result<()> {
             usernames,
             rounds,
             dry_run,
-        } => cmd_run(&args.project_id, &scenario, &usernames, rounds, dry_run).await,
+            format,
+            swiss_rounds,
+        } => {
+            cmd_run(
+                &args.project_id,
+                &scenario,
+                &usernames,
+                rounds,
+                dry_run,
+                format,
+                swiss_rounds,
+            )
+            .await
+        }
         SubCommand::RunUnofficial {
             scenario,
             shortcodes,
             rounds,
             dev,
             wasm_cache,
-        } => cmd_run_unofficial(&scenario, &shortcodes, rounds, dev, wasm_cache).await,
+            format,
+            swiss_rounds,
+        } => {
+            cmd_run_unofficial(
+                &scenario,
+                &shortcodes,
+                rounds,
+                dev,
+                wasm_cache,
+                format,
+                swiss_rounds,
+            )
+            .await
+        }
         SubCommand::Fetch { scenario, out_dir } => {
             cmd_fetch(&args.project_id, &scenario, &out_dir).await
         }
@@ -105,6 +160,8 @@ async fn cmd_run(
     usernames: &[String],
     rounds: i32,
     dry_run: bool,
+    format: Format,
+    swiss_rounds: Option<usize>,
 ) -> anyhow::Result<()> {
     let db = FirestoreDb::new(project_id).await?;
     scenario::load_safe(scenario_name).expect("Unknown scenario");
@@ -127,7 +184,12 @@ async fn cmd_run(
     let ais: Vec<AI> = results.into_iter().collect::<anyhow::Result<Vec<AI>>>()?;
 
     log::info!("Running tournament");
-    let results = run_tournament(scenario_name, &ais, rounds);
+    let results = match format {
+        Format::RoundRobin => run_tournament(scenario_name, &ais, rounds),
+        Format::Swiss => run_swiss(scenario_name, &ais, rounds, swiss_rounds),
+        Format::SingleElimination => run_single_elimination(scenario_name, &ais, rounds),
+        Format::DoubleElimination => run_double_elimination(scenario_name, &ais, rounds),
+    };
 
     display_results(&results);
 
@@ -144,6 +206,8 @@ async fn cmd_run_unofficial(
     rounds: i32,
     dev: bool,
     wasm_cache: Option<PathBuf>,
+    format: Format,
+    swiss_rounds: Option<usize>,
 ) -> anyhow::Result<()> {
     scenario::load_safe(scenario_name).expect("Unknown scenario");
 
@@ -152,7 +216,12 @@ async fn cmd_run_unofficial(
         .await?;
 
     log::info!("Running tournament");
-    let results = run_tournament(scenario_name, &ais, rounds);
+    let results = match format {
+        Format::RoundRobin => run_tournament(scenario_name, &ais, rounds),
+        Format::Swiss => run_swiss(scenario_name, &ais, rounds, swiss_rounds),
+        Format::SingleElimination => run_single_elimination(scenario_name, &ais, rounds),
+        Format::DoubleElimination => run_double_elimination(scenario_name, &ais, rounds),
+    };
 
     display_results(&results);
 
@@ -168,6 +237,7 @@ fn run_tournament(scenario_name: &str, ais: &[AI], rounds: i32) -> TournamentRes
     let pairs: Vec<(i32, Vec<_>)> = (0..rounds)
         .flat_map(|round| (0..(ais.len())).permutations(2).map(move |x| (round, x)))
         .collect();
+    let pool = new_pool(scenario_name, ais);
     let progress = indicatif::ProgressBar::new(pairs.len() as u64);
     progress.set_style(
         indicatif::ProgressStyle::default_bar()
@@ -180,7 +250,7 @@ fn run_tournament(scenario_name: &str, ais: &[AI], rounds: i32) -> TournamentRes
             let seed = seeds[*round as usize];
             let ai0: &AI = &ais[indices[0]];
             let ai1: &AI = &ais[indices[1]];
-            let r = run_simulation(scenario_name, seed, &[ai0, ai1]);
+            let r = run_simulation(&pool, seed, &[ai0, ai1]);
             progress.inc(1);
             (*round, indices.clone(), r)
         })
@@ -220,6 +290,7 @@ fn run_tournament(scenario_name: &str, ais: &[AI], rounds: i32) -> TournamentRes
             username: x.name.clone(),
             shortcode: "".to_string(),
             rating: ratings[i].rating,
+            points: None,
         })
         .collect();
     competitors.sort_by_key(|c| (-c.rating * 1e6) as i64);
@@ -243,24 +314,455 @@ fn run_tournament(scenario_name: &str, ais: &[AI], rounds: i32) -> TournamentRes
         scenario_name: scenario_name.to_string(),
         competitors,
         win_matrix,
+        format: TournamentFormat::RoundRobin,
+        rounds: vec![],
+    }
+}
+
+/// Tracks ratings and head-to-head results for tournaments played as a
+/// sequence of matches between pairs of AIs.
+struct Bracket<'a> {
+    scenario_name: &'a str,
+    ais: &'a [AI],
+    pool: SimulationPool,
+    games: i32,
+    config: Glicko2Config,
+    ratings: Vec<Glicko2Rating>,
+    wins: HashMap<(usize, usize), u32>,
+    played: HashMap<(usize, usize), u32>,
+    rounds: Vec<TournamentRound>,
+}
+
+// Extra pairs of games played to decide a tied elimination match.
+const MAX_TIEBREAK_GAMES: usize = 10;
+
+impl<'a> Bracket<'a> {
+    fn new(scenario_name: &'a str, ais: &'a [AI], games: i32) -> Self {
+        let mut ratings: Vec<Glicko2Rating> = Vec::new();
+        ratings.resize_with(ais.len(), Default::default);
+        Self {
+            scenario_name,
+            ais,
+            pool: new_pool(scenario_name, ais),
+            games,
+            config: Glicko2Config::new(),
+            ratings,
+            wins: HashMap::new(),
+            played: HashMap::new(),
+            rounds: vec![],
+        }
+    }
+
+    fn record(&mut self, i0: usize, i1: usize, outcome: Outcomes) {
+        let (r0, r1) = glicko2(&self.ratings[i0], &self.ratings[i1], &outcome, &self.config);
+        self.ratings[i0] = r0;
+        self.ratings[i1] = r1;
+        *self.played.entry((i0, i1)).or_default() += 1;
+        *self.played.entry((i1, i0)).or_default() += 1;
+        if outcome == Outcomes::WIN {
+            *self.wins.entry((i0, i1)).or_default() += 1;
+        } else if outcome == Outcomes::LOSS {
+            *self.wins.entry((i1, i0)).or_default() += 1;
+        }
+    }
+
+    /// Plays one game on each side of the given seed, returning outcomes for i0.
+    fn play_seed(&self, i0: usize, i1: usize, seed: u32) -> [Outcomes; 2] {
+        let (ai0, ai1) = (&self.ais[i0], &self.ais[i1]);
+        [
+            run_simulation(&self.pool, seed, &[ai0, ai1]),
+            invert(run_simulation(&self.pool, seed, &[ai1, ai0])),
+        ]
+    }
+
+    /// Plays a round of matches in parallel and returns the winner of each,
+    /// or None for a draw. A pair with no opponent is a bye for the first AI.
+    fn play_round(
+        &mut self,
+        name: String,
+        pairs: &[(usize, Option<usize>)],
+        allow_draws: bool,
+    ) -> Vec<Option<usize>> {
+        log::info!("Playing {}", name);
+        let jobs: Vec<(usize, u32)> = pairs
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, i1))| i1.is_some())
+            .flat_map(|(k, _)| (0..self.games).map(move |_| (k, rand::thread_rng().gen())))
+            .collect();
+        let outcomes: Vec<(usize, [Outcomes; 2])> = jobs
+            .par_iter()
+            .map(|&(k, seed)| {
+                let (i0, i1) = pairs[k];
+                (k, self.play_seed(i0, i1.unwrap(), seed))
+            })
+            .collect();
+
+        let mut tallies: Vec<[u32; 3]> = vec![[0; 3]; pairs.len()];
+        for (k, results) in outcomes {
+            let (i0, i1) = pairs[k];
+            for outcome in results {
+                self.record(i0, i1.unwrap(), outcome);
+                tally(&mut tallies[k], outcome);
+            }
+        }
+
+        let mut winners = vec![];
+        let mut matches = vec![];
+        for (k, &(i0, i1)) in pairs.iter().enumerate() {
+            let Some(i1) = i1 else {
+                winners.push(Some(i0));
+                matches.push(TournamentMatch {
+                    username0: self.ais[i0].name.clone(),
+                    username1: None,
+                    wins0: 0,
+                    wins1: 0,
+                    draws: 0,
+                    winner: Some(self.ais[i0].name.clone()),
+                });
+                continue;
+            };
+
+            if !allow_draws {
+                for _ in 0..MAX_TIEBREAK_GAMES {
+                    if tallies[k][0] != tallies[k][1] {
+                        break;
+                    }
+                    for outcome in self.play_seed(i0, i1, rand::thread_rng().gen()) {
+                        self.record(i0, i1, outcome);
+                        tally(&mut tallies[k], outcome);
+                    }
+                }
+            }
+
+            let [wins0, wins1, draws] = tallies[k];
+            let winner = if wins0 > wins1 {
+                Some(i0)
+            } else if wins1 > wins0 {
+                Some(i1)
+            } else if allow_draws {
+                None
+            } else {
+                // Still tied, the higher seed (lower index) advances.
+                Some(i0.min(i1))
+            };
+            winners.push(winner);
+            matches.push(TournamentMatch {
+                username0: self.ais[i0].name.clone(),
+                username1: Some(self.ais[i1].name.clone()),
+                wins0,
+                wins1,
+                draws,
+                winner: winner.map(|i| self.ais[i].name.clone()),
+            });
+        }
+
+        self.rounds.push(TournamentRound { name, matches });
+        winners
+    }
+
+    fn results(
+        self,
+        format: TournamentFormat,
+        standings: &[usize],
+        points: Option<&[f64]>,
+    ) -> TournamentResults {
+        let competitors = standings
+            .iter()
+            .map(|&i| TournamentCompetitor {
+                username: self.ais[i].name.clone(),
+                shortcode: "".to_string(),
+                rating: self.ratings[i].rating,
+                points: points.map(|x| x[i]),
+            })
+            .collect();
+
+        let mut win_matrix: Vec<f64> = vec![];
+        for &i0 in standings {
+            for &i1 in standings {
+                let played = self.played.get(&(i0, i1)).copied().unwrap_or_default();
+                let wins = self.wins.get(&(i0, i1)).copied().unwrap_or_default();
+                win_matrix.push(if played > 0 {
+                    wins as f64 / played as f64
+                } else {
+                    0.0
+                });
+            }
+        }
+
+        TournamentResults {
+            scenario_name: self.scenario_name.to_string(),
+            competitors,
+            win_matrix,
+            format,
+            rounds: self.rounds,
+        }
+    }
+}
+
+fn invert(outcome: Outcomes) -> Outcomes {
+    match outcome {
+        Outcomes::WIN => Outcomes::LOSS,
+        Outcomes::LOSS => Outcomes::WIN,
+        Outcomes::DRAW => Outcomes::DRAW,
+    }
+}
+
+fn tally(tally: &mut [u32; 3], outcome: Outcomes) {
+    match outcome {
+        Outcomes::WIN => tally[0] += 1,
+        Outcomes::LOSS => tally[1] += 1,
+        Outcomes::DRAW => tally[2] += 1,
+    }
+}
+
+fn run_swiss(
+    scenario_name: &str,
+    ais: &[AI],
+    games: i32,
+    num_rounds: Option<usize>,
+) -> TournamentResults {
+    let n = ais.len();
+    let num_rounds = num_rounds.unwrap_or_else(|| (n.max(2) as f64).log2().ceil() as usize);
+    let mut bracket = Bracket::new(scenario_name, ais, games);
+    let mut points = vec![0.0; n];
+    let mut had_bye = vec![false; n];
+    let mut met: HashSet<(usize, usize)> = HashSet::new();
+
+    for round in 1..=num_rounds {
+        let pairs = swiss_pairs(&points, &mut had_bye, &mut met);
+        let winners = bracket.play_round(format!("Round {round}"), &pairs, true);
+        for (&(i0, i1), winner) in pairs.iter().zip(winners) {
+            match (winner, i1) {
+                (Some(winner), _) => points[winner] += 1.0,
+                (None, Some(i1)) => {
+                    points[i0] += 0.5;
+                    points[i1] += 0.5;
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+
+    let mut standings: Vec<usize> = (0..n).collect();
+    standings.sort_by_key(|&i| {
+        (
+            -(points[i] * 2.0) as i64,
+            (-bracket.ratings[i].rating * 1e6) as i64,
+        )
+    });
+    bracket.results(TournamentFormat::Swiss, &standings, Some(&points))
+}
+
+/// Pairs entrants for a Swiss round, recording byes and pairings so they
+/// aren't repeated in later rounds.
+fn swiss_pairs(
+    points: &[f64],
+    had_bye: &mut [bool],
+    met: &mut HashSet<(usize, usize)>,
+) -> Vec<(usize, Option<usize>)> {
+    let n = points.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by_key(|&i| (-(points[i] * 2.0) as i64, i));
+
+    // The lowest-ranked entrant that hasn't had a bye sits out.
+    let bye = if n % 2 == 1 {
+        let index = order
+            .iter()
+            .rposition(|&i| !had_bye[i])
+            .unwrap_or(order.len() - 1);
+        Some(order.remove(index))
+    } else {
+        None
+    };
+
+    // Pair each entrant with the highest-ranked opponent it hasn't met, or
+    // with the next entrant if every pairing repeats a match.
+    let pairs = pair_unmet(&order, met)
+        .unwrap_or_else(|| order.chunks(2).map(|chunk| (chunk[0], chunk[1])).collect());
+    let mut pairs: Vec<(usize, Option<usize>)> = pairs
+        .into_iter()
+        .map(|(i0, i1)| {
+            met.insert((i0.min(i1), i0.max(i1)));
+            (i0, Some(i1))
+        })
+        .collect();
+    if let Some(i) = bye {
+        had_bye[i] = true;
+        pairs.push((i, None));
+    }
+    pairs
+}
+
+/// Pairs the entrants in order without repeating a match, backtracking when a
+/// greedy choice leaves the rest unpairable.
+fn pair_unmet(order: &[usize], met: &HashSet<(usize, usize)>) -> Option<Vec<(usize, usize)>> {
+    let Some((&i0, rest)) = order.split_first() else {
+        return Some(vec![]);
+    };
+    for (k, &i1) in rest.iter().enumerate() {
+        if met.contains(&(i0.min(i1), i0.max(i1))) {
+            continue;
+        }
+        let mut remaining = rest.to_vec();
+        remaining.remove(k);
+        if let Some(mut pairs) = pair_unmet(&remaining, met) {
+            pairs.insert(0, (i0, i1));
+            return Some(pairs);
+        }
+    }
+    None
+}
+
+/// Returns seed numbers in bracket order so that the top seeds meet last.
+fn bracket_order(size: usize) -> Vec<usize> {
+    let mut order = vec![0];
+    while order.len() < size {
+        let n = order.len() * 2;
+        order = order.iter().flat_map(|&x| [x, n - 1 - x]).collect();
+    }
+    order
+}
+
+/// Pairs adjacent slots, giving a bye to an entrant without an opponent.
+fn pair_slots(slots: &[Option<usize>]) -> Vec<(usize, Option<usize>)> {
+    slots
+        .chunks(2)
+        .filter_map(|chunk| match chunk {
+            [Some(i0), Some(i1)] => Some((*i0, Some(*i1))),
+            [Some(i), None] | [None, Some(i)] | [Some(i)] => Some((*i, None)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn loser(pair: (usize, Option<usize>), winner: usize) -> Option<usize> {
+    match pair {
+        (i0, Some(i1)) if i0 == winner => Some(i1),
+        (i0, Some(_)) => Some(i0),
+        (_, None) => None,
+    }
+}
+
+fn run_single_elimination(scenario_name: &str, ais: &[AI], games: i32) -> TournamentResults {
+    let n = ais.len();
+    let mut bracket = Bracket::new(scenario_name, ais, games);
+    let mut slots: Vec<Option<usize>> = bracket_order(n.next_power_of_two())
+        .into_iter()
+        .map(|seed| (seed < n).then_some(seed))
+        .collect();
+    let mut eliminated: Vec<usize> = vec![];
+
+    let mut round = 1;
+    while slots.len() > 1 {
+        let name = match slots.len() {
+            2 => "Final".to_string(),
+            4 => "Semifinals".to_string(),
+            _ => format!("Round {round}"),
+        };
+        let pairs = pair_slots(&slots);
+        let winners = bracket.play_round(name, &pairs, false);
+        slots = vec![];
+        for (&pair, winner) in pairs.iter().zip(winners) {
+            let winner = winner.unwrap();
+            slots.push(Some(winner));
+            eliminated.extend(loser(pair, winner));
+        }
+        round += 1;
     }
+
+    let mut standings: Vec<usize> = slots.into_iter().flatten().collect();
+    standings.extend(eliminated.into_iter().rev());
+    bracket.results(TournamentFormat::SingleElimination, &standings, None)
 }
 
-fn run_simulation(scenario_name: &str, seed: u32, ais: &[&AI]) -> Outcomes {
+fn run_double_elimination(scenario_name: &str, ais: &[AI], games: i32) -> TournamentResults {
+    let n = ais.len();
+    let mut bracket = Bracket::new(scenario_name, ais, games);
+    let mut winners: Vec<Option<usize>> = bracket_order(n.next_power_of_two())
+        .into_iter()
+        .map(|seed| (seed < n).then_some(seed))
+        .collect();
+    let mut losers: Vec<usize> = vec![];
+    let mut eliminated: Vec<usize> = vec![];
+
+    let (mut winners_round, mut losers_round) = (1, 1);
+    while winners.len() > 1 || losers.len() > 1 {
+        let mut dropped = vec![];
+        if winners.len() > 1 {
+            let pairs = pair_slots(&winners);
+            let results =
+                bracket.play_round(format!("Winners Round {winners_round}"), &pairs, false);
+            winners = vec![];
+            for (&pair, winner) in pairs.iter().zip(results) {
+                let winner = winner.unwrap();
+                winners.push(Some(winner));
+                dropped.extend(loser(pair, winner));
+            }
+            winners_round += 1;
+        }
+
+        // Entrants dropping out of the winners bracket face losers bracket survivors.
+        let mut pool: Vec<Option<usize>> = vec![];
+        for k in 0..losers.len().max(dropped.len()) {
+            pool.extend(losers.get(k).map(|&i| Some(i)));
+            pool.extend(dropped.get(k).map(|&i| Some(i)));
+        }
+        losers = pool.iter().flatten().copied().collect();
+        if pool.len() > 1 {
+            let pairs = pair_slots(&pool);
+            let results = bracket.play_round(format!("Losers Round {losers_round}"), &pairs, false);
+            losers = vec![];
+            for (&pair, winner) in pairs.iter().zip(results) {
+                let winner = winner.unwrap();
+                losers.push(winner);
+                eliminated.extend(loser(pair, winner));
+            }
+            losers_round += 1;
+        }
+    }
+
+    let mut standings = vec![];
+    match (winners.first().copied().flatten(), losers.first().copied()) {
+        (Some(champion), Some(challenger)) => {
+            let pair = (champion, Some(challenger));
+            let mut winner =
+                bracket.play_round("Grand Final".to_string(), &[pair], false)[0].unwrap();
+            if winner == challenger {
+                // The winners bracket champion hasn't lost yet, so play again.
+                winner =
+                    bracket.play_round("Grand Final Reset".to_string(), &[pair], false)[0].unwrap();
+            }
+            standings.push(winner);
+            standings.extend(loser(pair, winner));
+        }
+        (Some(champion), None) => standings.push(champion),
+        _ => {}
+    }
+    standings.extend(eliminated.into_iter().rev());
+    bracket.results(TournamentFormat::DoubleElimination, &standings, None)
+}
+
+/// Creates a pool with each AI's code compiled once.
+fn new_pool(scenario_name: &str, ais: &[AI]) -> SimulationPool {
+    let mut pool = SimulationPool::new(scenario_name);
+    for ai in ais {
+        pool.add(&ai.compiled_code);
+    }
+    pool
+}
+
+fn run_simulation(pool: &SimulationPool, seed: u32, ais: &[&AI]) -> Outcomes {
     let f = move || {
         let codes: Vec<_> = ais.iter().map(|x| x.compiled_code.clone()).collect();
-        let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
-        while sim.status() == scenario::Status::Running && sim.tick() < scenario::MAX_TICKS {
-            sim.step();
-        }
-        match sim.status() {
+        match pool.run(seed, &codes).status {
             scenario::Status::Victory { team: 0 } => Outcomes::WIN,
             scenario::Status::Victory { team: 1 } => Outcomes::LOSS,
             scenario::Status::Draw => Outcomes::DRAW,
             _ => unreachable!(),
         }
     };
-    match ::std::panic::catch_unwind(f) {
+    match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(f)) {
         Ok(x) => x,
         Err(e) => {
             log::error!("Simulation panicked: {:?}", e);
@@ -270,19 +772,43 @@ fn run_simulation(scenario_name: &str, seed: u32, ais: &[&AI]) -> Outcomes {
 }
 
 fn display_results(results: &TournamentResults) {
+    for round in &results.rounds {
+        println!("{}:", round.name);
+        for m in &round.matches {
+            match &m.username1 {
+                Some(username1) => println!(
+                    "  {} {}-{}-{} {}",
+                    m.username0, m.wins0, m.draws, m.wins1, username1
+                ),
+                None => println!("  {} bye", m.username0),
+            }
+        }
+    }
+    if !results.rounds.is_empty() {
+        println!();
+    }
+
     let mut table = Table::new();
     table.load_preset(UTF8_FULL);
-    table.set_header(vec!["Name", "Rating"]);
+    table.set_header(vec!["Name", "Rating", "Points"]);
     for competitor in &results.competitors {
         table.add_row(vec![
             competitor.username.clone(),
             format!("{:.0}", competitor.rating),
+            competitor
+                .points
+                .map(|x| format!("{x:.1}"))
+                .unwrap_or_default(),
         ]);
     }
-    println!("Scenario: {}", results.scenario_name);
+    println!("Scenario: {} ({:?})", results.scenario_name, results.format);
     println!("{table}");
     println!();
 
+    if results.format != TournamentFormat::RoundRobin {
+        return;
+    }
+
     let mut table = Table::new();
     table.load_preset(UTF8_FULL);
     let mut header: Vec<String> = results
@@ -438,3 +964,128 @@ async fn cmd_write(
         .await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SIZES: [usize; 5] = [1, 2, 3, 5, 8];
+
+    /// Asserts that every entrant appears in exactly one pair and returns the
+    /// entrants with byes.
+    fn check_pairs(n: usize, pairs: &[(usize, Option<usize>)]) -> Vec<usize> {
+        let mut seen: Vec<usize> = pairs
+            .iter()
+            .flat_map(|&(i0, i1)| std::iter::once(i0).chain(i1))
+            .collect();
+        seen.sort();
+        assert_eq!(seen, (0..n).collect::<Vec<_>>(), "pairs {pairs:?}");
+        pairs
+            .iter()
+            .filter(|(_, i1)| i1.is_none())
+            .map(|&(i, _)| i)
+            .collect()
+    }
+
+    fn first_round(n: usize) -> Vec<(usize, Option<usize>)> {
+        let slots: Vec<Option<usize>> = bracket_order(n.next_power_of_two())
+            .into_iter()
+            .map(|seed| (seed < n).then_some(seed))
+            .collect();
+        pair_slots(&slots)
+    }
+
+    #[test]
+    fn test_bracket_order() {
+        assert_eq!(bracket_order(1), vec![0]);
+        assert_eq!(bracket_order(2), vec![0, 1]);
+        assert_eq!(bracket_order(4), vec![0, 3, 1, 2]);
+        assert_eq!(bracket_order(8), vec![0, 7, 3, 4, 1, 6, 2, 5]);
+    }
+
+    #[test]
+    fn test_pair_slots() {
+        assert_eq!(first_round(1), vec![(0, None)]);
+        assert_eq!(first_round(2), vec![(0, Some(1))]);
+        assert_eq!(first_round(3), vec![(0, None), (1, Some(2))]);
+        assert_eq!(
+            first_round(5),
+            vec![(0, None), (3, Some(4)), (1, None), (2, None)]
+        );
+        assert_eq!(
+            first_round(8),
+            vec![(0, Some(7)), (3, Some(4)), (1, Some(6)), (2, Some(5))]
+        );
+
+        for n in SIZES {
+            let pairs = first_round(n);
+            let byes = check_pairs(n, &pairs);
+            // Byes go to the top seeds.
+            let expected: Vec<usize> = if n == 1 {
+                vec![0]
+            } else {
+                (0..n.next_power_of_two() - n).collect()
+            };
+            assert_eq!(byes, expected, "n={n}");
+        }
+    }
+
+    #[test]
+    fn test_swiss_pairs() {
+        for n in SIZES {
+            let num_rounds = (n.max(2) as f64).log2().ceil() as usize;
+            let mut points = vec![0.0; n];
+            let mut had_bye = vec![false; n];
+            let mut met: HashSet<(usize, usize)> = HashSet::new();
+            let mut all_byes = vec![];
+            let mut all_pairs = vec![];
+            for _ in 0..num_rounds {
+                let pairs = swiss_pairs(&points, &mut had_bye, &mut met);
+                let byes = check_pairs(n, &pairs);
+                assert_eq!(byes.len(), n % 2, "n={n} pairs={pairs:?}");
+                all_byes.extend(byes);
+                for &(i0, i1) in pairs.iter() {
+                    match i1 {
+                        // The higher seed wins.
+                        Some(i1) => {
+                            points[i0.min(i1)] += 1.0;
+                            all_pairs.push((i0.min(i1), i0.max(i1)));
+                        }
+                        None => points[i0] += 1.0,
+                    }
+                }
+            }
+
+            let mut unique_byes = all_byes.clone();
+            unique_byes.sort();
+            unique_byes.dedup();
+            assert_eq!(unique_byes.len(), all_byes.len(), "repeated bye n={n}");
+
+            let mut unique_pairs = all_pairs.clone();
+            unique_pairs.sort();
+            unique_pairs.dedup();
+            assert_eq!(unique_pairs.len(), all_pairs.len(), "repeated pair n={n}");
+        }
+    }
+
+    #[test]
+    fn test_swiss_pairs_backtracks() {
+        // Greedily pairing 0 with 2 would leave 1 and 3, who already met.
+        let points = vec![1.0; 4];
+        let mut had_bye = vec![false; 4];
+        let mut met = HashSet::from([(0, 1), (1, 3)]);
+        let pairs = swiss_pairs(&points, &mut had_bye, &mut met);
+        assert_eq!(pairs, vec![(0, Some(3)), (1, Some(2))]);
+    }
+
+    #[test]
+    fn test_swiss_bye_goes_to_lowest_ranked() {
+        let points = vec![2.0, 1.0, 0.0, 1.5, 0.5];
+        let mut had_bye = vec![false, false, true, false, false];
+        let mut met = HashSet::new();
+        let pairs = swiss_pairs(&points, &mut had_bye, &mut met);
+        // Entrant 2 is last but already had a bye, so entrant 4 sits out.
+        assert_eq!(pairs.last(), Some(&(4, None)));
+        assert!(had_bye[4]);
+    }
+}
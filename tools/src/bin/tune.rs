@@ -1,8 +1,9 @@
 use clap::Parser as _;
 use metaheuristics_nature::utility::prelude::*;
 use metaheuristics_nature::{Bounded, ObjFunc, Solver};
+use oort_simulator::pool::SimulationPool;
+use oort_simulator::scenario;
 use oort_simulator::simulation::Code;
-use oort_simulator::{scenario, simulation};
 use std::cell::RefCell;
 use std::default::Default;
 use std::path::PathBuf;
@@ -217,25 +218,18 @@ fn compile(name: String, code: String) -> Option<Vec<u8>> {
 
 fn run_simulations(scenario_name: &str, codes: Vec<Code>, num_seeds: u32) -> f64 {
     let reverse_codes = codes.iter().rev().cloned().collect::<Vec<_>>();
-    (0..num_seeds)
-        .into_par_iter()
-        .flat_map(|seed| [(seed, false), (seed, true)])
-        .map(|(seed, reverse)| {
-            let codes = if reverse { &reverse_codes } else { &codes };
-            let player_team = if reverse { 1 } else { 0 };
-            let (status, time) = run_simulation(scenario_name, seed, codes.clone());
-            match status {
-                scenario::Status::Victory { team } if team == player_team => time,
-                _ => 1e6,
-            }
+    let seeds: Vec<u32> = (0..num_seeds).collect();
+    let mut pool = SimulationPool::new(scenario_name);
+    [(&codes, 0), (&reverse_codes, 1)]
+        .into_iter()
+        .flat_map(|(codes, player_team)| {
+            pool.run_seeds(codes, &seeds)
+                .results
+                .into_iter()
+                .map(move |result| match result.status {
+                    scenario::Status::Victory { team } if team == player_team => result.time,
+                    _ => 1e6,
+                })
         })
         .sum()
 }
-
-fn run_simulation(scenario_name: &str, seed: u32, codes: Vec<Code>) -> (scenario::Status, f64) {
-    let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
-    while sim.status() == scenario::Status::Running && sim.tick() < scenario::MAX_TICKS {
-        sim.step();
-    }
-    (sim.status(), sim.time())
-}